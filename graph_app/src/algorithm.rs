@@ -1,10 +1,28 @@
+use std::str::FromStr;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use strum::{
     Display,
     EnumIter,
+    IntoEnumIterator,
 };
 
 /// Available algorithms for processing text
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Display, EnumIter)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    Display,
+    EnumIter,
+    Serialize,
+    Deserialize,
+)]
 pub(crate) enum Algorithm {
     /// ngrams::parse_corpus - Parse corpus using n-gram frequency analysis
     #[default]
@@ -18,6 +36,10 @@ pub(crate) enum Algorithm {
     /// context-insert::insert - Insert patterns into the graph
     #[strum(serialize = "Context Insert")]
     ContextInsert,
+
+    /// context-search::find_sequence - Search texts without changing the graph
+    #[strum(serialize = "Context Search")]
+    ContextSearch,
 }
 
 impl Algorithm {
@@ -33,6 +55,47 @@ impl Algorithm {
             Algorithm::ContextInsert => {
                 "Insert patterns into the graph using context-insert module"
             }
+            Algorithm::ContextSearch => {
+                "Find the largest vertex matching the texts using context-search module, without changing the graph"
+            }
         }
     }
+
+    /// Name used for the algorithm on the command line
+    pub(crate) fn cli_name(&self) -> &'static str {
+        match self {
+            Algorithm::NgramsParseCorpus => "ngrams",
+            Algorithm::ContextRead => "read",
+            Algorithm::ContextInsert => "insert",
+            Algorithm::ContextSearch => "search",
+        }
+    }
+
+    /// Whether running the algorithm changes the graph of the tab
+    pub(crate) fn mutates_graph(&self) -> bool {
+        !matches!(self, Algorithm::ContextSearch)
+    }
+
+    /// Whether a run of the algorithm waits while its task is paused
+    pub(crate) fn is_pausable(&self) -> bool {
+        matches!(self, Algorithm::NgramsParseCorpus)
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Algorithm::iter()
+            .find(|a| a.cli_name() == s.to_ascii_lowercase())
+            .ok_or_else(|| {
+                format!(
+                    "unknown algorithm {:?}, expected one of {}",
+                    s,
+                    Algorithm::iter()
+                        .map(|a| a.cli_name())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+    }
 }
@@ -1,20 +1,34 @@
 //! Central panel with closeable graph tabs and inserter window.
 
+use std::hash::{
+    DefaultHasher,
+    Hash,
+    Hasher,
+};
+
 use eframe::egui::{
     self,
+    Color32,
     Ui,
 };
+use strum::IntoEnumIterator;
 
 use super::{
+    estimate::PendingEstimate,
     App,
     GraphTab,
 };
 use crate::{
+    algorithm::Algorithm,
+    app::history::{
+        REDO_SHORTCUT,
+        UNDO_SHORTCUT,
+    },
     examples::{
-        build_graph1,
-        build_graph2,
-        build_graph3,
+        AssetPreset,
+        BUILTIN_GRAPHS,
     },
+    graph::GraphView,
     widgets::{EditableLabel, Inserter},
 };
 
@@ -39,10 +53,17 @@ impl App {
                 // Show the graph for the selected tab and handle clicks
                 let mut clicked_node = None;
                 let mut background_clicked = false;
+                let mut error = None;
+                let (layout_kind, layering) = (self.layout_kind, self.layering);
                 if let Some(mut vis) = self.vis_mut() {
+                    vis.set_layout(layout_kind, layering);
                     let response = vis.show(ui);
                     clicked_node = response.clicked_node;
                     background_clicked = response.background_clicked;
+                    error = response.error;
+                }
+                if let Some(error) = error {
+                    self.output.error(error);
                 }
 
                 // Update selection if a node was clicked, clear if background was clicked
@@ -58,6 +79,7 @@ impl App {
 
                 // Show inserter as a floating window within the central panel
                 self.show_inserter_window(ctx, viewport_rect);
+                self.show_search_window(ctx, viewport_rect);
             })
             .response
             .context_menu(|ui| self.context_menu(ui));
@@ -138,28 +160,37 @@ impl App {
         egui::MenuBar::new().ui(ui, |ui| {
             // Edit menu
             ui.menu_button("Edit", |ui| {
+                let (can_undo, can_redo) =
+                    self.current_tab().map_or((false, false), |tab| {
+                        (tab.history.can_undo(), tab.history.can_redo())
+                    });
+                let undo = egui::Button::new("Undo")
+                    .shortcut_text(ui.ctx().format_shortcut(&UNDO_SHORTCUT));
+                if ui.add_enabled(can_undo, undo).clicked() {
+                    self.undo();
+                    ui.close();
+                }
+                let redo = egui::Button::new("Redo")
+                    .shortcut_text(ui.ctx().format_shortcut(&REDO_SHORTCUT));
+                if ui.add_enabled(can_redo, redo).clicked() {
+                    self.redo();
+                    ui.close();
+                }
+
+                ui.separator();
+
                 // Presets submenu
                 ui.menu_button("Load Preset", |ui| {
-                    if let Some(ctx) = self.ctx() {
-                        if ui.button("Graph 1").clicked() {
-                            ctx.graph().set_graph(build_graph1());
-                            if let Some(mut vis) = self.vis_mut() {
-                                vis.mark_dirty();
-                            }
-                            ui.close();
-                        }
-                        if ui.button("Graph 2").clicked() {
-                            ctx.graph().set_graph(build_graph2());
-                            if let Some(mut vis) = self.vis_mut() {
-                                vis.mark_dirty();
-                            }
+                    for (name, build) in BUILTIN_GRAPHS {
+                        if ui.button(name).clicked() {
+                            self.load_graph(name, build(), Default::default());
                             ui.close();
                         }
-                        if ui.button("Graph 3").clicked() {
-                            ctx.graph().set_graph(build_graph3());
-                            if let Some(mut vis) = self.vis_mut() {
-                                vis.mark_dirty();
-                            }
+                    }
+                    ui.separator();
+                    for preset in AssetPreset::ALL {
+                        if ui.button(preset.file_name()).clicked() {
+                            self.load_asset_preset(preset);
                             ui.close();
                         }
                     }
@@ -168,18 +199,75 @@ impl App {
                 ui.separator();
 
                 if ui.button("Clear").clicked() {
-                    if let Some(mut ctx) = self.ctx_mut() {
-                        ctx.graph_mut().clear();
-                    }
-                    if let Some(mut vis) = self.vis_mut() {
-                        vis.mark_dirty();
-                    }
+                    self.clear_graph();
                     ui.close();
                 }
             });
+
+            self.show_view_toggle(ui);
+
+            let query_result =
+                self.ctx().and_then(|ctx| ctx.graph().query_result());
+            if let Some(result) = query_result {
+                ui.separator();
+                let (color, text) = if result.complete {
+                    (
+                        Color32::from_rgb(80, 220, 120),
+                        format!(
+                            "🔎 {:?} found in {}",
+                            result.query, result.root_string
+                        ),
+                    )
+                } else {
+                    (
+                        ui.visuals().warn_fg_color,
+                        format!(
+                            "🔎 {:?} partially matched in {}",
+                            result.query, result.root_string
+                        ),
+                    )
+                };
+                ui.colored_label(color, text);
+            }
+
+            let partial = self.ctx().and_then(|ctx| ctx.graph().partial());
+            if let Some(pass) = partial {
+                ui.separator();
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!("⚠ Partial result, cancelled in {:?} pass", pass),
+                );
+            }
         });
     }
 
+    /// Switch between the partition graph and the lattice of an ngrams run
+    fn show_view_toggle(
+        &mut self,
+        ui: &mut Ui,
+    ) {
+        // the context is locked while a task runs
+        let Some(has_containment) =
+            self.ctx().map(|ctx| ctx.graph().has_containment())
+        else {
+            return;
+        };
+        let Some(mut vis) = self.vis_mut() else {
+            return;
+        };
+        if !has_containment {
+            // a run without a lattice replaced the graph
+            vis.set_view(GraphView::Partition);
+            return;
+        }
+        ui.separator();
+        let mut view = vis.view();
+        for option in GraphView::iter() {
+            ui.selectable_value(&mut view, option, option.to_string());
+        }
+        vis.set_view(view);
+    }
+
     pub(crate) fn create_new_tab(&mut self) {
         let id = self.next_tab_id;
         self.next_tab_id += 1;
@@ -252,11 +340,45 @@ impl App {
         self.inserter_open = inserter_open;
     }
 
+    /// Estimate the inserter texts in the background when they changed,
+    /// showing the previous estimate meanwhile.
+    fn update_estimate(
+        &mut self,
+        texts: &[String],
+        ctx: &egui::Context,
+    ) {
+        let finished = self
+            .pending_estimate
+            .as_ref()
+            .and_then(|pending| Some((pending.hash(), pending.take()?)));
+        if finished.is_some() {
+            self.estimate = finished;
+            self.pending_estimate = None;
+        }
+
+        let mut hasher = DefaultHasher::new();
+        texts.hash(&mut hasher);
+        let hash = hasher.finish();
+        let latest = self
+            .pending_estimate
+            .as_ref()
+            .map(PendingEstimate::hash)
+            .or_else(|| self.estimate.as_ref().map(|(h, _)| *h));
+        if latest != Some(hash) {
+            let texts =
+                texts.iter().filter(|t| !t.is_empty()).cloned().collect();
+            // replacing a pending estimate cancels it
+            self.pending_estimate =
+                Some(PendingEstimate::spawn(hash, texts, ctx.clone()));
+        }
+    }
+
     fn show_inserter_content(
         &mut self,
         ui: &mut Ui,
     ) {
         let is_running = self.is_task_running();
+        let is_paused = self.is_task_paused();
 
         // Get texts from the read context
         let mut texts = if let Some(mut read_ctx) = self.ctx_mut() {
@@ -265,11 +387,21 @@ impl App {
             return;
         };
 
+        let estimate =
+            if self.selected_algorithm == Algorithm::NgramsParseCorpus {
+                self.update_estimate(&texts, ui.ctx());
+                self.estimate.as_ref().map(|(_, e)| e)
+            } else {
+                None
+            };
         let response = Inserter::new(
             &mut self.selected_algorithm,
             &mut texts,
             is_running,
+            is_paused,
         )
+        .estimate(estimate)
+        .pausable(self.current_task_pausable)
         .show(ui);
 
         // Put texts back
@@ -286,13 +418,12 @@ impl App {
             self.abort();
         }
 
+        if response.pause_clicked {
+            self.toggle_pause();
+        }
+
         if response.clear_clicked {
-            if let Some(mut ctx) = self.ctx_mut() {
-                ctx.graph_mut().clear();
-            }
-            if let Some(mut vis) = self.vis_mut() {
-                vis.mark_dirty();
-            }
+            self.clear_graph();
         }
 
         #[cfg(target_arch = "wasm32")]
@@ -0,0 +1,64 @@
+//! Estimating the inserter texts off the UI thread.
+//!
+//! The estimate hashes the n-grams of the texts, which takes too long for
+//! large corpora to run on every change. It runs as a blocking task while the
+//! inserter keeps showing the previous estimate.
+
+use std::sync::{
+    Arc,
+    Mutex,
+};
+
+use eframe::egui;
+use ngrams::graph::{
+    estimate::Estimate,
+    Corpus,
+};
+
+use crate::task::TaskHandle;
+
+/// Estimate running as a blocking task, cancelled when dropped
+#[derive(Debug)]
+pub(crate) struct PendingEstimate {
+    /// Hash of the estimated texts
+    hash: u64,
+    task: TaskHandle,
+    result: Arc<Mutex<Option<Estimate>>>,
+}
+
+impl PendingEstimate {
+    /// Start estimating `texts`, repainting `ctx` when done
+    pub(crate) fn spawn(
+        hash: u64,
+        texts: Vec<String>,
+        ctx: egui::Context,
+    ) -> Self {
+        let result = Arc::new(Mutex::new(None));
+        let slot = result.clone();
+        let task = TaskHandle::spawn_blocking(move |cancellation| {
+            let estimate = Corpus::new("estimate", texts).estimate(None);
+            if !cancellation.is_cancelled() {
+                if let Ok(mut slot) = slot.lock() {
+                    *slot = Some(estimate);
+                }
+                ctx.request_repaint();
+            }
+        });
+        Self { hash, task, result }
+    }
+
+    pub(crate) fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Take the finished estimate
+    pub(crate) fn take(&self) -> Option<Estimate> {
+        self.result.lock().ok()?.take()
+    }
+}
+
+impl Drop for PendingEstimate {
+    fn drop(&mut self) {
+        self.task.cancel();
+    }
+}
@@ -0,0 +1,267 @@
+//! Saving and opening graph tabs as project files.
+//!
+//! File dialogs run asynchronously, their results are queued as
+//! [`FileEvent`]s and applied on the next frame.
+
+use std::{
+    path::PathBuf,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use super::{
+    execute,
+    App,
+    GraphTab,
+};
+use crate::project::{
+    Project,
+    ProjectError,
+};
+
+/// Extension of project files
+pub(crate) const PROJECT_EXTENSION: &str = "json";
+
+/// Result of a file dialog
+#[derive(Debug)]
+pub(crate) enum FileEvent {
+    /// A project file was read
+    Opened {
+        file_name: String,
+        path: Option<PathBuf>,
+        text: String,
+    },
+    /// The project of a tab was written
+    Saved {
+        tab_id: usize,
+        file_name: String,
+        path: Option<PathBuf>,
+    },
+    Failed(String),
+}
+
+/// Queue of file events shared with the dialog tasks
+pub(crate) type FileEvents = Arc<Mutex<Vec<FileEvent>>>;
+
+fn push_event(
+    events: &FileEvents,
+    event: FileEvent,
+) {
+    if let Ok(mut events) = events.lock() {
+        events.push(event);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn handle_path(handle: &rfd::FileHandle) -> Option<PathBuf> {
+    Some(handle.path().to_path_buf())
+}
+
+/// Browsers do not expose the path of a file
+#[cfg(target_arch = "wasm32")]
+fn handle_path(_handle: &rfd::FileHandle) -> Option<PathBuf> {
+    None
+}
+
+fn project_dialog() -> rfd::AsyncFileDialog {
+    let dialog = rfd::AsyncFileDialog::new()
+        .add_filter("Graph project", &[PROJECT_EXTENSION]);
+    #[cfg(not(target_arch = "wasm32"))]
+    let dialog = match std::env::current_dir() {
+        Ok(current_dir) => dialog.set_directory(current_dir),
+        Err(_) => dialog,
+    };
+    dialog
+}
+
+impl GraphTab {
+    /// Build a tab with the graph and view of a project
+    pub(crate) fn from_project(
+        id: usize,
+        project: &Project,
+    ) -> Result<Self, ProjectError> {
+        let restored = project.restore()?;
+        let tab =
+            GraphTab::with_graph(id, project.name.clone(), restored.graph);
+        if let Some(mut vis) = tab.vis_mut() {
+            vis.restore(
+                restored.view,
+                restored.zoom,
+                restored.pan,
+                restored.positions,
+            );
+        }
+        Ok(tab)
+    }
+}
+
+impl App {
+    /// Capture a tab as a project
+    pub(crate) fn tab_project(
+        &self,
+        tab: &GraphTab,
+    ) -> Result<Project, ProjectError> {
+        let ctx = tab.ctx().ok_or(ProjectError::Busy)?;
+        let vis = tab.vis().ok_or(ProjectError::Busy)?;
+        Ok(Project::capture(
+            &tab.name,
+            self.selected_algorithm,
+            ctx.graph(),
+            &vis,
+        ))
+    }
+
+    /// Capture the current tab as a project
+    fn current_project(&self) -> Result<(usize, Project), ProjectError> {
+        let tab = self.current_tab().ok_or(ProjectError::Busy)?;
+        Ok((tab.id, self.tab_project(tab)?))
+    }
+
+    /// Save the current tab, asking for a file unless it was saved before
+    /// or `save_as` is set
+    pub(crate) fn save_project(
+        &mut self,
+        save_as: bool,
+    ) {
+        let (tab_id, project) = match self.current_project() {
+            Ok(project) => project,
+            Err(err) => {
+                self.output.error(format!("Failed to save project: {}", err));
+                return;
+            },
+        };
+        let json = project.to_json();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(path) = self
+            .current_tab()
+            .and_then(|tab| tab.file.clone())
+            .filter(|_| !save_as)
+        {
+            match std::fs::write(&path, json) {
+                Ok(()) => self
+                    .output
+                    .success(format!("Saved project {}", path.display())),
+                Err(err) => self.output.error(format!(
+                    "Failed to save {}: {}",
+                    path.display(),
+                    err
+                )),
+            }
+            return;
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = save_as;
+
+        let events = self.file_events.clone();
+        let dialog = project_dialog()
+            .set_file_name(format!("{}.{}", project.name, PROJECT_EXTENSION));
+        execute(async move {
+            let Some(handle) = dialog.save_file().await else {
+                return;
+            };
+            let event = match handle.write(json.as_bytes()).await {
+                Ok(()) => FileEvent::Saved {
+                    tab_id,
+                    file_name: handle.file_name(),
+                    path: handle_path(&handle),
+                },
+                Err(err) => FileEvent::Failed(format!(
+                    "Failed to save {}: {}",
+                    handle.file_name(),
+                    err
+                )),
+            };
+            push_event(&events, event);
+        });
+    }
+
+    /// Open a project file in a new tab
+    pub(crate) fn open_project(&mut self) {
+        let events = self.file_events.clone();
+        let dialog = project_dialog();
+        execute(async move {
+            let Some(handle) = dialog.pick_file().await else {
+                return;
+            };
+            let bytes = handle.read().await;
+            let event = match String::from_utf8(bytes) {
+                Ok(text) => FileEvent::Opened {
+                    file_name: handle.file_name(),
+                    path: handle_path(&handle),
+                    text,
+                },
+                Err(err) => FileEvent::Failed(format!(
+                    "Failed to open {}: {}",
+                    handle.file_name(),
+                    err
+                )),
+            };
+            push_event(&events, event);
+        });
+    }
+
+    /// Open a project in a new tab and select it
+    fn open_project_text(
+        &mut self,
+        text: &str,
+        path: Option<PathBuf>,
+    ) -> Result<(), ProjectError> {
+        let project = Project::from_json(text)?;
+        let id = self.next_tab_id;
+        let mut tab = GraphTab::from_project(id, &project)?;
+        tab.file = path;
+        self.next_tab_id += 1;
+        self.tabs.push(tab);
+        self.selected_tab_id = id;
+        self.selected_algorithm = project.algorithm;
+        Ok(())
+    }
+
+    /// Apply the results of finished file dialogs
+    pub(crate) fn poll_file_events(&mut self) {
+        let events = match self.file_events.lock() {
+            Ok(mut events) => std::mem::take(&mut *events),
+            Err(_) => return,
+        };
+        for event in events {
+            match event {
+                FileEvent::Opened {
+                    file_name,
+                    path,
+                    text,
+                } => match self.open_project_text(&text, path) {
+                    Ok(()) => self
+                        .output
+                        .success(format!("Opened project {}", file_name)),
+                    Err(err) => {
+                        self.output.error(format!(
+                            "Failed to open {}: {}",
+                            file_name, err
+                        ));
+                        self.bottom_panel_open = true;
+                    },
+                },
+                FileEvent::Saved {
+                    tab_id,
+                    file_name,
+                    path,
+                } => {
+                    if let Some(tab) =
+                        self.tabs.iter_mut().find(|t| t.id == tab_id)
+                    {
+                        tab.file = path;
+                    }
+                    self.output
+                        .success(format!("Saved project {}", file_name));
+                },
+                FileEvent::Failed(msg) => {
+                    self.output.error(msg);
+                    self.bottom_panel_open = true;
+                },
+            }
+        }
+    }
+}
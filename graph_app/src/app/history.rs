@@ -0,0 +1,279 @@
+//! Per-tab undo history of graph operations.
+//!
+//! Algorithm runs, clearing and loading presets record the state of the tab
+//! before they change it. States are captured as [`Project`]s, which store
+//! vertices by string, so they can be restored after the graph was rebuilt
+//! with new keys.
+
+use eframe::egui::{
+    Key,
+    KeyboardShortcut,
+    Modifiers,
+};
+
+use super::{
+    App,
+    GraphTab,
+};
+use crate::project::{
+    Project,
+    ProjectError,
+};
+
+pub(crate) const UNDO_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+pub(crate) const REDO_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
+/// Alternative redo shortcut common on Windows and Linux
+const REDO_SHORTCUT_ALT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND, Key::Y);
+
+/// Number of operations which can be undone per tab
+const HISTORY_LIMIT: usize = 50;
+
+/// State of a tab before or after an operation
+#[derive(Debug)]
+struct HistoryEntry {
+    operation: String,
+    state: Project,
+}
+
+/// Undo and redo stacks of a tab
+#[derive(Debug, Default)]
+pub(crate) struct History {
+    /// States before the operations, oldest first
+    undo: Vec<HistoryEntry>,
+    /// States after undone operations, next redo last
+    redo: Vec<HistoryEntry>,
+}
+
+impl History {
+    pub(crate) fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Operations which can be undone, oldest first
+    pub(crate) fn undo_operations(&self) -> impl Iterator<Item = &str> {
+        self.undo.iter().map(|entry| entry.operation.as_str())
+    }
+
+    /// Operations which can be redone, next first
+    pub(crate) fn redo_operations(&self) -> impl Iterator<Item = &str> {
+        self.redo.iter().rev().map(|entry| entry.operation.as_str())
+    }
+
+    /// Record the state before a new operation, dropping the redo stack
+    fn push(
+        &mut self,
+        operation: String,
+        state: Project,
+    ) {
+        if self.undo.len() == HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(HistoryEntry { operation, state });
+        self.redo.clear();
+    }
+
+    fn stack(
+        &mut self,
+        undo: bool,
+    ) -> &mut Vec<HistoryEntry> {
+        if undo {
+            &mut self.undo
+        } else {
+            &mut self.redo
+        }
+    }
+}
+
+impl GraphTab {
+    /// Replace the graph and the view with a recorded state
+    fn apply(
+        &mut self,
+        state: &Project,
+    ) -> Result<(), ProjectError> {
+        let restored = state.restore()?;
+        self.ctx()
+            .ok_or(ProjectError::Busy)?
+            .graph()
+            .replace(&restored.graph);
+        if let Some(mut vis) = self.vis_mut() {
+            vis.restore(
+                restored.view,
+                restored.zoom,
+                restored.pan,
+                restored.positions,
+            );
+        }
+        self.selected_node = None;
+        Ok(())
+    }
+}
+
+impl App {
+    /// Record the state of the current tab before `operation` changes it
+    pub(crate) fn record(
+        &mut self,
+        operation: impl Into<String>,
+    ) {
+        let operation = operation.into();
+        let Some(tab) = self.current_tab() else {
+            return;
+        };
+        match self.tab_project(tab) {
+            Ok(state) => {
+                if let Some(tab) = self.current_tab_mut() {
+                    tab.history.push(operation, state);
+                }
+            },
+            Err(err) => self
+                .output
+                .warn(format!("{} can not be undone: {}", operation, err)),
+        }
+    }
+
+    pub(crate) fn undo(&mut self) {
+        self.step(true);
+    }
+
+    pub(crate) fn redo(&mut self) {
+        self.step(false);
+    }
+
+    /// Undo or redo `count` operations, stopping at the first failure
+    pub(crate) fn step_by(
+        &mut self,
+        undo: bool,
+        count: usize,
+    ) {
+        for _ in 0..count {
+            if !self.step(undo) {
+                break;
+            }
+        }
+    }
+
+    /// Undo or redo the last operation of the current tab
+    fn step(
+        &mut self,
+        undo: bool,
+    ) -> bool {
+        let (verb, done) = if undo {
+            ("undo", "Undid")
+        } else {
+            ("redo", "Redid")
+        };
+        let Some(tab) = self.current_tab() else {
+            return false;
+        };
+        let possible = if undo {
+            tab.history.can_undo()
+        } else {
+            tab.history.can_redo()
+        };
+        if !possible {
+            return false;
+        }
+        let current = match self.tab_project(tab) {
+            Ok(current) => current,
+            Err(err) => {
+                self.output.warn(format!("Can not {}: {}", verb, err));
+                return false;
+            },
+        };
+        let Some(tab) = self.current_tab_mut() else {
+            return false;
+        };
+        let entry = tab.history.stack(undo).pop().unwrap();
+        let result = tab.apply(&entry.state);
+        let operation = entry.operation.clone();
+        match result {
+            Ok(()) => {
+                tab.history.stack(!undo).push(HistoryEntry {
+                    operation: entry.operation,
+                    state: current,
+                });
+                self.output.info(format!("{} {}", done, operation));
+                true
+            },
+            Err(err) => {
+                tab.history.stack(undo).push(entry);
+                self.output.error(format!(
+                    "Failed to {} {}: {}",
+                    verb, operation, err
+                ));
+                false
+            },
+        }
+    }
+
+    /// Undo and redo with the keyboard unless a text field has focus
+    pub(crate) fn handle_history_shortcuts(
+        &mut self,
+        ctx: &eframe::egui::Context,
+    ) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        // redo first, undo would also match with shift held
+        let redo = ctx.input_mut(|i| {
+            i.consume_shortcut(&REDO_SHORTCUT)
+                || i.consume_shortcut(&REDO_SHORTCUT_ALT)
+        });
+        if redo {
+            self.redo();
+        } else if ctx.input_mut(|i| i.consume_shortcut(&UNDO_SHORTCUT)) {
+            self.undo();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::build_graph1;
+
+    fn vertex_count(app: &App) -> usize {
+        app.ctx().unwrap().graph().read().vertex_count()
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut app = App::new();
+        app.load_graph("Graph 1", build_graph1(), Default::default());
+        let loaded = vertex_count(&app);
+        assert!(loaded > 0);
+        app.clear_graph();
+        assert_eq!(vertex_count(&app), 0);
+
+        app.undo();
+        assert_eq!(vertex_count(&app), loaded);
+        app.undo();
+        assert_eq!(vertex_count(&app), 0);
+        let history = &app.current_tab().unwrap().history;
+        assert!(!history.can_undo());
+        assert_eq!(
+            history.redo_operations().collect::<Vec<_>>(),
+            ["Load Graph 1", "Clear"],
+        );
+
+        app.step_by(false, 2);
+        assert_eq!(vertex_count(&app), 0);
+        app.undo();
+        assert_eq!(vertex_count(&app), loaded);
+
+        // a new operation drops the redo stack
+        app.clear_graph();
+        let history = &app.current_tab().unwrap().history;
+        assert!(!history.can_redo());
+        assert_eq!(
+            history.undo_operations().collect::<Vec<_>>(),
+            ["Load Graph 1", "Clear"],
+        );
+    }
+}
@@ -10,9 +10,12 @@ use super::App;
 use crate::{
     algorithm::Algorithm,
     examples::{
-        build_graph1,
-        build_graph2,
-        build_graph3,
+        AssetPreset,
+        BUILTIN_GRAPHS,
+    },
+    vis::layout::{
+        layered::Layering,
+        LayoutKind,
     },
 };
 
@@ -51,29 +54,23 @@ impl App {
         }
 
         ui.menu_button("Load preset...", |ui| {
-            if let Some(ctx) = self.ctx() {
-                if ui.button("Graph 1").clicked() {
-                    ctx.graph().set_graph(build_graph1());
-                    ui.close();
-                }
-                if ui.button("Graph 2").clicked() {
-                    ctx.graph().set_graph(build_graph2());
+            for (name, build) in BUILTIN_GRAPHS {
+                if ui.button(name).clicked() {
+                    self.load_graph(name, build(), Default::default());
                     ui.close();
                 }
-                if ui.button("Graph 3").clicked() {
-                    ctx.graph().set_graph(build_graph3());
+            }
+            ui.separator();
+            for preset in AssetPreset::ALL {
+                if ui.button(preset.file_name()).clicked() {
+                    self.load_asset_preset(preset);
                     ui.close();
                 }
             }
         });
 
         if ui.button("Clear").clicked() {
-            if let Some(mut ctx) = self.ctx_mut() {
-                ctx.graph_mut().clear();
-            }
-            if let Some(mut vis) = self.vis_mut() {
-                vis.mark_dirty();
-            }
+            self.clear_graph();
             ui.close();
         }
     }
@@ -117,6 +114,68 @@ impl App {
                 ui.label("Description:");
                 ui.label(self.selected_algorithm.description());
 
+                if self.selected_algorithm == Algorithm::NgramsParseCorpus {
+                    ui.add_space(10.0);
+                    ui.label("Limits:");
+                    let limits = &mut self.parse_limits;
+                    optional_limit(
+                        ui,
+                        "Timeout (s)",
+                        &mut limits.timeout_secs,
+                        60,
+                    );
+                    optional_limit(
+                        ui,
+                        "Steps per pass",
+                        &mut limits.max_steps,
+                        100_000,
+                    );
+                    optional_limit(
+                        ui,
+                        "Vocabulary entries",
+                        &mut limits.max_vocab_entries,
+                        1_000_000,
+                    );
+                }
+
+                ui.add_space(15.0);
+                ui.separator();
+                ui.add_space(5.0);
+
+                ui.heading("Graph Layout");
+                ui.add_space(10.0);
+
+                ui.horizontal(|ui| {
+                    ui.label("Layout:");
+                    egui::ComboBox::from_id_salt("layout_selector")
+                        .selected_text(self.layout_kind.to_string())
+                        .show_ui(ui, |ui| {
+                            for kind in LayoutKind::iter() {
+                                ui.selectable_value(
+                                    &mut self.layout_kind,
+                                    kind,
+                                    kind.to_string(),
+                                );
+                            }
+                        });
+                });
+                if self.layout_kind == LayoutKind::Layered {
+                    ui.horizontal(|ui| {
+                        ui.label("Layering:");
+                        egui::ComboBox::from_id_salt("layering_selector")
+                            .selected_text(self.layering.to_string())
+                            .show_ui(ui, |ui| {
+                                for layering in Layering::iter() {
+                                    ui.selectable_value(
+                                        &mut self.layering,
+                                        layering,
+                                        layering.to_string(),
+                                    );
+                                }
+                            });
+                    });
+                }
+
                 ui.add_space(15.0);
                 ui.separator();
                 ui.add_space(5.0);
@@ -135,3 +194,21 @@ impl App {
             });
     }
 }
+
+/// Edit a limit which is unset while its checkbox is cleared.
+fn optional_limit<T: egui::emath::Numeric>(
+    ui: &mut Ui,
+    text: &str,
+    value: &mut Option<T>,
+    default: T,
+) {
+    ui.horizontal(|ui| {
+        let mut enabled = value.is_some();
+        if ui.checkbox(&mut enabled, text).changed() {
+            *value = enabled.then_some(default);
+        }
+        if let Some(value) = value {
+            ui.add(egui::DragValue::new(value).range(1..=usize::MAX));
+        }
+    });
+}
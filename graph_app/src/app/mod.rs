@@ -1,8 +1,14 @@
 //! Application state and core functionality.
 
 mod central;
+mod estimate;
+mod files;
+mod history;
 mod menus;
 mod panels;
+mod search;
+#[cfg(feature = "persistence")]
+mod session;
 mod tasks;
 
 use eframe::egui;
@@ -13,16 +19,42 @@ use serde::*;
 use crate::graph::*;
 use crate::{
     algorithm::Algorithm,
+    app::{
+        estimate::PendingEstimate,
+        files::FileEvents,
+        history::History,
+        search::VertexSearch,
+    },
+    examples::AssetPreset,
     graph::Graph,
     output::OutputBuffer,
-    read::ReadCtx,
+    read::{
+        ParseLimits,
+        ReadCtx,
+    },
     task::TaskHandle,
-    vis::graph::GraphVis,
+    vis::{
+        graph::GraphVis,
+        layout::{
+            layered::Layering,
+            LayoutKind,
+        },
+    },
     widgets::EditableLabelState,
 };
 #[cfg(not(target_arch = "wasm32"))]
 use async_std::sync::RwLock as AsyncRwLock;
-use context_trace::graph::vertex::key::VertexKey;
+use context_trace::{
+    graph::{
+        vertex::key::VertexKey,
+        Hypergraph,
+    },
+    HashSet,
+};
+use ngrams::{
+    graph::estimate::Estimate,
+    io::Imported,
+};
 use std::sync::{
     Arc,
     RwLock as SyncRwLock,
@@ -43,6 +75,15 @@ pub(crate) struct GraphTab {
     pub(crate) read_ctx: Arc<SyncRwLock<ReadCtx>>,
     /// Currently selected node in the graph
     pub(crate) selected_node: Option<VertexKey>,
+    /// Project file the tab was last saved to or opened from
+    pub(crate) file: Option<std::path::PathBuf>,
+    /// Undo and redo stacks of the graph operations
+    pub(crate) history: History,
+    /// Vertex search of the search window
+    pub(crate) search: VertexSearch,
+    /// Project JSON of the last session save, stored again while busy
+    #[cfg(feature = "persistence")]
+    pub(crate) stored_project: Option<String>,
 }
 
 impl GraphTab {
@@ -50,7 +91,14 @@ impl GraphTab {
         id: usize,
         name: impl Into<String>,
     ) -> Self {
-        let graph = Graph::default();
+        Self::with_graph(id, name, Graph::default())
+    }
+
+    pub(crate) fn with_graph(
+        id: usize,
+        name: impl Into<String>,
+        graph: Graph,
+    ) -> Self {
         Self {
             id,
             name: name.into(),
@@ -61,6 +109,11 @@ impl GraphTab {
             #[cfg(target_arch = "wasm32")]
             read_ctx: Arc::new(SyncRwLock::new(ReadCtx::new(graph))),
             selected_node: None,
+            file: None,
+            history: History::default(),
+            search: VertexSearch::default(),
+            #[cfg(feature = "persistence")]
+            stored_project: None,
         }
     }
 
@@ -99,9 +152,6 @@ impl GraphTab {
 #[cfg_attr(feature = "persistence", derive(Deserialize, Serialize))]
 #[cfg_attr(feature = "persistence", serde(default))]
 pub struct App {
-    #[allow(unused)]
-    graph_file: Option<std::path::PathBuf>,
-
     /// List of open graph tabs
     #[cfg_attr(feature = "persistence", serde(skip))]
     pub(crate) tabs: Vec<GraphTab>,
@@ -126,6 +176,16 @@ pub struct App {
     #[cfg_attr(feature = "persistence", serde(skip))]
     pub(crate) settings_open: bool,
 
+    /// Whether the vertex search window is open
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub(crate) search_open: bool,
+
+    /// Layout of the graph canvas
+    pub(crate) layout_kind: LayoutKind,
+
+    /// Layer assignment of the layered layout
+    pub(crate) layering: Layering,
+
     /// Whether the left panel is open
     pub(crate) left_panel_open: bool,
 
@@ -147,13 +207,32 @@ pub struct App {
     /// Currently selected algorithm
     pub(crate) selected_algorithm: Algorithm,
 
+    /// Limits of the ngrams parse
+    pub(crate) parse_limits: ParseLimits,
+
     /// Current running task (unified across platforms)
     #[cfg_attr(feature = "persistence", serde(skip))]
     pub(crate) current_task: Option<TaskHandle>,
 
+    /// Whether the current task can be paused
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub(crate) current_task_pausable: bool,
+
     /// Output buffer for the bottom panel
     #[cfg_attr(feature = "persistence", serde(skip))]
     pub(crate) output: OutputBuffer,
+
+    /// Estimate of the inserter texts, keyed by their hash
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub(crate) estimate: Option<(u64, Estimate)>,
+
+    /// Estimate of the changed inserter texts, replacing `estimate` when done
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub(crate) pending_estimate: Option<PendingEstimate>,
+
+    /// Results of file dialogs to apply on the next frame
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    pub(crate) file_events: FileEvents,
 }
 
 impl Default for App {
@@ -166,13 +245,15 @@ impl App {
     pub fn new() -> Self {
         let initial_tab = GraphTab::new(0, "Graph 1");
         Self {
-            graph_file: None,
             tabs: vec![initial_tab],
             selected_tab_id: 0,
             next_tab_id: 1,
             inserter_open: true,
             inserter_manually_moved: false,
             settings_open: false,
+            search_open: false,
+            layout_kind: LayoutKind::default(),
+            layering: Layering::default(),
             left_panel_open: false,
             right_panel_open: false,
             bottom_panel_open: false,
@@ -180,11 +261,27 @@ impl App {
             bottom_panel_overlaps_right: false,
             status_bar_open: true,
             selected_algorithm: Algorithm::default(),
+            parse_limits: ParseLimits::default(),
             current_task: None,
+            current_task_pausable: false,
             output: OutputBuffer::new(),
+            estimate: None,
+            pending_estimate: None,
+            file_events: FileEvents::default(),
         }
     }
 
+    /// Restore the last session from the storage of eframe
+    #[cfg(feature = "persistence")]
+    pub fn from_storage(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage.map(Self::load_session).unwrap_or_default()
+    }
+
+    #[cfg(not(feature = "persistence"))]
+    pub fn from_storage(_storage: Option<&dyn eframe::Storage>) -> Self {
+        Self::new()
+    }
+
     /// Get the currently selected tab
     pub(crate) fn current_tab(&self) -> Option<&GraphTab> {
         self.tabs.iter().find(|t| t.id == self.selected_tab_id)
@@ -230,29 +327,56 @@ impl App {
         self.current_tab()?.vis_mut()
     }
 
-    #[allow(unused)]
-    fn open_file_dialog(&mut self) {
-        let mut dialog = rfd::AsyncFileDialog::default();
-        let res = std::env::current_dir();
-        if let Ok(current_dir) = &res {
-            dialog = dialog.set_directory(current_dir);
+    /// Replace the current graph with a preset, recording the old graph
+    pub(crate) fn load_graph(
+        &mut self,
+        name: &str,
+        graph: Hypergraph,
+        labels: HashSet<VertexKey>,
+    ) {
+        self.record(format!("Load {}", name));
+        if let Some(ctx) = self.ctx() {
+            ctx.graph().set_graph(graph);
+            *ctx.graph().labels.write().unwrap() = labels;
+        }
+        if let Some(mut vis) = self.vis_mut() {
+            vis.mark_dirty();
         }
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
-    #[allow(unused)]
-    async fn read_graph_file(
-        graph: Arc<AsyncRwLock<Option<String>>>,
-        file: &rfd::FileHandle,
+    /// Clear the current graph, recording the old graph
+    pub(crate) fn clear_graph(&mut self) {
+        self.record("Clear");
+        if let Some(mut ctx) = self.ctx_mut() {
+            ctx.graph_mut().clear();
+        }
+        if let Some(mut vis) = self.vis_mut() {
+            vis.mark_dirty();
+        }
+    }
+
+    /// Replace the current graph with an asset file, reporting import errors
+    pub(crate) fn load_asset_preset(
+        &mut self,
+        preset: AssetPreset,
     ) {
-        let content = file.read().await;
-        match std::str::from_utf8(&content[..]) {
-            Ok(_content) => {},
+        match preset.load() {
+            Ok(Imported { graph, labels }) => {
+                let vertex_count = graph.vertex_count();
+                self.load_graph(preset.file_name(), graph, labels);
+                self.output.success(format!(
+                    "Loaded {} ({} vertices)",
+                    preset.file_name(),
+                    vertex_count
+                ));
+            },
             Err(err) => {
-                rfd::AsyncMessageDialog::default()
-                    .set_description(format!("{}", err))
-                    .show()
-                    .await;
+                self.output.error(format!(
+                    "Failed to load {}: {}",
+                    preset.file_name(),
+                    err
+                ));
+                self.bottom_panel_open = true;
             },
         }
     }
@@ -262,9 +386,9 @@ impl eframe::App for App {
     #[cfg(feature = "persistence")]
     fn save(
         &mut self,
-        storage: &mut dyn epi::Storage,
+        storage: &mut dyn eframe::Storage,
     ) {
-        epi::set_value(storage, epi::APP_KEY, self);
+        self.save_session(storage);
     }
 
     fn update(
@@ -315,6 +439,15 @@ impl eframe::App for App {
         // Settings window
         self.show_settings_window(ctx);
 
+        // Undo and redo of graph operations
+        self.handle_history_shortcuts(ctx);
+
+        // Open or record files picked in dialogs
+        self.poll_file_events();
+
+        // Show what a paused task found so far
+        self.poll_paused_task();
+
         // Handle finished tasks (native only)
         #[cfg(not(target_arch = "wasm32"))]
         self.poll_finished_tasks();
@@ -335,13 +468,11 @@ impl eframe::App for App {
     }
 }
 
-#[allow(unused)]
 #[cfg(not(target_arch = "wasm32"))]
 fn execute<F: std::future::Future<Output = ()> + Send + 'static>(f: F) {
     tokio::spawn(f);
 }
 
-#[allow(unused)]
 #[cfg(target_arch = "wasm32")]
 fn execute<F: std::future::Future<Output = ()> + 'static>(f: F) {
     wasm_bindgen_futures::spawn_local(f);
@@ -22,7 +22,13 @@ use egui::containers::menu::{
 };
 use strum::IntoEnumIterator;
 
-use super::App;
+use super::{
+    history::{
+        REDO_SHORTCUT,
+        UNDO_SHORTCUT,
+    },
+    App,
+};
 use crate::algorithm::Algorithm;
 
 impl App {
@@ -35,8 +41,16 @@ impl App {
             ui.horizontal(|ui| {
                 // File menu
                 ui.menu_button("File", |ui| {
-                    if ui.button("Open...").clicked() {
-                        self.open_file_dialog();
+                    if ui.button("Open Project...").clicked() {
+                        self.open_project();
+                        ui.close();
+                    }
+                    if ui.button("Save Project").clicked() {
+                        self.save_project(false);
+                        ui.close();
+                    }
+                    if ui.button("Save Project As...").clicked() {
+                        self.save_project(true);
                         ui.close();
                     }
                     ui.separator();
@@ -61,6 +75,7 @@ impl App {
                         ui.separator();
                         ui.checkbox(&mut self.inserter_open, "Inserter Window");
                         ui.checkbox(&mut self.settings_open, "Settings Window");
+                        ui.checkbox(&mut self.search_open, "Search Window");
                         ui.separator();
                         if ui.button("New Tab").clicked() {
                             self.create_new_tab();
@@ -135,9 +150,65 @@ impl App {
                         self.abort();
                     }
                 });
+
+                ui.add_space(20.0);
+                ui.separator();
+                self.show_history(ui);
             });
     }
 
+    /// Undo history of the current tab, clicking an operation undoes or
+    /// redoes everything up to it
+    fn show_history(
+        &mut self,
+        ui: &mut egui::Ui,
+    ) {
+        ui.heading("History");
+        let Some(tab) = self.current_tab() else {
+            return;
+        };
+        let undo: Vec<String> =
+            tab.history.undo_operations().map(String::from).collect();
+        let redo: Vec<String> =
+            tab.history.redo_operations().map(String::from).collect();
+
+        ui.horizontal(|ui| {
+            let undo_button = egui::Button::new("↶ Undo")
+                .shortcut_text(ui.ctx().format_shortcut(&UNDO_SHORTCUT));
+            if ui.add_enabled(!undo.is_empty(), undo_button).clicked() {
+                self.undo();
+            }
+            let redo_button = egui::Button::new("↷ Redo")
+                .shortcut_text(ui.ctx().format_shortcut(&REDO_SHORTCUT));
+            if ui.add_enabled(!redo.is_empty(), redo_button).clicked() {
+                self.redo();
+            }
+        });
+
+        // (undo, count) of the clicked entry
+        let mut jump = None;
+        egui::ScrollArea::vertical()
+            .id_salt("left_panel_history")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                for (i, operation) in undo.iter().enumerate() {
+                    if ui.selectable_label(false, operation).clicked() {
+                        jump = Some((true, undo.len() - i));
+                    }
+                }
+                let _ = ui.selectable_label(true, "● Current");
+                for (i, operation) in redo.iter().enumerate() {
+                    let text = egui::RichText::new(operation).weak();
+                    if ui.selectable_label(false, text).clicked() {
+                        jump = Some((false, i + 1));
+                    }
+                }
+            });
+        if let Some((undo, count)) = jump {
+            self.step_by(undo, count);
+        }
+    }
+
     pub(crate) fn right_panel(
         &mut self,
         ctx: &egui::Context,
@@ -569,7 +640,15 @@ impl App {
                             if let Some(status) = read_ctx.status() {
                                 let status = status.read().unwrap();
                                 ui.separator();
-                                ui.label(format!("Pass: {:?}", status.pass()));
+                                match status.stage_name() {
+                                    Some(stage) if *status.pass()
+                                        < ngrams::ProcessStatus::Partitions =>
+                                        ui.label(format!("Stage: {}", stage)),
+                                    _ => ui.label(format!(
+                                        "Pass: {:?}",
+                                        status.pass()
+                                    )),
+                                };
                                 let progress = *status.steps() as f32
                                     / *status.steps_total() as f32;
                                 ui.add(
@@ -0,0 +1,289 @@
+//! Vertex search of a graph tab.
+//!
+//! Matches the strings of the vertices shown on the canvas by substring or
+//! regular expression. Matches are highlighted on the canvas, the selected
+//! one is centered and Enter steps through them.
+
+use context_trace::graph::vertex::key::VertexKey;
+use eframe::egui::{
+    self,
+    Key,
+    KeyboardShortcut,
+    Modifiers,
+    Rect,
+    Ui,
+};
+use regex::Regex;
+
+use super::App;
+use crate::vis::graph::GraphVis;
+
+pub(crate) const SEARCH_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND, Key::F);
+
+/// Compiled search query
+#[derive(Debug)]
+enum Matcher {
+    Substring(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn is_match(
+        &self,
+        string: &str,
+    ) -> bool {
+        match self {
+            Matcher::Substring(query) => string.contains(query.as_str()),
+            Matcher::Regex(regex) => regex.is_match(string),
+        }
+    }
+}
+
+/// Search state of a tab
+#[derive(Debug, Default)]
+pub(crate) struct VertexSearch {
+    pub(crate) query: String,
+    /// Interpret the query as a regular expression
+    pub(crate) regex: bool,
+    /// Matching vertices with their strings, sorted by string
+    matches: Vec<(VertexKey, String)>,
+    /// Index of the selected match
+    current: Option<usize>,
+    /// Error of an invalid regular expression
+    error: Option<String>,
+    /// Query, mode and canvas generation the matches were found for
+    searched: Option<(String, bool, usize)>,
+}
+
+impl VertexSearch {
+    fn matcher(&self) -> Result<Matcher, regex::Error> {
+        if self.regex {
+            Regex::new(&self.query).map(Matcher::Regex)
+        } else {
+            Ok(Matcher::Substring(self.query.clone()))
+        }
+    }
+
+    /// Search the nodes of `vis` again if the query or the canvas changed
+    pub(crate) fn update(
+        &mut self,
+        vis: &GraphVis,
+    ) {
+        let searched = (self.query.clone(), self.regex, vis.generation());
+        if self.searched.as_ref() == Some(&searched) {
+            return;
+        }
+        self.searched = Some(searched);
+        let current = self.current_key();
+        self.matches.clear();
+        self.error = None;
+        if !self.query.is_empty() {
+            match self.matcher() {
+                Ok(matcher) => {
+                    self.matches = vis
+                        .nodes()
+                        .filter(|node| matcher.is_match(&node.name))
+                        .map(|node| (node.key, node.name.clone()))
+                        .collect();
+                    self.matches.sort_by(|a, b| a.1.cmp(&b.1));
+                },
+                Err(err) => self.error = Some(err.to_string()),
+            }
+        }
+        self.current = current
+            .and_then(|key| self.matches.iter().position(|(k, _)| *k == key));
+    }
+
+    pub(crate) fn matches(&self) -> impl Iterator<Item = VertexKey> + '_ {
+        self.matches.iter().map(|(key, _)| *key)
+    }
+
+    pub(crate) fn current_key(&self) -> Option<VertexKey> {
+        self.current.map(|i| self.matches[i].0)
+    }
+
+    /// Select the next match, or the previous one if `back` is set
+    pub(crate) fn step(
+        &mut self,
+        back: bool,
+    ) -> Option<VertexKey> {
+        let len = self.matches.len();
+        if len == 0 {
+            return None;
+        }
+        self.current = Some(match (self.current, back) {
+            (None, false) => 0,
+            (None, true) => len - 1,
+            (Some(i), false) => (i + 1) % len,
+            (Some(i), true) => (i + len - 1) % len,
+        });
+        self.current_key()
+    }
+
+    /// Show the search box and the results, returns the match to focus
+    fn show(
+        &mut self,
+        ui: &mut Ui,
+        vis: &GraphVis,
+        request_focus: bool,
+    ) -> Option<VertexKey> {
+        let mut selected = None;
+        let mut enter = false;
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.query)
+                    .hint_text("Search vertices")
+                    .desired_width(180.0),
+            );
+            enter = response.lost_focus()
+                && ui.input(|i| i.key_pressed(Key::Enter));
+            if request_focus || enter {
+                response.request_focus();
+            }
+            ui.checkbox(&mut self.regex, ".*")
+                .on_hover_text("Regular expression");
+        });
+        self.update(vis);
+        if enter {
+            let back = ui.input(|i| i.modifiers.shift);
+            selected = self.step(back);
+        }
+
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        } else if !self.query.is_empty() {
+            ui.horizontal(|ui| {
+                let count = self.matches.len();
+                match self.current {
+                    Some(i) => {
+                        ui.label(format!("{} / {} matches", i + 1, count))
+                    },
+                    None => ui.label(format!("{} matches", count)),
+                };
+                let previous =
+                    ui.small_button("⏶").on_hover_text("Shift+Enter");
+                if previous.clicked() {
+                    selected = self.step(true);
+                }
+                if ui.small_button("⏷").on_hover_text("Enter").clicked() {
+                    selected = self.step(false);
+                }
+            });
+        }
+
+        egui::ScrollArea::vertical()
+            .id_salt("search_results")
+            .max_height(250.0)
+            .show(ui, |ui| {
+                for (i, (key, string)) in self.matches.iter().enumerate() {
+                    let is_current = self.current == Some(i);
+                    if ui.selectable_label(is_current, string).clicked() {
+                        self.current = Some(i);
+                        selected = Some(*key);
+                    }
+                }
+            });
+        selected
+    }
+}
+
+impl App {
+    /// Floating search window of the current tab, toggled with Ctrl+F
+    pub(crate) fn show_search_window(
+        &mut self,
+        ctx: &egui::Context,
+        viewport_rect: Rect,
+    ) {
+        let shortcut = ctx.input_mut(|i| i.consume_shortcut(&SEARCH_SHORTCUT));
+        if shortcut {
+            self.search_open = true;
+        }
+        let mut search_open = self.search_open;
+        let Some(tab) = self.current_tab_mut() else {
+            return;
+        };
+        let Ok(mut vis) = tab.vis.write() else {
+            return;
+        };
+        if !search_open {
+            vis.set_highlight(Default::default(), None);
+            return;
+        }
+
+        let mut selected = None;
+        egui::Window::new("🔍 Search")
+            .open(&mut search_open)
+            .resizable(true)
+            .default_width(260.0)
+            .default_pos(viewport_rect.left_top() + egui::vec2(10.0, 10.0))
+            .constrain_to(viewport_rect)
+            .show(ctx, |ui| {
+                selected = tab.search.show(ui, &vis, shortcut);
+            });
+        vis.set_highlight(
+            tab.search.matches().collect(),
+            tab.search.current_key(),
+        );
+        if let Some(key) = selected {
+            vis.focus(key);
+            tab.selected_node = Some(key);
+            ctx.request_repaint();
+        }
+        drop(vis);
+        self.search_open = search_open;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        examples::build_graph1,
+        graph::Graph,
+    };
+
+    fn search(
+        vis: &GraphVis,
+        query: &str,
+        regex: bool,
+    ) -> VertexSearch {
+        let mut search = VertexSearch {
+            query: query.to_string(),
+            regex,
+            ..Default::default()
+        };
+        search.update(vis);
+        search
+    }
+
+    #[test]
+    fn test_vertex_search() {
+        let mut vis = GraphVis::new(Graph::from(build_graph1()));
+        vis.update().unwrap();
+
+        let found = search(&vis, "ab", false);
+        let strings: Vec<&str> =
+            found.matches.iter().map(|(_, s)| s.as_str()).collect();
+        assert!(strings.contains(&"xab"));
+        assert!(strings.iter().all(|s| s.contains("ab")));
+
+        let found = search(&vis, "^x.*z$", true);
+        assert!(!found.matches.is_empty());
+        for (_, string) in &found.matches {
+            assert!(string.starts_with('x') && string.ends_with('z'));
+        }
+
+        let mut found = search(&vis, "(", true);
+        assert!(found.error.is_some());
+        assert_eq!(found.step(false), None);
+
+        // stepping wraps around in both directions
+        let mut found = search(&vis, "ab", false);
+        let first = found.matches().next();
+        let last = found.matches().last();
+        assert_eq!(found.step(false), first);
+        assert_eq!(found.step(true), last);
+        assert_eq!(found.step(false), first);
+    }
+}
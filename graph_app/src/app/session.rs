@@ -0,0 +1,264 @@
+//! Session persistence across restarts.
+//!
+//! The settings of the app are stored under `eframe::APP_KEY`, the tabs and
+//! the output history in a [`Session`] under [`SESSION_KEY`]. Every tab is
+//! stored as project JSON, so older sessions are upgraded by the project
+//! migrations, and the session layout itself by the [`MIGRATIONS`].
+//! Projects larger than [`INLINE_LIMIT`] are written to side files in the
+//! storage directory instead of the key-value storage. Browsers have no
+//! storage directory, there all projects are stored inline.
+//!
+//! A tab busy with a task can not be captured, its project of the last save
+//! is stored again instead.
+
+use std::path::PathBuf;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    App,
+    GraphTab,
+};
+use crate::{
+    output::OutputLine,
+    project::Project,
+};
+
+/// Storage key of the session
+pub(crate) const SESSION_KEY: &str = "session";
+/// Version of the session layout, increased on incompatible changes
+pub(crate) const SESSION_VERSION: u32 = 1;
+/// Upgrades of the session layout, `MIGRATIONS[i]` turns a session of
+/// version `i + 1` into version `i + 2`. Add an entry when increasing
+/// [`SESSION_VERSION`].
+const MIGRATIONS: [fn(&mut serde_json::Value); SESSION_VERSION as usize - 1] =
+    [];
+/// Largest project stored in the key-value storage
+const INLINE_LIMIT: usize = 64 * 1024;
+/// Number of output lines kept across restarts
+const HISTORY_LIMIT: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize)]
+enum StoredProject {
+    /// Project JSON kept in the key-value storage
+    Inline(String),
+    /// Name of a project file in the side file directory
+    SideFile(String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TabSession {
+    id: usize,
+    name: String,
+    file: Option<PathBuf>,
+    /// Missing if the tab was busy with a task in every save since it was
+    /// opened
+    project: Option<StoredProject>,
+}
+
+/// Open tabs and output history
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Session {
+    version: u32,
+    tabs: Vec<TabSession>,
+    selected_tab_id: usize,
+    next_tab_id: usize,
+    output: Vec<OutputLine>,
+}
+
+impl Session {
+    fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("sessions always serialize")
+    }
+
+    /// Read a session, upgrading older versions
+    fn from_json(json: &str) -> Result<Self, String> {
+        let mut value: serde_json::Value =
+            serde_json::from_str(json).map_err(|err| err.to_string())?;
+        let version = value["version"].as_u64().unwrap_or(0) as u32;
+        if version == 0 || version > SESSION_VERSION {
+            return Err(format!("unsupported version {}", version));
+        }
+        for migrate in &MIGRATIONS[version as usize - 1..] {
+            migrate(&mut value);
+        }
+        value["version"] = SESSION_VERSION.into();
+        serde_json::from_value(value).map_err(|err| err.to_string())
+    }
+}
+
+/// Directory of the side files
+#[cfg(not(target_arch = "wasm32"))]
+fn side_file_dir() -> Option<PathBuf> {
+    eframe::storage_dir(crate::APP_NAME).map(|dir| dir.join("tabs"))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn side_file_dir() -> Option<PathBuf> {
+    None
+}
+
+/// Store large projects in a side file, falling back to inline storage
+fn store_project(
+    id: usize,
+    json: String,
+) -> StoredProject {
+    let Some(dir) = side_file_dir().filter(|_| json.len() > INLINE_LIMIT)
+    else {
+        return StoredProject::Inline(json);
+    };
+    let name = format!("tab-{}.json", id);
+    let written = std::fs::create_dir_all(&dir)
+        .and_then(|()| std::fs::write(dir.join(&name), &json));
+    match written {
+        Ok(()) => StoredProject::SideFile(name),
+        Err(err) => {
+            eprintln!("Failed to write side file {}: {}", name, err);
+            StoredProject::Inline(json)
+        },
+    }
+}
+
+fn load_project(stored: StoredProject) -> Result<String, String> {
+    match stored {
+        StoredProject::Inline(json) => Ok(json),
+        StoredProject::SideFile(name) => {
+            let dir = side_file_dir()
+                .ok_or_else(|| format!("No directory for {}", name))?;
+            std::fs::read_to_string(dir.join(&name))
+                .map_err(|err| format!("Failed to read {}: {}", name, err))
+        },
+    }
+}
+
+/// Delete side files of closed tabs
+fn remove_stale_side_files(tabs: &[TabSession]) {
+    let Some(entries) = side_file_dir().and_then(|d| d.read_dir().ok())
+    else {
+        return;
+    };
+    let used: Vec<&str> = tabs
+        .iter()
+        .filter_map(|tab| match &tab.project {
+            Some(StoredProject::SideFile(name)) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        if !used.iter().any(|used| name == **used) {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+impl App {
+    fn session(&mut self) -> Session {
+        let captured: Vec<Option<String>> = self
+            .tabs
+            .iter()
+            .map(|tab| self.tab_project(tab).ok().map(|p| p.to_json()))
+            .collect();
+        for (tab, json) in self.tabs.iter_mut().zip(captured) {
+            if json.is_some() {
+                tab.stored_project = json;
+            }
+        }
+        let tabs: Vec<TabSession> = self
+            .tabs
+            .iter()
+            .map(|tab| TabSession {
+                id: tab.id,
+                name: tab.name.clone(),
+                file: tab.file.clone(),
+                project: tab
+                    .stored_project
+                    .clone()
+                    .map(|json| store_project(tab.id, json)),
+            })
+            .collect();
+        remove_stale_side_files(&tabs);
+        let output = self.output.lines();
+        let skip = output.len().saturating_sub(HISTORY_LIMIT);
+        Session {
+            version: SESSION_VERSION,
+            tabs,
+            selected_tab_id: self.selected_tab_id,
+            next_tab_id: self.next_tab_id,
+            output: output.into_iter().skip(skip).collect(),
+        }
+    }
+
+    /// Store the settings and the session
+    pub(crate) fn save_session(
+        &mut self,
+        storage: &mut dyn eframe::Storage,
+    ) {
+        let session = self.session();
+        eframe::set_value(storage, eframe::APP_KEY, &*self);
+        storage.set_string(SESSION_KEY, session.to_json());
+    }
+
+    /// Restore the settings and the session of the last run
+    pub(crate) fn load_session(storage: &dyn eframe::Storage) -> Self {
+        let mut app: App =
+            eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+        let session = storage.get_string(SESSION_KEY);
+        match session.as_deref().map(Session::from_json) {
+            Some(Ok(session)) => app.restore_session(session),
+            Some(Err(err)) => app
+                .output
+                .warn(format!("Discarded the last session: {}", err)),
+            None => {},
+        }
+        app
+    }
+
+    fn restore_session(
+        &mut self,
+        session: Session,
+    ) {
+        self.output.extend(session.output);
+        if session.tabs.is_empty() {
+            return;
+        }
+        let mut tabs = Vec::new();
+        for stored in session.tabs {
+            let tab = stored
+                .project
+                .ok_or_else(|| "The tab was busy when saving".to_string())
+                .and_then(load_project)
+                .and_then(|json| {
+                    let mut tab = Project::from_json(&json)
+                        .and_then(|project| {
+                            GraphTab::from_project(stored.id, &project)
+                        })
+                        .map_err(|err| err.to_string())?;
+                    tab.stored_project = Some(json);
+                    Ok(tab)
+                });
+            let mut tab = tab.unwrap_or_else(|err| {
+                self.output.warn(format!(
+                    "Could not restore tab {}: {}",
+                    stored.name, err
+                ));
+                GraphTab::new(stored.id, stored.name.clone())
+            });
+            tab.name = stored.name;
+            tab.file = stored.file;
+            tabs.push(tab);
+        }
+        let max_id = tabs.iter().map(|tab| tab.id).max().unwrap_or(0);
+        self.next_tab_id = session.next_tab_id.max(max_id + 1);
+        let selected = session.selected_tab_id;
+        self.selected_tab_id = if tabs.iter().any(|tab| tab.id == selected) {
+            selected
+        } else {
+            tabs[0].id
+        };
+        self.tabs = tabs;
+    }
+}
@@ -31,6 +31,7 @@ impl App {
         });
 
         self.current_task = Some(task);
+        self.current_task_pausable = false;
     }
 
     /// Start a test blocking task.
@@ -56,14 +57,19 @@ impl App {
         });
 
         self.current_task = Some(task);
+        self.current_task_pausable = false;
     }
 
     /// Start running the selected algorithm.
     pub(crate) fn start_read(&mut self) {
         let algorithm = self.selected_algorithm;
         let output = self.output.clone();
+        let limits = self.parse_limits;
 
         output.info(format!("Starting {} algorithm...", algorithm));
+        if algorithm.mutates_graph() {
+            self.record(format!("Run {}", algorithm));
+        }
 
         let ctx = match self.current_tab() {
             Some(tab) => tab.read_ctx.clone(),
@@ -72,15 +78,30 @@ impl App {
 
         #[cfg(not(target_arch = "wasm32"))]
         let task = TaskHandle::spawn(move |cancellation| async move {
-            native::run_algorithm_task(ctx, algorithm, cancellation).await;
+            native::run_algorithm_task(
+                ctx,
+                algorithm,
+                output,
+                limits,
+                cancellation,
+            )
+            .await;
         });
 
         #[cfg(target_arch = "wasm32")]
         let task = TaskHandle::spawn(move |cancellation| async move {
-            wasm::run_algorithm_task(ctx, algorithm, cancellation).await;
+            wasm::run_algorithm_task(
+                ctx,
+                algorithm,
+                output,
+                limits,
+                cancellation,
+            )
+            .await;
         });
 
         self.current_task = Some(task);
+        self.current_task_pausable = algorithm.is_pausable();
     }
 
     /// Abort the currently running task.
@@ -95,6 +116,43 @@ impl App {
         self.output.warn("Operation aborted.");
     }
 
+    /// Pause the running task, or resume it if it is paused.
+    pub(crate) fn toggle_pause(&mut self) {
+        if !self.current_task_pausable {
+            return;
+        }
+        if let Some(task) = &self.current_task {
+            let cancellation = task.cancellation();
+            if cancellation.is_paused() {
+                cancellation.resume();
+                self.output.info("Operation resumed.");
+            } else {
+                cancellation.pause();
+                self.output.info("Operation paused.");
+            }
+        }
+    }
+
+    /// Check if the running task is paused.
+    pub(crate) fn is_task_paused(&self) -> bool {
+        self.current_task
+            .as_ref()
+            .map(|t| t.is_running() && t.cancellation().is_paused())
+            .unwrap_or(false)
+    }
+
+    /// Show the labels of a paused task in the current tab.
+    pub(crate) fn poll_paused_task(&mut self) {
+        if !self.is_task_paused() {
+            return;
+        }
+        if let Some(tab) = self.current_tab() {
+            if let Some(mut vis) = tab.vis_mut() {
+                vis.show_paused_labels();
+            }
+        }
+    }
+
     /// Poll for finished tasks and handle their results.
     pub(crate) fn poll_finished_tasks(&mut self) {
         let task_finished = self
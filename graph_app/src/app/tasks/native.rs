@@ -6,7 +6,11 @@ use async_std::sync::RwLock;
 
 use crate::{
     algorithm::Algorithm,
-    read::ReadCtx,
+    output::OutputBuffer,
+    read::{
+        ParseLimits,
+        ReadCtx,
+    },
     task::CancellationHandle,
 };
 
@@ -14,10 +18,14 @@ use crate::{
 pub(crate) async fn run_algorithm_task(
     ctx: Arc<RwLock<ReadCtx>>,
     algorithm: Algorithm,
+    output: OutputBuffer,
+    limits: ParseLimits,
     cancellation: CancellationHandle,
 ) {
     println!("Task starting: algorithm = {:?}", algorithm);
     let mut ctx_guard = ctx.write().await;
+    ctx_guard.set_output(output);
+    ctx_guard.set_limits(limits);
     ctx_guard.run_algorithm(algorithm, cancellation).await;
     println!("Task completed");
 }
@@ -7,7 +7,11 @@ use std::sync::{
 
 use crate::{
     algorithm::Algorithm,
-    read::ReadCtx,
+    output::OutputBuffer,
+    read::{
+        ParseLimits,
+        ReadCtx,
+    },
     task::CancellationHandle,
 };
 
@@ -15,6 +19,8 @@ use crate::{
 pub(crate) async fn run_algorithm_task(
     ctx: Arc<RwLock<ReadCtx>>,
     algorithm: Algorithm,
+    output: OutputBuffer,
+    limits: ParseLimits,
     cancellation: CancellationHandle,
 ) {
     web_sys::console::log_1(
@@ -23,6 +29,8 @@ pub(crate) async fn run_algorithm_task(
 
     {
         let mut ctx_guard = ctx.write().unwrap();
+        ctx_guard.set_output(output);
+        ctx_guard.set_limits(limits);
         ctx_guard.run_algorithm(algorithm, cancellation).await;
     }
 
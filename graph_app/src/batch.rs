@@ -0,0 +1,214 @@
+//! Headless batch mode running an algorithm without opening a window.
+//!
+//! `graph_app run --algorithm ngrams --input corpus.txt --output graph.json`
+//! runs the same `ReadCtx::run_algorithm` as the app, prints the output
+//! buffer to stdout and writes the resulting graph.
+//!
+//! `graph_app run --algorithm search --graph graph.json --input query.txt`
+//! searches the texts in the graph and fails unless the whole query is
+//! found. It needs no output since the graph is not changed.
+
+use std::{
+    fs::File,
+    io::{
+        BufWriter,
+        Write,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::ExitCode,
+};
+
+use ngrams::{
+    graph::strings::StringGraph,
+    io::{
+        corpus::read_corpus_file,
+        import::import_file,
+        write_dot,
+        write_graphml,
+        CorpusFormat,
+        ExportOptions,
+        ImportError,
+        Imported,
+    },
+};
+
+use crate::{
+    algorithm::Algorithm,
+    graph::Graph,
+    output::{
+        OutputBuffer,
+        OutputLevel,
+    },
+    read::{
+        ParseLimits,
+        ReadCtx,
+    },
+    task::CancellationHandle,
+};
+
+/// Arguments of `graph_app run`
+#[derive(Debug, clap::Args)]
+pub struct BatchArgs {
+    /// Algorithm to run: ngrams, read, insert or search
+    #[arg(long, short)]
+    algorithm: Algorithm,
+    /// Corpus file with the texts to process
+    #[arg(long, short)]
+    input: PathBuf,
+    /// Layout of the corpus file: lines or leipzig
+    #[arg(long, default_value = "lines")]
+    format: CorpusFormat,
+    /// Graph to start from, `.json`, `.graphml` or `.dot`
+    #[arg(long)]
+    graph: Option<PathBuf>,
+    /// Where to write the resulting graph, `.json`, `.graphml` or `.dot`,
+    /// required unless searching
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+    /// Seconds after which the ngrams parse stops
+    #[arg(long)]
+    timeout: Option<u64>,
+    /// Maximum number of steps of a single ngrams pass
+    #[arg(long)]
+    max_steps: Option<usize>,
+    /// Maximum number of ngrams vocabulary entries
+    #[arg(long)]
+    max_vocab_entries: Option<usize>,
+}
+
+/// Read a graph written by [`write_graph`].
+fn read_graph(path: &Path) -> Result<Imported, ImportError> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => {
+            let text = std::fs::read_to_string(path)?;
+            let strings: StringGraph = serde_json::from_str(&text)
+                .map_err(|e| ImportError::Parse(e.to_string()))?;
+            strings.build().map_err(|e| ImportError::Invalid(vec![e]))
+        },
+        _ => import_file(path),
+    }
+}
+
+/// Write the graph of a tab, choosing the format from the extension.
+///
+/// JSON files contain the vertices keyed by string with their child
+/// patterns and label flags.
+fn write_graph(
+    graph: &Graph,
+    path: &Path,
+) -> std::io::Result<()> {
+    let hypergraph = graph.read();
+    let labels = graph.labels.read().unwrap().clone();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut writer = BufWriter::new(File::create(path)?);
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("graphml" | "xml") => write_graphml(
+            &hypergraph,
+            &ExportOptions::default().labels(labels),
+            &mut writer,
+        )?,
+        Some("dot" | "gv") => write_dot(
+            &hypergraph,
+            &ExportOptions::default().labels(labels),
+            &mut writer,
+        )?,
+        _ => serde_json::to_writer_pretty(
+            &mut writer,
+            &StringGraph::from_hypergraph(&hypergraph, &labels),
+        )?,
+    }
+    writer.flush()
+}
+
+fn print_output(output: &OutputBuffer) {
+    for line in output.lines() {
+        println!("{} {}", line.level.prefix(), line.text);
+    }
+}
+
+/// Run an algorithm without a window, returns a failure if it logged an
+/// error, produced an empty graph, did not find the whole query or the
+/// files could not be accessed.
+pub async fn run(args: BatchArgs) -> ExitCode {
+    if args.output.is_none() && args.algorithm.mutates_graph() {
+        eprintln!("{} needs an --output to write the graph to", args.algorithm);
+        return ExitCode::FAILURE;
+    }
+    let output = OutputBuffer::new();
+    let graph = match &args.graph {
+        Some(path) => match read_graph(path) {
+            Ok(Imported { graph, labels }) => {
+                let graph = Graph::from(graph);
+                *graph.labels.write().unwrap() = labels;
+                graph
+            },
+            Err(err) => {
+                eprintln!("Failed to read {}: {}", path.display(), err);
+                return ExitCode::FAILURE;
+            },
+        },
+        None => Graph::default(),
+    };
+    let corpus = match read_corpus_file(&args.input, args.format) {
+        Ok(corpus) => corpus,
+        Err(err) => {
+            eprintln!("Failed to read {}: {}", args.input.display(), err);
+            return ExitCode::FAILURE;
+        },
+    };
+
+    let mut ctx = ReadCtx::new(graph);
+    ctx.graph_mut().insert_texts = corpus.to_vec();
+    ctx.set_output(output.clone());
+    ctx.set_limits(ParseLimits {
+        timeout_secs: args.timeout,
+        max_steps: args.max_steps,
+        max_vocab_entries: args.max_vocab_entries,
+    });
+    ctx.run_algorithm(args.algorithm, CancellationHandle::new())
+        .await;
+    print_output(&output);
+
+    let failed = output
+        .lines()
+        .iter()
+        .any(|line| line.level == OutputLevel::Error);
+    if failed {
+        return ExitCode::FAILURE;
+    }
+    if !args.algorithm.mutates_graph() {
+        // the output buffer already reports the match
+        match ctx.graph().query_result() {
+            Some(found) if found.complete => {},
+            Some(_) => {
+                eprintln!("Only part of the query was found");
+                return ExitCode::FAILURE;
+            },
+            None => {
+                eprintln!("{} found no match", args.algorithm);
+                return ExitCode::FAILURE;
+            },
+        }
+    } else if ctx.graph().read().vertex_count() == 0 {
+        eprintln!("{} produced an empty graph", args.algorithm);
+        return ExitCode::FAILURE;
+    }
+    let Some(path) = &args.output else {
+        return ExitCode::SUCCESS;
+    };
+    match write_graph(ctx.graph(), path) {
+        Ok(()) => {
+            println!("Wrote {}", path.display());
+            ExitCode::SUCCESS
+        },
+        Err(err) => {
+            eprintln!("Failed to write {}: {}", path.display(), err);
+            ExitCode::FAILURE
+        },
+    }
+}
@@ -6,6 +6,19 @@ use context_trace::{
     insert_atoms,
     insert_patterns,
 };
+use ngrams::io::{
+    import_dot,
+    import_graphml,
+    ImportError,
+    Imported,
+};
+
+/// Graphs built in code, offered next to the asset presets
+pub(crate) const BUILTIN_GRAPHS: [(&str, fn() -> Hypergraph); 3] = [
+    ("Graph 1", build_graph1),
+    ("Graph 2", build_graph2),
+    ("Graph 3", build_graph3),
+];
 
 pub(crate) fn build_graph1() -> Hypergraph {
     let graph = Hypergraph::default();
@@ -139,3 +152,52 @@ pub(crate) fn build_graph3() -> Hypergraph {
     );
     graph
 }
+
+/// Graph files from `test/assets`, bundled so they also load on wasm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AssetPreset {
+    ComplexGiggerish,
+    ExampleGraph,
+    TextGraph,
+    SeqGraph,
+    TestGraph,
+}
+impl AssetPreset {
+    pub(crate) const ALL: [Self; 5] = [
+        Self::ComplexGiggerish,
+        Self::ExampleGraph,
+        Self::TextGraph,
+        Self::SeqGraph,
+        Self::TestGraph,
+    ];
+    pub(crate) fn file_name(&self) -> &'static str {
+        match self {
+            Self::ComplexGiggerish => "complex_giggerish.graphml",
+            Self::ExampleGraph => "example_graph.graphml",
+            Self::TextGraph => "text_graph.graphml",
+            Self::SeqGraph => "seq_graph.dot",
+            Self::TestGraph => "test_graph.dot",
+        }
+    }
+    fn content(&self) -> &'static str {
+        match self {
+            Self::ComplexGiggerish => include_str!(
+                "../../test/assets/complex_giggerish.graphml"
+            ),
+            Self::ExampleGraph =>
+                include_str!("../../test/assets/example_graph.graphml"),
+            Self::TextGraph =>
+                include_str!("../../test/assets/text_graph.graphml"),
+            Self::SeqGraph => include_str!("../../test/assets/seq_graph.dot"),
+            Self::TestGraph =>
+                include_str!("../../test/assets/test_graph.dot"),
+        }
+    }
+    pub(crate) fn load(&self) -> Result<Imported, ImportError> {
+        if self.file_name().ends_with(".dot") {
+            import_dot(self.content())
+        } else {
+            import_graphml(self.content())
+        }
+    }
+}
@@ -13,6 +13,20 @@ use context_trace::{
     },
     HashSet,
 };
+use ngrams::graph::{
+    vocabulary::ProcessStatus,
+    PausedLabelsSlot,
+};
+
+use crate::query::QueryResult;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use strum::{
+    Display,
+    EnumIter,
+};
 #[allow(unused)]
 use petgraph::{
     graph::{
@@ -30,6 +44,37 @@ pub(crate) struct Graph {
     pub(crate) rec: Option<rerun::RecordingStream>,
     pub(crate) insert_texts: Vec<String>,
     pub(crate) labels: Arc<RwLock<HashSet<VertexKey>>>,
+    /// Pass reached by a cancelled ngrams run whose result is shown
+    pub(crate) partial: Arc<RwLock<Option<ProcessStatus>>>,
+    /// N-gram lattice of the last ngrams run, sharing keys with `graph`
+    pub(crate) containment: Arc<RwLock<Option<HypergraphRef>>>,
+    /// Match of the last context search
+    pub(crate) query_result: Arc<RwLock<Option<QueryResult>>>,
+    /// Labels published by a paused ngrams run, not shown yet
+    pub(crate) paused_labels: PausedLabelsSlot,
+}
+
+/// Which graph of a tab the canvas shows
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    Display,
+    EnumIter,
+    Serialize,
+    Deserialize,
+)]
+pub(crate) enum GraphView {
+    /// The graph built by the last algorithm
+    #[default]
+    #[strum(serialize = "Partitions")]
+    Partition,
+    /// The containment lattice of all n-grams of the last ngrams run
+    #[strum(serialize = "Lattice")]
+    Containment,
 }
 impl Default for Graph {
     fn default() -> Self {
@@ -48,6 +93,10 @@ impl From<HypergraphRef> for Graph {
             graph: Arc::new(RwLock::new(graph)),
             insert_texts: vec![String::from("aabbaabbaa")],
             labels: Default::default(),
+            partial: Default::default(),
+            containment: Default::default(),
+            query_result: Default::default(),
+            paused_labels: Default::default(),
             #[cfg(not(target_arch = "wasm32"))]
             rec: None,
         }
@@ -62,6 +111,10 @@ impl From<rerun::RecordingStream> for Graph {
             ))),
             insert_texts: vec![String::from("aabbaabbaa")],
             labels: Default::default(),
+            partial: Default::default(),
+            containment: Default::default(),
+            query_result: Default::default(),
+            paused_labels: Default::default(),
             rec: Some(rec),
         }
     }
@@ -81,10 +134,63 @@ impl Graph {
         graph: Hypergraph,
     ) {
         *self.write() = HypergraphRef::from(graph);
+        self.clear_ngrams_state();
+        *self.query_result.write().unwrap() = None;
     }
     pub(crate) fn clear(&self) {
         // Replace the underlying graph with a new empty one, keeping the same Arc
         *self.write() = HypergraphRef::from(Hypergraph::default());
+        self.clear_ngrams_state();
+        *self.query_result.write().unwrap() = None;
+    }
+    /// Forget the lattice and partial pass of the last ngrams run, which
+    /// no longer describe a graph changed by another algorithm
+    pub(crate) fn clear_ngrams_state(&self) {
+        *self.partial.write().unwrap() = None;
+        *self.containment.write().unwrap() = None;
+    }
+    /// Take over the graphs of `other`, keeping the insert texts
+    pub(crate) fn replace(
+        &self,
+        other: &Graph,
+    ) {
+        *self.write() = other.read().clone();
+        *self.labels.write().unwrap() = other.labels.read().unwrap().clone();
+        *self.partial.write().unwrap() = other.partial();
+        *self.containment.write().unwrap() =
+            other.containment.read().unwrap().clone();
+        *self.query_result.write().unwrap() = None;
+    }
+    /// Pass reached by the run which produced a partial graph
+    pub(crate) fn partial(&self) -> Option<ProcessStatus> {
+        *self.partial.read().unwrap()
+    }
+    pub(crate) fn query_result(&self) -> Option<QueryResult> {
+        self.query_result.read().unwrap().clone()
+    }
+    /// Show the labels published by a paused ngrams run with their
+    /// lattice, returns whether there were any
+    pub(crate) fn take_paused_labels(&self) -> bool {
+        let Some(paused) = self.paused_labels.write().unwrap().take() else {
+            return false;
+        };
+        *self.labels.write().unwrap() = paused.labels;
+        *self.containment.write().unwrap() = Some(paused.containment.into());
+        true
+    }
+    pub(crate) fn has_containment(&self) -> bool {
+        self.containment.read().unwrap().is_some()
+    }
+    /// The graph shown in `view`, falls back to the partition graph when
+    /// there is no lattice
+    pub(crate) fn view(
+        &self,
+        view: GraphView,
+    ) -> HypergraphRef {
+        match (view, &*self.containment.read().unwrap()) {
+            (GraphView::Containment, Some(containment)) => containment.clone(),
+            _ => self.read().clone(),
+        }
     }
     //pub(crate) fn read_text(
     //    &mut self,
@@ -4,9 +4,18 @@
 mod algorithm;
 mod app;
 pub use app::App;
+#[cfg(not(target_arch = "wasm32"))]
+mod batch;
+#[cfg(not(target_arch = "wasm32"))]
+pub use batch::{
+    run as run_batch,
+    BatchArgs,
+};
 mod examples;
 mod graph;
 mod output;
+mod project;
+mod query;
 mod read;
 pub(crate) mod task;
 mod vis;
@@ -26,6 +35,8 @@ use eframe::wasm_bindgen::{
 };
 pub(crate) use tracing::*;
 
+/// Name of the app window, also used as the id of its storage
+pub const APP_NAME: &str = "Graph App";
 
 // Native-only open function
 #[cfg(not(target_arch = "wasm32"))]
@@ -33,7 +44,7 @@ pub(crate) async fn open(_graph: HypergraphRef) -> Result<(), eframe::Error> {
     // Each tab manages its own graph internally
     let app = App::new();
     eframe::run_native(
-        "Graph App",
+        APP_NAME,
         eframe::NativeOptions::default(),
         Box::new(|_| Ok(Box::new(app))),
     )
@@ -3,6 +3,10 @@
 #![allow(clippy::obfuscated_if_else)]
 
 use graph_app::App;
+#[cfg(not(target_arch = "wasm32"))]
+use graph_app::APP_NAME;
+#[cfg(not(target_arch = "wasm32"))]
+use std::process::ExitCode;
 
 #[cfg(not(target_arch = "wasm32"))]
 #[derive(Debug, clap::Parser)]
@@ -11,14 +15,26 @@ use graph_app::App;
 pub(crate) struct Args {
     #[command(flatten)]
     rerun: rerun::clap::RerunArgs,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, clap::Subcommand)]
+pub(crate) enum Command {
+    /// Run an algorithm on a corpus without opening a window
+    Run(graph_app::BatchArgs),
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 #[tokio::main(flavor = "multi_thread", worker_threads = 2)]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
     use clap::Parser as _;
 
-    let _args = Args::parse();
+    let args = Args::parse();
+    if let Some(Command::Run(batch)) = args.command {
+        return Ok(graph_app::run_batch(batch).await);
+    }
 
     // let (rec, _serve_guard) = args.rerun.init("context_graph_app")?;
     println!("Main thread {:?}", std::thread::current().id());
@@ -29,15 +45,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     eframe::run_native(
-        "Graph App",
+        APP_NAME,
         native_options,
         Box::new(|creation_context| {
             use eframe::egui::ThemePreference;
 
             creation_context.egui_ctx.set_theme(ThemePreference::Dark);
-            Ok(Box::new(App::new()))
+            Ok(Box::new(App::from_storage(creation_context.storage)))
         }),
     )
+    .map(|()| ExitCode::SUCCESS)
     .map_err(|e| e.into())
 }
 
@@ -75,7 +92,7 @@ fn main() {
                 Box::new(|creation_context| {
                     use eframe::egui::ThemePreference;
                     creation_context.egui_ctx.set_theme(ThemePreference::Dark);
-                    Ok(Box::new(App::new()))
+                    Ok(Box::new(App::from_storage(creation_context.storage)))
                 }),
             )
             .await;
@@ -2,8 +2,12 @@
 
 use std::sync::{Arc, Mutex};
 
+#[cfg(feature = "persistence")]
+use serde::{Deserialize, Serialize};
+
 /// A line of output with optional styling
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "persistence", derive(Deserialize, Serialize))]
 pub(crate) struct OutputLine {
     pub(crate) text: String,
     pub(crate) level: OutputLevel,
@@ -11,6 +15,7 @@ pub(crate) struct OutputLine {
 
 /// Level/category for output lines
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "persistence", derive(Deserialize, Serialize))]
 pub(crate) enum OutputLevel {
     #[default]
     Info,
@@ -83,6 +88,13 @@ impl OutputBuffer {
         self.push(text, OutputLevel::Error);
     }
 
+    /// Append lines, e.g. the history of a previous session
+    pub(crate) fn extend(&self, history: impl IntoIterator<Item = OutputLine>) {
+        if let Ok(mut lines) = self.lines.lock() {
+            lines.extend(history);
+        }
+    }
+
     /// Get all lines
     pub(crate) fn lines(&self) -> Vec<OutputLine> {
         self.lines.lock().map(|l| l.clone()).unwrap_or_default()
@@ -0,0 +1,408 @@
+//! Versioned project files storing a graph tab.
+//!
+//! A project contains the graph with its labels, the texts of the inserter,
+//! the tab name, the selected algorithm and the view: zoom, pan and the
+//! positions of all nodes. Vertex keys change when a graph is rebuilt, so
+//! vertices and node positions are stored by vertex string.
+//!
+//! Files of older versions are upgraded on load by the [`MIGRATIONS`], which
+//! rewrite the JSON one version at a time before it is decoded.
+
+use std::fmt;
+
+use context_trace::{
+    graph::{
+        vertex::key::VertexKey,
+        Hypergraph,
+    },
+    HashMap,
+};
+use eframe::egui::{
+    Pos2,
+    Vec2,
+};
+use ngrams::{
+    graph::{
+        strings::StringGraph,
+        vocabulary::ProcessStatus,
+    },
+    io::{
+        Imported,
+        ValidationError,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    algorithm::Algorithm,
+    graph::{
+        Graph,
+        GraphView,
+    },
+    vis::graph::GraphVis,
+};
+
+/// Format identifier written to the header of every project file
+pub(crate) const PROJECT_FORMAT: &str = "graph-app-project";
+/// Version of the project format, increased on incompatible changes
+pub(crate) const PROJECT_VERSION: u32 = 1;
+
+/// Upgrades of the project format, `MIGRATIONS[i]` turns a file of version
+/// `i + 1` into version `i + 2`. Add an entry when increasing
+/// [`PROJECT_VERSION`].
+const MIGRATIONS: [fn(&mut serde_json::Value); PROJECT_VERSION as usize - 1] =
+    [];
+
+/// Error type for reading project files
+#[derive(Debug)]
+pub(crate) enum ProjectError {
+    /// The file is not valid JSON or misses fields
+    Decode(String),
+    /// The file is not a project file
+    InvalidHeader(String),
+    /// The file was saved with a version which can not be read
+    UnsupportedVersion { found: u32, supported: u32 },
+    /// The stored graph is not a valid hypergraph
+    Invalid(ValidationError),
+    /// The tab is busy with a running task
+    Busy,
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            ProjectError::Decode(e) => write!(f, "Decoding error: {}", e),
+            ProjectError::InvalidHeader(name) =>
+                write!(f, "Not a project file (format {:?})", name),
+            ProjectError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "Unsupported project version {} (supported: {})",
+                found, supported
+            ),
+            ProjectError::Invalid(e) => write!(f, "Invalid graph: {}", e),
+            ProjectError::Busy => write!(f, "The tab is busy with a task"),
+        }
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ProjectHeader {
+    pub(crate) format: String,
+    pub(crate) version: u32,
+}
+
+impl Default for ProjectHeader {
+    fn default() -> Self {
+        Self {
+            format: PROJECT_FORMAT.to_string(),
+            version: PROJECT_VERSION,
+        }
+    }
+}
+
+impl ProjectHeader {
+    fn validate(&self) -> Result<(), ProjectError> {
+        if self.format != PROJECT_FORMAT {
+            Err(ProjectError::InvalidHeader(self.format.clone()))
+        } else if self.version == 0 || self.version > PROJECT_VERSION {
+            Err(ProjectError::UnsupportedVersion {
+                found: self.version,
+                supported: PROJECT_VERSION,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Position of a node on the canvas
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct NodeState {
+    pub(crate) string: String,
+    pub(crate) pos: [f32; 2],
+    pub(crate) manually_moved: bool,
+}
+
+/// Canvas state of a tab
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ViewState {
+    #[serde(default)]
+    pub(crate) graph_view: GraphView,
+    pub(crate) zoom: f32,
+    pub(crate) pan: [f32; 2],
+    #[serde(default)]
+    pub(crate) nodes: Vec<NodeState>,
+}
+
+impl Default for ViewState {
+    fn default() -> Self {
+        Self {
+            graph_view: GraphView::default(),
+            zoom: 1.0,
+            pan: [0.0, 0.0],
+            nodes: Vec::new(),
+        }
+    }
+}
+
+/// A graph tab as stored in a project file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Project {
+    pub(crate) header: ProjectHeader,
+    pub(crate) name: String,
+    pub(crate) algorithm: Algorithm,
+    #[serde(default)]
+    pub(crate) insert_texts: Vec<String>,
+    /// The graph with its labels
+    pub(crate) graph: StringGraph,
+    /// Lattice of the last ngrams run
+    #[serde(default)]
+    pub(crate) containment: Option<StringGraph>,
+    /// Pass reached by a cancelled ngrams run
+    #[serde(default)]
+    pub(crate) partial: Option<ProcessStatus>,
+    #[serde(default)]
+    pub(crate) view: ViewState,
+}
+
+/// The parts of a tab restored from a project
+#[derive(Debug)]
+pub(crate) struct Restored {
+    pub(crate) graph: Graph,
+    pub(crate) view: GraphView,
+    pub(crate) zoom: f32,
+    pub(crate) pan: Vec2,
+    pub(crate) positions: HashMap<VertexKey, (Pos2, bool)>,
+}
+
+fn key_strings(graph: &Hypergraph) -> HashMap<String, VertexKey> {
+    graph
+        .vertex_keys()
+        .map(|key| (graph.vertex_key_string(&key), key))
+        .collect()
+}
+
+impl Project {
+    /// Capture the state of a tab
+    pub(crate) fn capture(
+        name: &str,
+        algorithm: Algorithm,
+        graph: &Graph,
+        vis: &GraphVis,
+    ) -> Self {
+        let labels = graph.labels.read().unwrap().clone();
+        let strings = StringGraph::from_hypergraph(&graph.read(), &labels);
+        let containment =
+            graph.containment.read().unwrap().as_ref().map(|containment| {
+                StringGraph::from_hypergraph(containment, &Default::default())
+            });
+        let viewed = graph.view(vis.view());
+        let nodes = vis
+            .nodes()
+            .map(|node| NodeState {
+                string: viewed.vertex_key_string(&node.key),
+                pos: [node.world_pos.x, node.world_pos.y],
+                manually_moved: node.manually_moved,
+            })
+            .collect();
+        Self {
+            header: ProjectHeader::default(),
+            name: name.to_string(),
+            algorithm,
+            insert_texts: graph.insert_texts.clone(),
+            graph: strings,
+            containment,
+            partial: graph.partial(),
+            view: ViewState {
+                graph_view: vis.view(),
+                zoom: vis.zoom(),
+                pan: [vis.pan().x, vis.pan().y],
+                nodes,
+            },
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("projects always serialize")
+    }
+
+    /// Read a project, checking its header and upgrading older versions
+    pub(crate) fn from_json(json: &str) -> Result<Self, ProjectError> {
+        let decode = |e: serde_json::Error| ProjectError::Decode(e.to_string());
+        let mut value: serde_json::Value =
+            serde_json::from_str(json).map_err(decode)?;
+        let header: ProjectHeader =
+            serde_json::from_value(value["header"].clone()).map_err(decode)?;
+        header.validate()?;
+        for migrate in &MIGRATIONS[header.version as usize - 1..] {
+            migrate(&mut value);
+        }
+        value["header"]["version"] = PROJECT_VERSION.into();
+        serde_json::from_value(value).map_err(decode)
+    }
+
+    /// Build the graph of the project and resolve the node positions
+    pub(crate) fn restore(&self) -> Result<Restored, ProjectError> {
+        let (graph, labels, containment) = match &self.containment {
+            Some(containment) => {
+                // the partition graph shares the keys of the lattice
+                let containment = containment
+                    .build()
+                    .map_err(ProjectError::Invalid)?
+                    .graph;
+                let Imported { graph, labels } = self
+                    .graph
+                    .build_with_keys(&containment)
+                    .map_err(ProjectError::Invalid)?;
+                (graph, labels, Some(containment))
+            },
+            None => {
+                let Imported { graph, labels } =
+                    self.graph.build().map_err(ProjectError::Invalid)?;
+                (graph, labels, None)
+            },
+        };
+        let keys = key_strings(containment.as_ref().unwrap_or(&graph));
+        let positions = self
+            .view
+            .nodes
+            .iter()
+            .filter_map(|node| {
+                let key = *keys.get(&node.string)?;
+                let pos = Pos2::new(node.pos[0], node.pos[1]);
+                Some((key, (pos, node.manually_moved)))
+            })
+            .collect();
+
+        let mut restored = Graph::from(graph);
+        restored.insert_texts = self.insert_texts.clone();
+        *restored.labels.write().unwrap() = labels;
+        *restored.containment.write().unwrap() = containment.map(Into::into);
+        *restored.partial.write().unwrap() = self.partial;
+        Ok(Restored {
+            graph: restored,
+            view: self.view.graph_view,
+            zoom: self.view.zoom,
+            pan: Vec2::new(self.view.pan[0], self.view.pan[1]),
+            positions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::app::GraphTab;
+    use ngrams::{
+        cancellation::Cancellation,
+        graph::{
+            parse_corpus,
+            Corpus,
+            StatusHandle,
+        },
+    };
+
+    fn parsed_graph() -> Graph {
+        let result = parse_corpus(
+            Corpus::new("test_project", ["abab", "abcabc", "babc"]),
+            StatusHandle::default(),
+            Cancellation::None,
+        )
+        .unwrap();
+        let mut graph = Graph::from(result.graph);
+        graph.insert_texts = vec!["abc".to_string()];
+        *graph.labels.write().unwrap() = result.labels;
+        *graph.containment.write().unwrap() = Some(result.containment.into());
+        graph
+    }
+
+    /// Position and moved flag of every node, by vertex string
+    fn node_states(
+        graph: &Graph,
+        vis: &GraphVis,
+    ) -> BTreeMap<String, (Pos2, bool)> {
+        let viewed = graph.view(vis.view());
+        vis.nodes()
+            .map(|node| {
+                (
+                    viewed.vertex_key_string(&node.key),
+                    (node.world_pos, node.manually_moved),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_project_roundtrip() {
+        let graph = parsed_graph();
+        let mut vis = GraphVis::new(graph.clone());
+        vis.update().unwrap();
+        // move a node and the camera
+        let moved = vis.nodes().next().unwrap().key;
+        let pos = Pos2::new(123.0, -45.0);
+        let pan = Vec2::new(10.0, -20.0);
+        vis.restore(
+            vis.view(),
+            2.5,
+            pan,
+            [(moved, (pos, true))].into_iter().collect(),
+        );
+        vis.update().unwrap();
+        let states = node_states(&graph, &vis);
+        assert!(states.values().any(|state| *state == (pos, true)));
+
+        let project =
+            Project::capture("tab", Algorithm::ContextRead, &graph, &vis);
+        let json = project.to_json();
+        let read = Project::from_json(&json).unwrap();
+        assert_eq!(read.name, "tab");
+        assert_eq!(read.algorithm, Algorithm::ContextRead);
+        assert_eq!(read.graph, project.graph);
+
+        let restored = read.restore().unwrap();
+        let labels = restored.graph.labels.read().unwrap().clone();
+        assert_eq!(
+            StringGraph::from_hypergraph(&restored.graph.read(), &labels),
+            project.graph,
+        );
+        assert_eq!(restored.graph.insert_texts, graph.insert_texts);
+        assert!(restored.graph.has_containment());
+
+        // the view of a tab opened from the project
+        let tab = GraphTab::from_project(0, &read).unwrap();
+        let ctx = tab.ctx().unwrap();
+        let mut vis = tab.vis_mut().unwrap();
+        vis.update().unwrap();
+        assert_eq!(vis.zoom(), 2.5);
+        assert_eq!(vis.pan(), pan);
+        assert_eq!(node_states(ctx.graph(), &vis), states);
+    }
+
+    #[test]
+    fn test_project_header() {
+        let graph = parsed_graph();
+        let vis = GraphVis::new(graph.clone());
+        let mut project =
+            Project::capture("tab", Algorithm::default(), &graph, &vis);
+        project.header.version = PROJECT_VERSION + 1;
+        assert!(matches!(
+            Project::from_json(&project.to_json()),
+            Err(ProjectError::UnsupportedVersion { .. })
+        ));
+        project.header.format = "other".to_string();
+        assert!(matches!(
+            Project::from_json(&project.to_json()),
+            Err(ProjectError::InvalidHeader(_))
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+//! Context search queries against the graph of a tab.
+//!
+//! A search finds the largest vertex containing the query, or the longest
+//! prefix of it, without changing the graph. See
+//! `doc/hypergraph-context-model/searching-hyperedges.md`.
+
+use std::fmt;
+
+use context_search::{
+    Find,
+    PathCoverage,
+};
+use context_trace::{
+    graph::{
+        vertex::{
+            has_vertex_index::HasVertexIndex,
+            has_vertex_key::HasVertexKey,
+            key::VertexKey,
+        },
+        HypergraphRef,
+    },
+    IndexRangePath,
+};
+
+use crate::vis::node::SelectionState;
+
+/// Error type of context searches
+#[derive(Debug)]
+pub(crate) enum QueryError {
+    /// There is nothing to search for
+    Empty,
+    /// No vertex matches the beginning of the query
+    NotFound(String),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            QueryError::Empty => write!(f, "Empty query"),
+            QueryError::NotFound(reason) => write!(f, "No match: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// Match of a context search
+#[derive(Debug, Clone)]
+pub(crate) struct QueryResult {
+    pub(crate) query: String,
+    /// Largest vertex containing the matched part of the query
+    pub(crate) root: VertexKey,
+    pub(crate) root_string: String,
+    /// Matched range in a child pattern of `root`, None if all of `root`
+    /// matched
+    pub(crate) selection: Option<SelectionState>,
+    /// Whether the whole query was matched
+    pub(crate) complete: bool,
+}
+
+/// Selection of the children of the root pattern a range path covers
+fn selection(path: &IndexRangePath) -> SelectionState {
+    let start = path.start.sub_path.root_entry;
+    let end = path.end.sub_path.root_entry;
+    SelectionState {
+        pattern_id: path.root.location.pattern_id,
+        range: start..end + 1,
+        trace: path.clone(),
+    }
+}
+
+/// Search `query` in `graph` by its atoms
+pub(crate) fn search(
+    graph: &HypergraphRef,
+    query: &str,
+) -> Result<QueryResult, QueryError> {
+    if query.is_empty() {
+        return Err(QueryError::Empty);
+    }
+    let response = graph
+        .find_sequence(query.chars())
+        .map_err(|reason| QueryError::NotFound(format!("{:?}", reason)))?;
+    let root = response.root_token();
+    let data = graph
+        .get_vertex_data(root.vertex_index())
+        .map_err(|err| QueryError::NotFound(format!("{:?}", err)))?;
+    let selection = match &response.end.path {
+        PathCoverage::Range(end) => Some(selection(&end.path)),
+        _ => None,
+    };
+    Ok(QueryResult {
+        query: query.to_string(),
+        root: data.vertex_key(),
+        root_string: graph.index_string(root.vertex_index()),
+        selection,
+        complete: response.is_complete(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::build_graph1;
+
+    #[test]
+    fn test_context_search() {
+        let graph = HypergraphRef::from(build_graph1());
+
+        let found = search(&graph, "xaby").unwrap();
+        assert!(found.complete);
+        assert_eq!(found.root_string, "xaby");
+        assert!(found.selection.is_none());
+
+        let found = search(&graph, "xabw").unwrap();
+        assert!(!found.complete);
+        assert!(found.root_string.contains("xab"));
+
+        assert!(matches!(search(&graph, ""), Err(QueryError::Empty)));
+    }
+}
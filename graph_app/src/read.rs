@@ -10,6 +10,8 @@ use serde::*;
 use crate::{
     algorithm::Algorithm,
     graph::*,
+    output::OutputBuffer,
+    query,
     task::CancellationHandle,
 };
 
@@ -32,6 +34,40 @@ use std::hash::{
 pub(crate) struct ReadCtx {
     graph: Graph,
     status: Option<StatusHandle>,
+    /// Receives the messages of algorithm runs, logged to the console if unset
+    output: Option<OutputBuffer>,
+    /// Limits of the ngrams parse
+    limits: ParseLimits,
+}
+
+/// Limits of the ngrams parse as edited in the settings
+///
+/// The timeout is stored as a duration and only turned into a deadline
+/// when a parse starts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "persistence", derive(Deserialize, Serialize))]
+pub(crate) struct ParseLimits {
+    /// Seconds after which the parse stops
+    pub(crate) timeout_secs: Option<u64>,
+    /// Maximum number of steps of a single pass
+    pub(crate) max_steps: Option<usize>,
+    /// Maximum number of vocabulary entries
+    pub(crate) max_vocab_entries: Option<usize>,
+}
+
+impl ParseLimits {
+    /// The limits of a parse starting now.
+    pub(crate) fn start(&self) -> ngrams::Limits {
+        let limits = ngrams::Limits {
+            max_steps: self.max_steps,
+            max_vocab_entries: self.max_vocab_entries,
+            ..Default::default()
+        };
+        match self.timeout_secs {
+            Some(secs) => limits.timeout(std::time::Duration::from_secs(secs)),
+            None => limits,
+        }
+    }
 }
 
 impl ReadCtx {
@@ -40,9 +76,27 @@ impl ReadCtx {
         Self {
             graph,
             status: None,
+            output: None,
+            limits: ParseLimits::default(),
         }
     }
 
+    /// Send the messages of algorithm runs to `output`.
+    pub(crate) fn set_output(
+        &mut self,
+        output: OutputBuffer,
+    ) {
+        self.output = Some(output);
+    }
+
+    /// Stop the following ngrams parses at `limits`.
+    pub(crate) fn set_limits(
+        &mut self,
+        limits: ParseLimits,
+    ) {
+        self.limits = limits;
+    }
+
     /// Get the current status handle, if any.
     pub(crate) fn status(&self) -> Option<&StatusHandle> {
         self.status.as_ref()
@@ -59,6 +113,52 @@ impl ReadCtx {
     }
 }
 
+// ============================================================================
+// Messages
+// ============================================================================
+
+impl ReadCtx {
+    fn info(
+        &self,
+        msg: &str,
+    ) {
+        match &self.output {
+            Some(output) => output.info(msg),
+            None => log_info(msg),
+        }
+    }
+
+    fn success(
+        &self,
+        msg: &str,
+    ) {
+        match &self.output {
+            Some(output) => output.success(msg),
+            None => log_info(msg),
+        }
+    }
+
+    fn warn(
+        &self,
+        msg: &str,
+    ) {
+        match &self.output {
+            Some(output) => output.warn(msg),
+            None => log_info(msg),
+        }
+    }
+
+    fn error(
+        &self,
+        msg: &str,
+    ) {
+        match &self.output {
+            Some(output) => output.error(msg),
+            None => log_error(msg),
+        }
+    }
+}
+
 // ============================================================================
 // Algorithm execution - unified interface
 // ============================================================================
@@ -72,7 +172,11 @@ impl ReadCtx {
         algorithm: Algorithm,
         cancellation: CancellationHandle,
     ) {
-        log_info(&format!("Running algorithm: {:?}", algorithm));
+        self.info(&format!("Running algorithm: {:?}", algorithm));
+        if algorithm.mutates_graph() {
+            // the match would point into the old graph
+            *self.graph.query_result.write().unwrap() = None;
+        }
 
         match algorithm {
             Algorithm::NgramsParseCorpus => {
@@ -84,9 +188,12 @@ impl ReadCtx {
             Algorithm::ContextInsert => {
                 self.run_context_insert(&cancellation).await;
             },
+            Algorithm::ContextSearch => {
+                self.run_context_search(&cancellation).await;
+            },
         }
 
-        log_info("Task done.");
+        self.info("Task done.");
     }
 
     /// Run ngrams::parse_corpus algorithm.
@@ -101,10 +208,12 @@ impl ReadCtx {
             StatusHandle,
         };
 
-        log_info("Starting ngrams parse corpus...");
+        self.info("Starting ngrams parse corpus...");
 
         let graph = self.graph.graph.clone();
         let labels = self.graph.labels.clone();
+        let partial = self.graph.partial.clone();
+        let containment = self.graph.containment.clone();
         let insert_texts = self.graph.insert_texts.clone();
 
         // Guard against empty corpus
@@ -115,11 +224,11 @@ impl ReadCtx {
             .collect();
 
         if non_empty_texts.is_empty() {
-            log_info("No text to parse (insert_texts is empty)");
+            self.warn("No text to parse (insert_texts is empty)");
             return;
         }
 
-        log_info(&format!("Insert texts: {:?}", non_empty_texts));
+        self.info(&format!("Insert texts: {:?}", non_empty_texts));
 
         let status = StatusHandle::from(Status::new(non_empty_texts.clone()));
         self.status = Some(status.clone());
@@ -131,28 +240,66 @@ impl ReadCtx {
         };
         let corpus = Corpus::new(corpus_name.clone(), non_empty_texts);
 
-        log_info(&format!("Created corpus: {}", corpus_name));
+        self.info(&format!("Created corpus: {}", corpus_name));
 
         // Execute the parse - platform-specific
         let result = self
             .execute_parse_corpus(corpus, status, cancellation)
             .await;
+        // labels published while paused are replaced by the result
+        self.graph.paused_labels.write().unwrap().take();
 
         match result {
+            // The labels refer to the keys of the lattice and the partition
+            // graph, so the three are only installed together
+            Ok(res) if !res.has_graph() => {
+                self.warn(&format!(
+                    "Parse stopped in the {:?} pass before the partition \
+                     graph, the graph is unchanged",
+                    res.partial.unwrap_or_default(),
+                ));
+                let mut found: Vec<String> = res
+                    .labels
+                    .iter()
+                    .map(|key| res.containment.vertex_key_string(key))
+                    .collect();
+                found.sort();
+                self.info(&format!("Labels found so far: {:?}", found));
+            },
             Ok(res) => {
-                self.graph.insert_texts.clear();
+                match res.partial {
+                    Some(pass) => self.warn(&format!(
+                        "Parse stopped in the {:?} pass, showing the partial \
+                         result",
+                        pass
+                    )),
+                    None => {
+                        self.graph.insert_texts.clear();
+                        self.success(
+                            "Ngrams parse corpus completed successfully",
+                        );
+                    },
+                }
                 *graph.write().unwrap() = res.graph.into();
                 *labels.write().unwrap() = res.labels;
-                log_info("Ngrams parse corpus completed successfully");
+                *partial.write().unwrap() = res.partial;
+                *containment.write().unwrap() = Some(res.containment.into());
             },
             Err(CancelReason::Cancelled) => {
-                log_info("Parse operation was cancelled");
+                self.warn("Parse operation was cancelled");
             },
             Err(CancelReason::Error) => {
-                log_error("Parse operation encountered an error");
+                self.error("Parse operation encountered an error");
             },
             Err(CancelReason::EmptyVocabulary) => {
-                log_info("Parse operation cancelled: empty vocabulary");
+                self.warn("Parse operation cancelled: empty vocabulary");
+            },
+            Err(
+                reason @ (CancelReason::DeadlineExceeded
+                | CancelReason::StepLimitExceeded
+                | CancelReason::MemoryLimitExceeded),
+            ) => {
+                self.error(&format!("Parse operation stopped: {:?}", reason));
             },
         }
     }
@@ -168,10 +315,13 @@ impl ReadCtx {
         let combined_text: String = insert_texts.join("");
 
         if combined_text.is_empty() {
-            log_info("No text to read");
+            self.warn("No text to read");
             return;
         }
 
+        // the graph changes in place while reading
+        self.graph.clear_ngrams_state();
+
         let mut read_ctx = context_read::context::ReadCtx::new(
             graph_ref.clone(),
             combined_text.chars(),
@@ -195,11 +345,11 @@ impl ReadCtx {
         }
 
         if cancellation.is_cancelled() {
-            log_info("Context read operation was cancelled");
+            self.warn("Context read operation was cancelled");
         } else {
             *self.graph.write() = graph_ref;
             self.graph.insert_texts.clear();
-            log_info("Context read completed successfully");
+            self.success("Context read completed successfully");
         }
     }
 
@@ -211,6 +361,9 @@ impl ReadCtx {
         let graph_ref: HypergraphRef = self.graph.read().clone();
         let insert_texts = self.graph.insert_texts.clone();
 
+        // the graph changes in place while inserting
+        self.graph.clear_ngrams_state();
+
         let mut insert_ctx =
             context_insert::InsertCtx::<Token>::from(graph_ref.clone());
 
@@ -219,7 +372,7 @@ impl ReadCtx {
 
         for (i, text) in insert_texts.iter().enumerate() {
             if cancellation.is_cancelled() {
-                log_info("Context insert operation was cancelled");
+                self.warn("Context insert operation was cancelled");
                 return;
             }
 
@@ -227,17 +380,17 @@ impl ReadCtx {
                 continue;
             }
 
-            log_info(&format!("Inserting text {}: {}", i + 1, text));
+            self.info(&format!("Inserting text {}: {}", i + 1, text));
 
             // Get tokens for insertion
             let tokens = self.get_tokens_for_text(&graph_ref, text);
 
             match insert_ctx.insert(tokens) {
                 Ok(_result) => {
-                    log_info(&format!("Inserted: {}", text));
+                    self.info(&format!("Inserted: {}", text));
                 },
                 Err(err) => {
-                    log_error(&format!(
+                    self.error(&format!(
                         "Error inserting '{}': {:?}",
                         text, err
                     ));
@@ -251,11 +404,57 @@ impl ReadCtx {
         if !cancellation.is_cancelled() {
             *self.graph.write() = graph_ref;
             self.graph.insert_texts.clear();
-            log_info("Context insert completed successfully");
+            self.success("Context insert completed successfully");
         }
     }
 }
 
+impl ReadCtx {
+    /// Run context-search, keeping the match to show it on the canvas.
+    ///
+    /// The graph is not changed and the texts are kept to refine the query.
+    async fn run_context_search(
+        &mut self,
+        cancellation: &CancellationHandle,
+    ) {
+        let query: String = self.graph.insert_texts.join("");
+        if query.is_empty() {
+            self.warn("No query to search");
+            return;
+        }
+
+        // Yield before starting (wasm only)
+        yield_if_wasm().await;
+        if cancellation.is_cancelled() {
+            self.warn("Context search operation was cancelled");
+            return;
+        }
+
+        let graph_ref: HypergraphRef = self.graph.read().clone();
+        let result = match query::search(&graph_ref, &query) {
+            Ok(found) => {
+                if found.complete {
+                    self.success(&format!(
+                        "Found {:?} in {}",
+                        query, found.root_string
+                    ));
+                } else {
+                    self.warn(&format!(
+                        "Partial match of {:?}, largest match in {}",
+                        query, found.root_string
+                    ));
+                }
+                Some(found)
+            },
+            Err(err) => {
+                self.error(&format!("Search for {:?} failed: {}", query, err));
+                None
+            },
+        };
+        *self.graph.query_result.write().unwrap() = result;
+    }
+}
+
 // ============================================================================
 // Platform-specific implementations
 // ============================================================================
@@ -274,18 +473,26 @@ impl ReadCtx {
     > {
         use ngrams::Cancellation;
 
-        let ngrams_cancellation = Cancellation::from(cancellation.token());
+        let ngrams_cancellation = Cancellation::from(cancellation.token())
+            .with_pause_flag(cancellation.pause_flag())
+            .with_limits(self.limits.start());
+        let options = partial_options(&self.graph);
 
         // Run in blocking task pool
         let result = tokio::task::spawn_blocking(move || {
-            ngrams::graph::parse_corpus(corpus, status, ngrams_cancellation)
+            ngrams::graph::parse_corpus_with_options(
+                corpus,
+                status,
+                ngrams_cancellation,
+                options,
+            )
         })
         .await;
 
         match result {
             Ok(res) => res,
             Err(join_error) => {
-                log_error(&format!("Parse task panicked: {:?}", join_error));
+                self.error(&format!("Parse task panicked: {:?}", join_error));
                 Err(ngrams::graph::traversal::pass::CancelReason::Error)
             },
         }
@@ -315,13 +522,21 @@ impl ReadCtx {
     > {
         use ngrams::cancellation::Cancellation;
 
-        let ngrams_cancellation = Cancellation::from(cancellation.flag());
+        // The pause flag is not shared: waiting on the main thread would
+        // block the UI which has to resume the run.
+        let ngrams_cancellation = Cancellation::from(cancellation.flag())
+            .with_limits(self.limits.start());
 
         // Yield to allow UI to update before starting heavy work
         yield_if_wasm().await;
 
         // Run synchronously (wasm is single-threaded)
-        ngrams::graph::parse_corpus(corpus, status, ngrams_cancellation)
+        ngrams::graph::parse_corpus_with_options(
+            corpus,
+            status,
+            ngrams_cancellation,
+            partial_options(&self.graph),
+        )
     }
 
     /// Get tokens for text insertion.
@@ -344,6 +559,13 @@ impl ReadCtx {
 // Cross-platform utilities
 // ============================================================================
 
+/// Parse options keeping the partial result when the run is cancelled.
+fn partial_options(graph: &Graph) -> ngrams::graph::ParseOptions {
+    ngrams::graph::ParseOptions::default()
+        .partial(true)
+        .publish_paused(graph.paused_labels.clone())
+}
+
 /// Log an info message.
 #[cfg(not(target_arch = "wasm32"))]
 fn log_info(msg: &str) {
@@ -1,6 +1,5 @@
 //! Cross-platform cancellation handle.
 
-#[cfg(target_arch = "wasm32")]
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc,
@@ -34,6 +33,8 @@ pub(crate) struct CancellationHandle {
     token: CancellationToken,
     #[cfg(target_arch = "wasm32")]
     flag: Arc<AtomicBool>,
+    /// Shared with the running task, which waits while it is set
+    paused: Arc<AtomicBool>,
 }
 
 impl CancellationHandle {
@@ -44,6 +45,7 @@ impl CancellationHandle {
             token: CancellationToken::new(),
             #[cfg(target_arch = "wasm32")]
             flag: Arc::new(AtomicBool::new(false)),
+            paused: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -66,6 +68,26 @@ impl CancellationHandle {
         return self.flag.load(Ordering::SeqCst);
     }
 
+    /// Request the task to wait before its next step.
+    pub(crate) fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Let a paused task continue.
+    pub(crate) fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Check if a pause has been requested.
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Get the pause flag shared with the task.
+    pub(crate) fn pause_flag(&self) -> Arc<AtomicBool> {
+        self.paused.clone()
+    }
+
     /// Get the native cancellation token (native only).
     ///
     /// This is useful for integrating with tokio's cancellation utilities.
@@ -87,6 +109,7 @@ impl CancellationHandle {
     pub(crate) fn child(&self) -> Self {
         Self {
             token: self.token.child_token(),
+            paused: self.paused.clone(),
         }
     }
 
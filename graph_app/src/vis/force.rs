@@ -0,0 +1,230 @@
+//! Force-directed layout refined over frames.
+//!
+//! Starts from the grid layout and moves the nodes by Fruchterman-Reingold
+//! forces: edges pull parents and children together, nearby nodes repel
+//! each other and a vertical bias keeps wider vertices above narrower ones.
+//! Nodes moved by hand are pinned but still push and pull the others. The
+//! step size cools down until the layout is stable.
+//!
+//! Repulsion is cut off at a range. The node centers are bucketed into grid
+//! cells of that size, so each node only visits the cells around its own
+//! instead of all other nodes.
+
+use context_trace::{
+    graph::vertex::wide::Wide,
+    HashMap,
+};
+use eframe::egui::Vec2;
+use petgraph::{
+    graph::DiGraph,
+    visit::EdgeRef,
+};
+
+use super::node::NodeVis;
+
+/// Preferred distance between connected node centers
+const IDEAL_DISTANCE: f32 = 250.0;
+/// Distance between node centers beyond which nodes do not repel
+const REPULSION_RANGE: f32 = 2.0 * IDEAL_DISTANCE;
+/// Vertical distance between vertices of neighbouring widths
+const LEVEL_HEIGHT: f32 = 200.0;
+/// Strength of the pull towards the level of a vertex
+const VERTICAL_BIAS: f32 = 0.5;
+/// Largest displacement of the first step
+const START_TEMPERATURE: f32 = 100.0;
+/// Factor reducing the displacement after each step
+const COOLING: f32 = 0.97;
+/// Displacement below which the layout is considered stable
+const MIN_TEMPERATURE: f32 = 0.5;
+/// Steps run per frame
+const STEPS_PER_FRAME: usize = 5;
+
+/// Grid cell of a node center, cells are as wide as the repulsion range
+fn cell(center: Vec2) -> (i32, i32) {
+    (
+        (center.x / REPULSION_RANGE).floor() as i32,
+        (center.y / REPULSION_RANGE).floor() as i32,
+    )
+}
+
+/// Indices of the node centers in each grid cell
+fn buckets(centers: &[Vec2]) -> HashMap<(i32, i32), Vec<usize>> {
+    let mut cells: HashMap<(i32, i32), Vec<usize>> = HashMap::default();
+    for (i, center) in centers.iter().enumerate() {
+        cells.entry(cell(*center)).or_default().push(i);
+    }
+    cells
+}
+
+#[derive(Debug)]
+pub(crate) struct ForceLayout {
+    temperature: f32,
+}
+
+impl Default for ForceLayout {
+    fn default() -> Self {
+        Self {
+            temperature: START_TEMPERATURE,
+        }
+    }
+}
+
+impl ForceLayout {
+    pub(crate) fn is_running(&self) -> bool {
+        self.temperature > MIN_TEMPERATURE
+    }
+
+    /// Continue moving after nodes were dragged
+    pub(crate) fn reheat(&mut self) {
+        self.temperature = self.temperature.max(START_TEMPERATURE / 4.0);
+    }
+
+    /// Run the steps of one frame, returns whether the layout still moves
+    pub(crate) fn run_frame(
+        &mut self,
+        graph: &mut DiGraph<NodeVis, ()>,
+    ) -> bool {
+        for _ in 0..STEPS_PER_FRAME {
+            if !self.is_running() {
+                break;
+            }
+            self.step(graph);
+        }
+        self.is_running()
+    }
+
+    fn step(
+        &mut self,
+        graph: &mut DiGraph<NodeVis, ()>,
+    ) {
+        let centers: Vec<Vec2> = graph
+            .node_weights()
+            .map(|node| node.world_pos.to_vec2() + node.cached_size / 2.0)
+            .collect();
+        let mut displacement = vec![Vec2::ZERO; centers.len()];
+
+        // nodes in range repel each other, all of them are in the cells
+        // next to the cell of a node
+        let cells = buckets(&centers);
+        for i in 0..centers.len() {
+            let (x, y) = cell(centers[i]);
+            let near = (x - 1..=x + 1)
+                .flat_map(|x| (y - 1..=y + 1).map(move |y| (x, y)))
+                .filter_map(|cell| cells.get(&cell))
+                .flatten()
+                .copied();
+            for j in near {
+                if j <= i {
+                    continue;
+                }
+                let delta = centers[i] - centers[j];
+                let distance = delta.length().max(1.0);
+                if distance > REPULSION_RANGE {
+                    continue;
+                }
+                let force =
+                    delta / distance * (IDEAL_DISTANCE.powi(2) / distance);
+                displacement[i] += force;
+                displacement[j] -= force;
+            }
+        }
+
+        // edges pull parents and children together
+        for edge in graph.edge_references() {
+            let source = edge.source().index();
+            let target = edge.target().index();
+            let delta = centers[source] - centers[target];
+            let distance = delta.length().max(1.0);
+            let force = delta / distance * (distance.powi(2) / IDEAL_DISTANCE);
+            displacement[source] -= force;
+            displacement[target] += force;
+        }
+
+        // wider vertices are pulled above narrower ones
+        let mut widths: Vec<usize> =
+            graph.node_weights().map(|node| node.data.width()).collect();
+        widths.sort_unstable_by(|a, b| b.cmp(a));
+        widths.dedup();
+        for (i, node) in graph.node_weights().enumerate() {
+            let level = widths
+                .iter()
+                .position(|width| *width == node.data.width())
+                .unwrap_or(0);
+            let target_y = level as f32 * LEVEL_HEIGHT;
+            displacement[i].y += (target_y - centers[i].y) * VERTICAL_BIAS;
+        }
+
+        for (node, displacement) in graph.node_weights_mut().zip(displacement) {
+            if node.manually_moved {
+                continue;
+            }
+            let length = displacement.length();
+            if length > 0.0 {
+                node.world_pos +=
+                    displacement / length * length.min(self.temperature);
+            }
+        }
+        self.temperature *= COOLING;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use eframe::egui::Pos2;
+    use petgraph::graph::NodeIndex;
+
+    use super::*;
+    use crate::{
+        examples::build_graph1,
+        graph::{
+            Graph,
+            GraphView,
+        },
+    };
+
+    /// Nodes of all vertices of an example graph, close to each other
+    fn crowded_nodes() -> DiGraph<NodeVis, ()> {
+        let graph = Graph::from(build_graph1());
+        let keys: Vec<_> = graph.read().vertex_keys().collect();
+        let mut nodes = DiGraph::new();
+        for (i, key) in keys.iter().enumerate() {
+            let data = graph.read().expect_vertex_data(*key);
+            let pos = Pos2::new(10.0 * i as f32, 0.0);
+            nodes.add_node(NodeVis::new(
+                graph.clone(),
+                NodeIndex::new(i),
+                key,
+                &data,
+                pos,
+                0,
+                GraphView::default(),
+            ));
+        }
+        nodes
+    }
+
+    #[test]
+    fn test_force_layout_pins_and_cools() {
+        let mut nodes = crowded_nodes();
+        let pinned = NodeIndex::new(0);
+        nodes[pinned].manually_moved = true;
+        let start: Vec<Pos2> =
+            nodes.node_weights().map(|node| node.world_pos).collect();
+
+        let mut layout = ForceLayout::default();
+        let mut frames = 0;
+        while layout.run_frame(&mut nodes) {
+            frames += 1;
+            assert!(frames < 1000, "the layout does not cool down");
+        }
+        assert!(!layout.is_running());
+
+        assert_eq!(nodes[pinned].world_pos, start[0]);
+        // the crowded nodes were pushed apart
+        assert!(nodes
+            .node_weights()
+            .zip(&start)
+            .skip(1)
+            .any(|(node, start)| node.world_pos != *start));
+    }
+}
@@ -1,12 +1,12 @@
 use context_trace::{
     graph::vertex::{
-        has_vertex_key::HasVertexKey,
         key::VertexKey,
         location::pattern::PatternLocation,
         wide::Wide,
     },
     End,
     HashMap,
+    HashSet,
     IndexRangePath,
     IndexRoot,
     RolePath,
@@ -27,20 +27,45 @@ use eframe::egui::{
 use petgraph::{
     graph::{
         DiGraph,
+        EdgeIndex,
         NodeIndex,
     },
     visit::EdgeRef,
+    Direction,
 };
 
 use std::f32::consts::PI;
 
 use super::node::NodeVis;
 use crate::{
-    graph::Graph,
+    graph::{
+        Graph,
+        GraphView,
+    },
     vis::{
-        layout::GraphLayout,
-        node::SelectionState,
+        force::ForceLayout,
+        layout::{
+            layered::Layering,
+            GraphLayout,
+            LayoutKind,
+        },
+        node::{
+            Detail,
+            Highlight,
+            Interaction,
+            SelectionState,
+        },
+        rebuild::{
+            rebuild,
+            Keep,
+            PendingRebuild,
+            Rebuild,
+            RebuildInput,
+            BACKGROUND_THRESHOLD,
+        },
+        spatial::SpatialIndex,
     },
+    task::CancellationHandle,
 };
 
 /// Response from showing the graph
@@ -50,6 +75,8 @@ pub(crate) struct GraphResponse {
     pub(crate) clicked_node: Option<VertexKey>,
     /// Whether the background was clicked (no node clicked)
     pub(crate) background_clicked: bool,
+    /// Why the nodes could not be rebuilt, to report in the output
+    pub(crate) error: Option<String>,
 }
 
 #[derive(Debug)]
@@ -65,6 +92,33 @@ pub(crate) struct GraphVis {
     zoom: f32,
     /// Pan offset
     pan: Vec2,
+    /// Which graph of the handle is shown
+    view: GraphView,
+    /// Positions and moved flags to apply on the next rebuild
+    restore: HashMap<VertexKey, (Pos2, bool)>,
+    /// Vertices matched by the search
+    highlight: HashSet<VertexKey>,
+    /// The selected search match
+    current_match: Option<VertexKey>,
+    /// Node to center in the viewport on the next frame
+    focus: Option<VertexKey>,
+    /// Layout used when the graph is rebuilt
+    layout_kind: LayoutKind,
+    /// Running force-directed layout
+    force: Option<ForceLayout>,
+    /// How the layered layout assigns layers
+    layering: Layering,
+    /// Bend points of edges routed by the layered layout, in world
+    /// coordinates
+    bends: HashMap<EdgeIndex, Vec<Pos2>>,
+    /// What the next rebuild keeps of the shown nodes
+    keep: Keep,
+    /// Rebuild running in the background
+    rebuild: Option<PendingRebuild>,
+    /// Node rects for culling and hit-testing, None after nodes moved
+    index: Option<SpatialIndex>,
+    /// Node dragged with the primary button
+    dragging: Option<NodeIndex>,
 }
 
 impl Default for GraphVis {
@@ -77,6 +131,19 @@ impl Default for GraphVis {
             generation: 0,
             zoom: 1.0,
             pan: Vec2::ZERO,
+            view: GraphView::default(),
+            restore: HashMap::default(),
+            highlight: HashSet::default(),
+            current_match: None,
+            focus: None,
+            layout_kind: LayoutKind::default(),
+            force: None,
+            layering: Layering::default(),
+            index: None,
+            dragging: None,
+            bends: HashMap::default(),
+            keep: Keep::default(),
+            rebuild: None,
         }
     }
 }
@@ -90,118 +157,131 @@ pub(crate) enum UpdateError {
 }
 use UpdateError::*;
 impl GraphVis {
-    #[cfg(not(target_arch = "wasm32"))]
-    fn update_rerun(
-        &mut self,
-        handle: &Graph,
-    ) -> Result<(), UpdateError> {
-        let rec = handle.rec.as_ref().ok_or(NoRecordingStream)?;
-        rec.log_static("/graph", &self.layout.re_nodes())
-            .map_err(Stream)?;
-
-        rec.log_static("/graph", &self.layout.re_edges())
-            .map_err(Stream)?;
-        Ok(())
+    /// Snapshot of the canvas to rebuild from
+    fn rebuild_input(&self) -> Result<RebuildInput, UpdateError> {
+        let handle = self.graph().ok_or(NotInitialized)?;
+        let (previous, previous_bends) = if self.keep == Keep::Nothing {
+            Default::default()
+        } else {
+            let nodes = self
+                .graph
+                .node_weights()
+                .map(|node| (node.key, node.clone()))
+                .collect();
+            let bends = self
+                .graph
+                .edge_references()
+                .filter_map(|edge| {
+                    let route = self.bends.get(&edge.id())?.clone();
+                    let source = self.graph[edge.source()].key;
+                    let target = self.graph[edge.target()].key;
+                    Some(((source, target), route))
+                })
+                .collect();
+            (nodes, bends)
+        };
+        Ok(RebuildInput {
+            handle,
+            view: self.view,
+            generation: self.generation + 1,
+            keep: self.keep,
+            previous,
+            saved_positions: self.restore.clone(),
+            previous_bends,
+            layered: (self.layout_kind == LayoutKind::Layered)
+                .then_some(self.layering),
+        })
     }
 
+    /// Rebuild the nodes on the calling thread
     pub(crate) fn update(&mut self) -> Result<(), UpdateError> {
-        let handle = self.graph().ok_or(NotInitialized)?;
-        let cg = handle.read();
-
-        let pg = cg.to_petgraph().filter_map_owned(
-            |_idx, (_index, node)| {
-                if node.data.width() <= 1 {
-                    None
-                } else {
-                    Some((node.data.vertex_key(), node))
-                }
-            },
-            |_idx, e| (e.token.width() > 1).then_some(()),
-        );
-
-        // Save manually moved positions before regenerating
-        let saved_positions: HashMap<VertexKey, Pos2> = self
-            .graph
-            .node_weights()
-            .filter(|n| n.manually_moved)
-            .map(|n| (n.key, n.world_pos))
-            .collect();
-
-        // Increment generation to reset window positions
-        self.generation += 1;
+        let input = self.rebuild_input()?;
+        if let Some(rebuild) = rebuild(input, &CancellationHandle::new()) {
+            self.apply_rebuild(rebuild);
+        }
+        Ok(())
+    }
 
-        // Regenerate layout
-        self.layout = GraphLayout::generate(&cg, pg);
-        // Clear the old graph to force rebuild in update_egui
-        self.graph = DiGraph::new();
+    /// Rebuild small graphs right away and large ones in the background,
+    /// replacing a rebuild which is still running
+    fn start_update(
+        &mut self,
+        ctx: &egui::Context,
+    ) -> Result<(), UpdateError> {
+        let large = self.graph().is_some_and(|handle| {
+            handle.read().vertex_count() >= BACKGROUND_THRESHOLD
+        });
+        if large {
+            let input = self.rebuild_input()?;
+            self.rebuild = Some(PendingRebuild::spawn(input, ctx.clone()));
+            Ok(())
+        } else {
+            self.rebuild = None;
+            self.update()
+        }
+    }
 
-        // Try to update rerun, but don't fail if it's not available (native only)
-        #[cfg(not(target_arch = "wasm32"))]
-        let _ = self.update_rerun(&handle);
-        self.update_egui(&handle, &saved_positions);
+    /// Apply a background rebuild once it is done, fails if the task
+    /// ended without a result
+    fn poll_rebuild(&mut self) -> Result<(), String> {
+        let Some(pending) = &self.rebuild else {
+            return Ok(());
+        };
+        // check first, the result is stored before the task finishes
+        let finished = pending.is_finished();
+        if let Some(rebuild) = pending.take() {
+            self.rebuild = None;
+            self.apply_rebuild(rebuild);
+        } else if finished {
+            self.rebuild = None;
+            return Err("Rebuilding the graph failed".to_string());
+        }
         Ok(())
     }
-    fn update_egui(
+
+    /// Show the rebuilt nodes, keeping nodes moved since it started
+    fn apply_rebuild(
         &mut self,
-        handle: &Graph,
-        saved_positions: &HashMap<VertexKey, Pos2>,
+        rebuild: Rebuild,
     ) {
-        let generation = self.generation;
-        if !self.initialized() {
-            self.graph = self.layout.graph.clone().map_owned(
-                |i, (k, n)| {
-                    // Use saved position if this node was manually moved, otherwise use layout position
-                    let (pos, manually_moved) =
-                        if let Some(&saved_pos) = saved_positions.get(&k) {
-                            (saved_pos, true)
-                        } else {
-                            let pos = self
-                                .layout
-                                .positions
-                                .get(&i)
-                                .copied()
-                                .unwrap_or_default();
-                            (pos, false)
-                        };
-                    let mut vis = NodeVis::new(
-                        handle.clone(),
-                        i,
-                        &k,
-                        &n.data,
-                        pos,
-                        generation,
-                    );
-                    vis.manually_moved = manually_moved;
-                    vis
-                },
-                |_, e| e,
-            );
+        let Rebuild {
+            layout,
+            mut graph,
+            bends,
+        } = rebuild;
+        let mut moved_since = Vec::new();
+        if self.keep != Keep::Nothing {
+            let moved: HashMap<VertexKey, Pos2> = self
+                .graph
+                .node_weights()
+                .filter(|node| node.manually_moved)
+                .map(|node| (node.key, node.world_pos))
+                .collect();
+            for idx in graph.node_indices() {
+                let node = &mut graph[idx];
+                if let Some(pos) = moved.get(&node.key) {
+                    if !node.manually_moved {
+                        moved_since.push(idx);
+                    }
+                    node.world_pos = *pos;
+                    node.manually_moved = true;
+                }
+            }
         }
-        for (_key, (i, node)) in self.layout.nodes.iter_mut() {
-            // Use saved position if this node was manually moved
-            let (pos, manually_moved) = if let Some(&saved_pos) =
-                saved_positions.get(_key)
-            {
-                (saved_pos, true)
-            } else {
-                let pos =
-                    self.layout.positions.get(i).copied().unwrap_or_default();
-                (pos, false)
-            };
-            if let Some(old) = self.graph.node_weight_mut(*i) {
-                *old = NodeVis::from_old(old, *i, &node.data);
-            } else {
-                let mut vis = NodeVis::new(
-                    handle.clone(),
-                    *i,
-                    _key,
-                    &node.data,
-                    pos,
-                    generation,
-                );
-                vis.manually_moved = manually_moved;
-                *i = self.graph.add_node(vis);
-            };
+        // Increment generation to reset window positions
+        self.generation += 1;
+        self.layout = layout;
+        self.graph = graph;
+        self.keep = Keep::default();
+        self.restore.clear();
+        self.force = match self.layout_kind {
+            LayoutKind::Force => Some(ForceLayout::default()),
+            LayoutKind::Grid | LayoutKind::Layered => None,
+        };
+        self.bends = bends;
+        self.index = None;
+        for idx in moved_since {
+            self.remove_bends(idx);
         }
     }
 
@@ -230,13 +310,16 @@ impl GraphVis {
     ) -> GraphResponse {
         let mut response = GraphResponse::default();
 
-        // Update if not initialized OR if marked dirty
-        if !self.initialized() || self.dirty {
-            if let Err(err) = self.update() {
+        // Update if never built OR if marked dirty, showing the old nodes
+        // while large graphs are rebuilt
+        let never_built = self.generation == 0 && self.rebuild.is_none();
+        if (never_built || self.dirty) && self.handle.is_some() {
+            if let Err(err) = self.start_update(ui.ctx()) {
                 println!("Error updating graph: {:?}", err);
             }
             self.dirty = false;
         }
+        response.error = self.poll_rebuild().err();
         let _events = self.poll_events();
 
         // Get the available rect for the graph viewport
@@ -253,6 +336,27 @@ impl GraphVis {
         // Draw subtle grid
         self.draw_grid(&painter, viewport_rect);
 
+        // Move the nodes of a force-directed layout a bit further
+        if let Some(force) = &mut self.force {
+            if force.is_running() {
+                if force.run_frame(&mut self.graph) {
+                    ui.ctx().request_repaint();
+                }
+                self.index = None;
+            }
+        }
+
+        // Center a focused node, zooming in to make it readable
+        if let Some(key) = self.focus.take() {
+            if let Some(node) = self.graph.node_weights().find(|n| n.key == key)
+            {
+                let center = node.world_pos + node.cached_size / 2.0;
+                self.zoom = self.zoom.max(1.0);
+                self.pan =
+                    viewport_rect.size() / 2.0 - center.to_vec2() * self.zoom;
+            }
+        }
+
         // Handle zoom with scroll wheel
         let hover_pos = ui.input(|i| i.pointer.hover_pos());
         let hovering_graph = hover_pos
@@ -282,6 +386,16 @@ impl GraphVis {
             self.pan += delta;
         }
 
+        if self.rebuild.is_some() {
+            painter.text(
+                viewport_rect.left_top() + vec2(10.0, 10.0),
+                egui::Align2::LEFT_TOP,
+                "Updating layout...",
+                egui::FontId::proportional(12.0),
+                Color32::from_rgb(150, 150, 150),
+            );
+        }
+
         // Show zoom level and pan info
         painter.text(
             viewport_rect.right_top() + vec2(-10.0, 10.0),
@@ -295,34 +409,79 @@ impl GraphVis {
         let zoom = self.zoom;
         let pan = self.pan;
         let viewport_min = viewport_rect.min;
+        let detail = Detail::for_zoom(zoom);
+        let to_world = |screen_pos: Pos2| {
+            ((screen_pos - viewport_min - pan) / zoom).to_pos2()
+        };
+        let to_screen = |world_rect: Rect| {
+            Rect::from_min_size(
+                viewport_min + world_rect.min.to_vec2() * zoom + pan,
+                world_rect.size() * zoom,
+            )
+        };
+
+        // Hit-test the pointer against the node rects in world coordinates
+        let graph = &self.graph;
+        let index = self.index.get_or_insert_with(|| {
+            SpatialIndex::new(graph.node_weights().map(NodeVis::world_rect))
+        });
+        let hovered_node = hover_pos
+            .filter(|_| hovering_graph)
+            .and_then(|pos| index.at(to_world(pos)));
+        if canvas_response.drag_started_by(egui::PointerButton::Primary) {
+            self.dragging = ui
+                .input(|i| i.pointer.press_origin())
+                .and_then(|pos| index.at(to_world(pos)));
+        } else if !canvas_response.dragged_by(egui::PointerButton::Primary) {
+            self.dragging = None;
+        }
+        let dragged_node = self.dragging;
+        let clicked_node = hovered_node
+            .filter(|_| canvas_response.clicked())
+            .map(|idx| self.graph[idx].key);
+
+        // First pass: render the nodes in the viewport
+        let visible = index.query(Rect::from_min_max(
+            to_world(viewport_rect.min),
+            to_world(viewport_rect.max),
+        ));
 
-        // First pass: render nodes and collect their screen rects
-        let mut node_screen_rects: HashMap<NodeIndex, Rect> =
-            HashMap::default();
-        let mut dragged_node: Option<NodeIndex> = None;
-        let mut clicked_node: Option<VertexKey> = None;
+        let found = self
+            .handle
+            .as_ref()
+            .and_then(|handle| handle.query_result())
+            .map(|result| (result.root, result.complete));
 
-        for (idx, node) in self.graph.nodes_mut() {
+        for &idx in &visible {
+            let node = &mut self.graph[idx];
             let screen_pos =
                 viewport_min + (node.world_pos.to_vec2() * zoom) + pan;
-
-            if let Some(node_response) =
-                node.show(ui, screen_pos, zoom, viewport_rect)
+            let highlight = if let Some((_, complete)) =
+                found.filter(|(root, _)| *root == node.key)
             {
-                node_screen_rects.insert(idx, node_response.rect);
-
-                if node_response
-                    .response
-                    .dragged_by(egui::PointerButton::Primary)
-                {
-                    dragged_node = Some(idx);
-                }
-
-                // Detect click (clicked = was pressed and released)
-                if node_response.response.clicked() {
-                    clicked_node = Some(node.key);
-                }
-            }
+                Some(Highlight::Found { complete })
+            } else if self.current_match == Some(node.key) {
+                Some(Highlight::Current)
+            } else if self.highlight.contains(&node.key) {
+                Some(Highlight::Match)
+            } else {
+                None
+            };
+            let interaction = if dragged_node == Some(idx) {
+                Interaction::Dragged
+            } else if hovered_node == Some(idx) {
+                Interaction::Hovered
+            } else {
+                Interaction::None
+            };
+            node.show(
+                &painter,
+                screen_pos,
+                zoom,
+                detail,
+                interaction,
+                highlight,
+            );
         }
 
         // Second pass: draw edges ON TOP of source nodes
@@ -336,60 +495,88 @@ impl GraphVis {
             let source_idx = edge.source(); // Parent node
             let target_idx = edge.target(); // Child node
 
-            if let Some((source_rect, target_rect)) = node_screen_rects
-                .get(&source_idx)
-                .zip(node_screen_rects.get(&target_idx))
+            let (Some(source_rect), Some(target_rect)) = (
+                index.rect(source_idx).map(to_screen),
+                index.rect(target_idx).map(to_screen),
+            ) else {
+                continue;
+            };
+
+            // Bend points of the layered layout on screen
+            let bends: Vec<Pos2> = self
+                .bends
+                .get(&edge.id())
+                .into_iter()
+                .flatten()
+                .map(|bend| viewport_min + bend.to_vec2() * zoom + pan)
+                .collect();
+
+            // Skip edges outside of the viewport
+            let bounds = bends
+                .iter()
+                .fold(source_rect.union(target_rect), |bounds, bend| {
+                    bounds.union(Rect::from_pos(*bend))
+                });
+            if !bounds.intersects(viewport_rect) {
+                continue;
+            }
+
+            // Dots are connected by plain lines
+            if detail == Detail::Dot {
+                clipped_painter.line_segment(
+                    [source_rect.center(), target_rect.center()],
+                    Stroke::new(1.0, Color32::from_gray(70)),
+                );
+                continue;
+            }
+
+            // Get the target/child node's vertex index to find it in source's child patterns
+            let target_vertex_idx =
+                *self.graph[target_idx].data.to_token().index;
+
+            // Get all child rects for this target from source node (same child can appear multiple times)
+            // if it was drawn in this frame
+            let child_rects: Vec<Rect> = if visible
+                .binary_search(&source_idx)
+                .is_ok()
             {
-                // Get the target/child node's vertex index to find it in source's child patterns
-                let target_vertex_idx = self
-                    .graph
-                    .node_weight(target_idx)
-                    .map(|n| *n.data.to_token().index);
-
-                // Get all child rects for this target from source node (same child can appear multiple times)
-                let child_rects: Vec<Rect> =
-                    if let Some(tgt_v_idx) = target_vertex_idx {
-                        self.graph
-                            .node_weight(source_idx)
-                            .and_then(|node| node.child_rects.get(&tgt_v_idx))
-                            .map(|rects| rects.clone())
-                            .unwrap_or_default()
-                    } else {
-                        vec![]
-                    };
-
-                // End point: at target/child node
-                let target_center = target_rect.center();
-
-                if child_rects.is_empty() {
-                    // Fallback: draw edge from source center to target
-                    let start = Self::border_intersection_point(
-                        source_rect,
-                        &target_center,
-                    );
-                    let end = Self::border_intersection_point(
-                        target_rect,
-                        &source_rect.center(),
-                    );
+                self.graph[source_idx]
+                    .child_rects
+                    .get(&target_vertex_idx)
+                    .cloned()
+                    .unwrap_or_default()
+            } else {
+                vec![]
+            };
+
+            // End point: at target/child node
+            let target_center = target_rect.center();
+
+            // Fallback: draw edge from source center to target,
+            // otherwise from each occurrence of the child in source's
+            // patterns
+            let start_rects = if child_rects.is_empty() {
+                vec![source_rect]
+            } else {
+                child_rects
+            };
+            for start_rect in &start_rects {
+                let start = Self::border_intersection_point(
+                    start_rect,
+                    bends.first().unwrap_or(&target_center),
+                );
+                let end = Self::border_intersection_point(
+                    &target_rect,
+                    bends.last().unwrap_or(&start_rect.center()),
+                );
+                if bends.is_empty() {
                     Self::edge_clipped(&clipped_painter, &start, &end, zoom);
                 } else {
-                    // Draw an edge from each occurrence of the child in source's patterns
-                    for child_rect in &child_rects {
-                        let start = Self::border_intersection_point(
-                            child_rect,
-                            &target_center,
-                        );
-                        let end = Self::border_intersection_point(
-                            target_rect,
-                            &child_rect.center(),
-                        );
-                        Self::edge_clipped(
-                            &clipped_painter,
-                            &start,
-                            &end,
-                            zoom,
-                        );
-                    }
+                    let points = std::iter::once(start)
+                        .chain(bends.iter().copied())
+                        .chain(std::iter::once(end))
+                        .collect();
+                    Self::edge_path_clipped(&clipped_painter, points, zoom);
                 }
             }
         }
@@ -404,6 +591,13 @@ impl GraphVis {
                 // Mark as manually moved
                 node.manually_moved = true;
             }
+            // Let the other nodes adjust to the pinned one
+            if let Some(force) = &mut self.force {
+                force.reheat();
+            }
+            // Routed edges of the moved node would cross other nodes
+            self.remove_bends(idx);
+            self.index = None;
         }
 
         // Set clicked node in response
@@ -460,15 +654,115 @@ impl GraphVis {
         }
     }
 
-    fn initialized(&self) -> bool {
-        self.graph.node_count() > 0 && self.handle.is_some()
-    }
-
     /// Mark the graph visualization as needing a rebuild
     pub(crate) fn mark_dirty(&mut self) {
         self.dirty = true;
     }
 
+    /// Show the labels published by a paused ngrams run
+    pub(crate) fn show_paused_labels(&mut self) {
+        if self.handle.as_ref().is_some_and(Graph::take_paused_labels) {
+            self.mark_dirty();
+        }
+    }
+
+    pub(crate) fn view(&self) -> GraphView {
+        self.view
+    }
+
+    pub(crate) fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    pub(crate) fn pan(&self) -> Vec2 {
+        self.pan
+    }
+
+    pub(crate) fn nodes(&self) -> impl Iterator<Item = &NodeVis> {
+        self.graph.node_weights()
+    }
+
+    /// Incremented whenever the nodes are rebuilt
+    pub(crate) fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Outline the search matches, `current` stronger than the others
+    pub(crate) fn set_highlight(
+        &mut self,
+        matches: HashSet<VertexKey>,
+        current: Option<VertexKey>,
+    ) {
+        self.highlight = matches;
+        self.current_match = current;
+    }
+
+    /// Pan and zoom to a node on the next frame
+    pub(crate) fn focus(
+        &mut self,
+        key: VertexKey,
+    ) {
+        self.focus = Some(key);
+    }
+
+    /// Show `view` with the given camera and node positions, nodes without
+    /// a position are laid out as usual
+    pub(crate) fn restore(
+        &mut self,
+        view: GraphView,
+        zoom: f32,
+        pan: Vec2,
+        positions: HashMap<VertexKey, (Pos2, bool)>,
+    ) {
+        self.view = view;
+        self.zoom = zoom;
+        self.pan = pan;
+        self.restore = positions;
+        self.keep = Keep::Nothing;
+        self.dirty = true;
+    }
+
+    /// Lay the nodes out again with `kind`, keeping manually moved nodes
+    pub(crate) fn set_layout(
+        &mut self,
+        kind: LayoutKind,
+        layering: Layering,
+    ) {
+        if self.layout_kind != kind
+            || (kind == LayoutKind::Layered && self.layering != layering)
+        {
+            self.keep = self.keep.max(Keep::Nodes);
+            self.dirty = true;
+        }
+        self.layout_kind = kind;
+        self.layering = layering;
+    }
+
+    /// Forget the routes of the edges at a node
+    fn remove_bends(
+        &mut self,
+        idx: NodeIndex,
+    ) {
+        for edge in self.graph.edges_directed(idx, Direction::Outgoing) {
+            self.bends.remove(&edge.id());
+        }
+        for edge in self.graph.edges_directed(idx, Direction::Incoming) {
+            self.bends.remove(&edge.id());
+        }
+    }
+
+    /// Switch the shown graph, laying it out from scratch
+    pub(crate) fn set_view(
+        &mut self,
+        view: GraphView,
+    ) {
+        if self.view != view {
+            self.view = view;
+            self.keep = Keep::Nothing;
+            self.dirty = true;
+        }
+    }
+
     pub(crate) fn new(graph: Graph) -> Self {
         Self {
             graph: DiGraph::new(),
@@ -478,6 +772,19 @@ impl GraphVis {
             generation: 0,
             zoom: 1.0,
             pan: Vec2::ZERO,
+            view: GraphView::default(),
+            restore: HashMap::default(),
+            highlight: HashSet::default(),
+            current_match: None,
+            focus: None,
+            layout_kind: LayoutKind::default(),
+            force: None,
+            layering: Layering::default(),
+            index: None,
+            dragging: None,
+            bends: HashMap::default(),
+            keep: Keep::default(),
+            rebuild: None,
         }
     }
     fn graph(&self) -> Option<Graph> {
@@ -548,6 +855,21 @@ impl GraphVis {
         ));
         Self::edge_tip_clipped(painter, source, target, 10.0 * zoom, zoom);
     }
+    /// Edge through bend points, with the tip on the last segment
+    fn edge_path_clipped(
+        painter: &egui::Painter,
+        points: Vec<Pos2>,
+        zoom: f32,
+    ) {
+        let [.., source, target] = points[..] else {
+            return;
+        };
+        painter.add(Shape::line(
+            points,
+            Stroke::new(1.0 * zoom, egui::Color32::WHITE),
+        ));
+        Self::edge_tip_clipped(painter, &source, &target, 10.0 * zoom, zoom);
+    }
     #[allow(clippy::many_single_char_names)]
     fn border_intersection_point(
         rect: &Rect,
@@ -584,3 +906,27 @@ impl GraphVis {
         tracing_egui::poll_events().drain(..).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::build_graph1;
+
+    #[test]
+    fn test_layered_keeps_restored_positions() {
+        let mut vis = GraphVis::new(Graph::from(build_graph1()));
+        vis.set_layout(LayoutKind::Layered, Layering::default());
+        vis.update().unwrap();
+        let key = vis.graph.node_weights().next().unwrap().key;
+        let pos = Pos2::new(1234.0, 5678.0);
+        vis.restore(
+            GraphView::default(),
+            1.0,
+            Vec2::ZERO,
+            [(key, (pos, false))].into_iter().collect(),
+        );
+        vis.update().unwrap();
+        let node = vis.graph.node_weights().find(|n| n.key == key).unwrap();
+        assert_eq!(node.world_pos, pos);
+    }
+}
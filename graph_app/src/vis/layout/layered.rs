@@ -0,0 +1,477 @@
+//! Layered (Sugiyama) layout of the vertex DAG.
+//!
+//! 1. Vertices are assigned to layers by width or by the longest path from
+//!    a vertex without parents.
+//! 2. Edges spanning several layers are split by dummy nodes, which become
+//!    the bends of the edge.
+//! 3. The nodes of each layer are ordered by barycenter sweeps, keeping the
+//!    order with the fewest edge crossings.
+//! 4. Nodes are moved towards the mean position of their neighbours, kept
+//!    apart by their sizes.
+
+use std::collections::VecDeque;
+
+use eframe::egui::{
+    Pos2,
+    Vec2,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use strum::{
+    Display,
+    EnumIter,
+};
+
+/// Horizontal gap between neighbouring nodes of a layer
+const H_SPACING: f32 = 50.0;
+/// Vertical gap between layers
+const V_SPACING: f32 = 80.0;
+/// Space reserved for an edge passing through a layer
+const DUMMY_WIDTH: f32 = 20.0;
+/// Number of barycenter sweeps ordering the layers
+const ORDER_SWEEPS: usize = 8;
+/// Number of sweeps aligning nodes with their neighbours
+const ALIGN_SWEEPS: usize = 4;
+/// Top left corner of the layout
+const ORIGIN: Pos2 = Pos2::new(60.0, 60.0);
+
+/// How vertices are assigned to layers
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    Display,
+    EnumIter,
+    Serialize,
+    Deserialize,
+)]
+pub(crate) enum Layering {
+    /// One layer per vertex width, widest on top
+    #[default]
+    #[strum(serialize = "By width")]
+    Width,
+    /// Layers by the longest path from a vertex without parents
+    #[strum(serialize = "By longest path")]
+    LongestPath,
+}
+
+/// A vertex to lay out
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LayerNode {
+    /// Token width of the vertex
+    pub(crate) width: usize,
+    /// Size of the node on the canvas
+    pub(crate) size: Vec2,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct LayeredLayout {
+    /// Top left corner of every node
+    pub(crate) positions: Vec<Pos2>,
+    /// Bend points of every edge, empty for edges between adjacent layers
+    pub(crate) bends: Vec<Vec<Pos2>>,
+}
+
+/// Nodes and dummy nodes with their edges between adjacent layers
+#[derive(Debug, Default)]
+struct Layered {
+    layer: Vec<usize>,
+    width: Vec<f32>,
+    /// Neighbours in the layer above
+    up: Vec<Vec<usize>>,
+    /// Neighbours in the layer below
+    down: Vec<Vec<usize>>,
+    /// Dummy nodes of every edge, top to bottom
+    chains: Vec<Vec<usize>>,
+}
+
+impl Layered {
+    fn add_node(
+        &mut self,
+        layer: usize,
+        width: f32,
+    ) -> usize {
+        self.layer.push(layer);
+        self.width.push(width);
+        self.up.push(Vec::new());
+        self.down.push(Vec::new());
+        self.layer.len() - 1
+    }
+
+    fn link(
+        &mut self,
+        parent: usize,
+        child: usize,
+    ) {
+        self.down[parent].push(child);
+        self.up[child].push(parent);
+    }
+}
+
+fn assign_layers(
+    nodes: &[LayerNode],
+    edges: &[(usize, usize)],
+    layering: Layering,
+) -> Vec<usize> {
+    match layering {
+        Layering::Width => {
+            let mut widths: Vec<usize> =
+                nodes.iter().map(|node| node.width).collect();
+            widths.sort_unstable_by(|a, b| b.cmp(a));
+            widths.dedup();
+            nodes
+                .iter()
+                .map(|node| {
+                    widths.iter().position(|w| *w == node.width).unwrap_or(0)
+                })
+                .collect()
+        },
+        Layering::LongestPath => {
+            let mut children = vec![Vec::new(); nodes.len()];
+            let mut parents = vec![0; nodes.len()];
+            for &(parent, child) in edges {
+                children[parent].push(child);
+                parents[child] += 1;
+            }
+            let mut layers = vec![0; nodes.len()];
+            let mut queue: VecDeque<usize> =
+                (0..nodes.len()).filter(|v| parents[*v] == 0).collect();
+            while let Some(v) = queue.pop_front() {
+                for &child in &children[v] {
+                    layers[child] = layers[child].max(layers[v] + 1);
+                    parents[child] -= 1;
+                    if parents[child] == 0 {
+                        queue.push_back(child);
+                    }
+                }
+            }
+            layers
+        },
+    }
+}
+
+/// Split edges spanning several layers by dummy nodes
+fn insert_dummies(
+    nodes: &[LayerNode],
+    edges: &[(usize, usize)],
+    layers: Vec<usize>,
+) -> Layered {
+    let mut layered = Layered::default();
+    for (node, layer) in nodes.iter().zip(layers) {
+        layered.add_node(layer, node.size.x);
+    }
+    for &(parent, child) in edges {
+        let (top, bottom) = (layered.layer[parent], layered.layer[child]);
+        let mut chain = Vec::new();
+        // edges against the layering are not part of the ordering
+        if bottom > top {
+            let mut previous = parent;
+            for layer in top + 1..bottom {
+                let dummy = layered.add_node(layer, DUMMY_WIDTH);
+                layered.link(previous, dummy);
+                chain.push(dummy);
+                previous = dummy;
+            }
+            layered.link(previous, child);
+        }
+        layered.chains.push(chain);
+    }
+    layered
+}
+
+/// Index of every node within its layer
+fn positions(
+    order: &[Vec<usize>],
+    count: usize,
+) -> Vec<usize> {
+    let mut positions = vec![0; count];
+    for layer in order {
+        for (i, v) in layer.iter().enumerate() {
+            positions[*v] = i;
+        }
+    }
+    positions
+}
+
+/// Pairs of edges between two layers which cross, given as the positions
+/// of their upper and lower ends
+///
+/// Counts the inversions of the lower ends in the order of the upper ends
+/// with a Fenwick tree, in `O(E log V)` instead of comparing all pairs.
+fn layer_crossings(mut edges: Vec<(usize, usize)>) -> usize {
+    edges.sort_unstable();
+    let size = edges.iter().map(|(_, lower)| lower + 1).max().unwrap_or(0);
+    // tree[i] counts the inserted lower ends in a range ending at i - 1
+    let mut tree = vec![0; size + 1];
+    let mut count = 0;
+    for (inserted, (_, lower)) in edges.iter().enumerate() {
+        // inserted edges ending at or left of `lower` do not cross, edges
+        // with the same upper end were inserted in order of their lower end
+        let mut not_crossing = 0;
+        let mut i = lower + 1;
+        while i > 0 {
+            not_crossing += tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        count += inserted - not_crossing;
+        let mut i = lower + 1;
+        while i <= size {
+            tree[i] += 1;
+            i += i & i.wrapping_neg();
+        }
+    }
+    count
+}
+
+fn crossings(
+    layered: &Layered,
+    order: &[Vec<usize>],
+) -> usize {
+    let positions = positions(order, layered.layer.len());
+    order
+        .iter()
+        .map(|layer| {
+            let edges: Vec<(usize, usize)> = layer
+                .iter()
+                .flat_map(|v| {
+                    layered.down[*v]
+                        .iter()
+                        .map(|c| (positions[*v], positions[*c]))
+                })
+                .collect();
+            layer_crossings(edges)
+        })
+        .sum()
+}
+
+/// Sort every layer by the mean position of its neighbours in the layer
+/// above, or below when sweeping upwards
+fn sweep(
+    layered: &Layered,
+    order: &mut [Vec<usize>],
+    downwards: bool,
+) {
+    let layer_indices: Vec<usize> = if downwards {
+        (1..order.len()).collect()
+    } else {
+        (0..order.len().saturating_sub(1)).rev().collect()
+    };
+    for l in layer_indices {
+        let positions = positions(order, layered.layer.len());
+        let mut keyed: Vec<(f32, usize)> = order[l]
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let neighbours = if downwards {
+                    &layered.up[*v]
+                } else {
+                    &layered.down[*v]
+                };
+                let key = if neighbours.is_empty() {
+                    i as f32
+                } else {
+                    neighbours.iter().map(|n| positions[*n] as f32).sum::<f32>()
+                        / neighbours.len() as f32
+                };
+                (key, *v)
+            })
+            .collect();
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        order[l] = keyed.into_iter().map(|(_, v)| v).collect();
+    }
+}
+
+/// Order the layers to reduce edge crossings
+fn order_layers(layered: &Layered) -> Vec<Vec<usize>> {
+    let count = layered.layer.iter().max().map_or(0, |max| max + 1);
+    let mut order = vec![Vec::new(); count];
+    for (v, layer) in layered.layer.iter().enumerate() {
+        order[*layer].push(v);
+    }
+    let mut best = order.clone();
+    let mut best_crossings = crossings(layered, &best);
+    for i in 0..ORDER_SWEEPS {
+        if best_crossings == 0 {
+            break;
+        }
+        sweep(layered, &mut order, i % 2 == 0);
+        let count = crossings(layered, &order);
+        if count < best_crossings {
+            best = order.clone();
+            best_crossings = count;
+        }
+    }
+    best
+}
+
+/// Horizontal centers of all nodes, keeping the order of each layer
+fn place_horizontally(
+    layered: &Layered,
+    order: &[Vec<usize>],
+) -> Vec<f32> {
+    let mut x = vec![0.0; layered.layer.len()];
+    for layer in order {
+        let mut right = 0.0;
+        for v in layer {
+            x[*v] = right + layered.width[*v] / 2.0;
+            right += layered.width[*v] + H_SPACING;
+        }
+    }
+    for i in 0..ALIGN_SWEEPS {
+        let downwards = i % 2 == 0;
+        let layer_indices: Vec<usize> = if downwards {
+            (1..order.len()).collect()
+        } else {
+            (0..order.len().saturating_sub(1)).rev().collect()
+        };
+        for l in layer_indices {
+            let mut right = f32::NEG_INFINITY;
+            for v in &order[l] {
+                let neighbours = if downwards {
+                    &layered.up[*v]
+                } else {
+                    &layered.down[*v]
+                };
+                let desired = if neighbours.is_empty() {
+                    x[*v]
+                } else {
+                    neighbours.iter().map(|n| x[*n]).sum::<f32>()
+                        / neighbours.len() as f32
+                };
+                let half = layered.width[*v] / 2.0;
+                x[*v] = desired.max(right + H_SPACING + half);
+                right = x[*v] + half;
+            }
+        }
+    }
+    x
+}
+
+/// Lay out `nodes` connected by parent-child `edges`
+pub(crate) fn layered_layout(
+    nodes: &[LayerNode],
+    edges: &[(usize, usize)],
+    layering: Layering,
+) -> LayeredLayout {
+    if nodes.is_empty() {
+        return LayeredLayout::default();
+    }
+    let layers = assign_layers(nodes, edges, layering);
+    let layered = insert_dummies(nodes, edges, layers);
+    let order = order_layers(&layered);
+    let x = place_horizontally(&layered, &order);
+    let left = (0..layered.layer.len())
+        .map(|v| x[v] - layered.width[v] / 2.0)
+        .fold(f32::INFINITY, f32::min);
+
+    // rows as high as their highest node
+    let mut heights = vec![0.0f32; order.len()];
+    for (node, layer) in nodes.iter().zip(&layered.layer) {
+        heights[*layer] = heights[*layer].max(node.size.y);
+    }
+    let tops: Vec<f32> = heights
+        .iter()
+        .scan(ORIGIN.y, |top, height| {
+            let current = *top;
+            *top += height + V_SPACING;
+            Some(current)
+        })
+        .collect();
+
+    let positions = nodes
+        .iter()
+        .enumerate()
+        .map(|(v, node)| {
+            Pos2::new(
+                ORIGIN.x + x[v] - left - node.size.x / 2.0,
+                tops[layered.layer[v]],
+            )
+        })
+        .collect();
+    let bends = layered
+        .chains
+        .iter()
+        .map(|chain| {
+            chain
+                .iter()
+                .map(|d| {
+                    let layer = layered.layer[*d];
+                    Pos2::new(
+                        ORIGIN.x + x[*d] - left,
+                        tops[layer] + heights[layer] / 2.0,
+                    )
+                })
+                .collect()
+        })
+        .collect();
+    LayeredLayout { positions, bends }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(width: usize) -> LayerNode {
+        LayerNode {
+            width,
+            size: Vec2::new(100.0, 60.0),
+        }
+    }
+
+    #[test]
+    fn test_layered_layout() {
+        // 0 and 1 are roots crossing over 2 and 3, 0 also reaches 4 directly
+        let nodes = [node(6), node(6), node(3), node(3), node(1)];
+        let edges = [(0, 3), (1, 2), (0, 4), (2, 4), (3, 4)];
+
+        let layout = layered_layout(&nodes, &edges, Layering::Width);
+        let [a, b, c, d, e] = layout.positions[..] else {
+            panic!("one position per node");
+        };
+        assert_eq!(a.y, b.y);
+        assert!(a.y < c.y && c.y == d.y && d.y < e.y);
+        // the children are ordered like their parents
+        assert_eq!(a.x < b.x, d.x < c.x);
+        // nodes of a layer do not overlap
+        assert!((a.x - b.x).abs() >= 100.0 + H_SPACING);
+        // only the edge skipping a layer bends
+        let bends: Vec<usize> =
+            layout.bends.iter().map(|bends| bends.len()).collect();
+        assert_eq!(bends, [0, 0, 1, 0, 0]);
+
+        let layering = assign_layers(&nodes, &edges, Layering::LongestPath);
+        assert_eq!(layering, [0, 0, 1, 1, 2]);
+    }
+
+    #[test]
+    fn test_layer_crossings() {
+        let brute = |edges: &[(usize, usize)]| {
+            let mut count = 0;
+            for (i, a) in edges.iter().enumerate() {
+                for b in &edges[i + 1..] {
+                    if (a.0 < b.0 && a.1 > b.1) || (a.0 > b.0 && a.1 < b.1) {
+                        count += 1;
+                    }
+                }
+            }
+            count
+        };
+        let mut state = 7u64;
+        let mut next = |max: u64| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            ((state >> 33) % max) as usize
+        };
+        for _ in 0..20 {
+            let edges: Vec<(usize, usize)> =
+                (0..next(40)).map(|_| (next(8), next(8))).collect();
+            assert_eq!(layer_crossings(edges.clone()), brute(&edges));
+        }
+        assert_eq!(layer_crossings(vec![(0, 1), (1, 0)]), 1);
+        assert_eq!(layer_crossings(vec![(0, 0), (0, 1), (1, 1)]), 0);
+    }
+}
@@ -1,3 +1,5 @@
+pub(crate) mod layered;
+
 use context_trace::{
     graph::{
         vertex::{key::VertexKey, wide::Wide},
@@ -8,6 +10,14 @@ use context_trace::{
 
 use eframe::egui::Pos2;
 use itertools::Itertools;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use strum::{
+    Display,
+    EnumIter,
+};
 #[allow(unused)]
 use petgraph::{
     graph::{
@@ -30,6 +40,32 @@ type GraphData = petgraph::Graph<
     ),
     (),
 >;
+/// How the nodes of the canvas are placed
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    Display,
+    EnumIter,
+    Serialize,
+    Deserialize,
+)]
+pub(crate) enum LayoutKind {
+    /// Rows of vertices of equal width, widest first
+    #[default]
+    #[strum(serialize = "Grid")]
+    Grid,
+    /// The grid refined by forces along the edges
+    #[strum(serialize = "Force-directed")]
+    Force,
+    /// Layers ordered to reduce edge crossings
+    #[strum(serialize = "Layered")]
+    Layered,
+}
+
 #[derive(Default, Debug)]
 pub(crate) struct GraphLayout {
     pub(crate) labels: Vec<String>,
@@ -1,5 +1,8 @@
+pub(crate) mod force;
 pub(crate) mod graph;
 pub(crate) mod layout;
 pub(crate) mod node;
 pub(crate) mod pattern;
+pub(crate) mod rebuild;
+pub(crate) mod spatial;
 pub(crate) mod status;
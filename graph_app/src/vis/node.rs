@@ -18,27 +18,77 @@ use eframe::{
         CornerRadius,
         Pos2,
         Rect,
-        Response,
-        Sense,
         Stroke,
         StrokeKind,
-        Ui,
         Vec2,
     },
     epaint::FontId,
 };
-use indexmap::IndexMap;
 use petgraph::graph::NodeIndex;
 
 use crate::{
-    graph::Graph,
+    graph::{
+        Graph,
+        GraphView,
+    },
     vis::pattern::ChildPatternsVis,
 };
 
-pub(crate) struct NodeResponse {
-    pub(crate) response: Response,
-    pub(crate) rect: Rect,
-    pub(crate) ranges: IndexMap<PatternId, Range<usize>>,
+/// Zoom below which nodes are drawn as boxes with their name
+const BOX_ZOOM: f32 = 0.5;
+/// Zoom below which nodes are drawn as dots
+const DOT_ZOOM: f32 = 0.2;
+/// Font size of the name in a box, in screen pixels
+const BOX_FONT_SIZE: f32 = 11.0;
+/// Radius of a node drawn as a dot, in screen pixels
+const DOT_RADIUS: f32 = 4.0;
+
+/// How much of a node is drawn at a zoom level
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Detail {
+    /// Frame with properties and child patterns
+    Full,
+    /// Colored box with the name
+    Box,
+    /// Colored dot
+    Dot,
+}
+
+impl Detail {
+    pub(crate) fn for_zoom(zoom: f32) -> Self {
+        if zoom >= BOX_ZOOM {
+            Detail::Full
+        } else if zoom >= DOT_ZOOM {
+            Detail::Box
+        } else {
+            Detail::Dot
+        }
+    }
+}
+
+/// Pointer interaction with a node, found by hit-testing the canvas
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Interaction {
+    #[default]
+    None,
+    Hovered,
+    Dragged,
+}
+
+/// Sizes of the parts of a full frame at a zoom level
+struct Metrics {
+    padding: f32,
+    title_height: f32,
+    row_height: f32,
+    char_width: f32,
+    key_value_gap: f32,
+    child_frame_height: f32,
+    child_frame_padding: f32,
+    child_spacing: f32,
+    column_gap: f32,
+    max_key_width: f32,
+    props_column_width: f32,
+    size: Vec2,
 }
 #[allow(unused)]
 #[derive(Clone, Debug)]
@@ -47,6 +97,16 @@ pub(crate) struct SelectionState {
     pub(crate) range: Range<usize>,
     pub(crate) trace: IndexRangePath,
 }
+/// Emphasis of a node matched by the vertex search
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Highlight {
+    /// One of the matches
+    Match,
+    /// The selected match
+    Current,
+    /// Root of the last context search
+    Found { complete: bool },
+}
 #[allow(unused)]
 #[derive(Clone, Debug)]
 pub(crate) struct NodeVis {
@@ -63,7 +123,9 @@ pub(crate) struct NodeVis {
     pub(crate) selected_range: Option<SelectionState>,
     /// Generation counter for unique IDs
     generation: usize,
-    /// Cached size from last render
+    /// Graph of the handle this vertex is taken from
+    view: GraphView,
+    /// Size of the full frame in world coordinates
     pub(crate) cached_size: Vec2,
     /// Map from child vertex index to its screen rects (updated during render)
     pub(crate) child_rects: HashMap<usize, Vec<Rect>>,
@@ -84,84 +146,92 @@ impl NodeVis {
         data: &VertexData,
         world_pos: Pos2,
         generation: usize,
+        view: GraphView,
     ) -> Self {
-        Self::new_impl(graph, idx, key, data, world_pos, None, generation)
+        let (name, child_patterns) = {
+            let graph_ref = graph.view(view);
+            let graph = &*graph_ref;
+            let name = graph.vertex_data_string(data.clone());
+            let child_patterns = ChildPatternsVis::new(graph, data);
+            (name, child_patterns)
+        };
+        let mut node = Self {
+            key: *key,
+            graph,
+            idx,
+            name,
+            data: data.clone(),
+            world_pos,
+            manually_moved: false,
+            child_patterns,
+            selected_range: None,
+            generation,
+            view,
+            cached_size: Vec2::ZERO,
+            child_rects: HashMap::new(),
+        };
+        node.cached_size = node.measure();
+        node
     }
     pub(crate) fn from_old(
         old: &Self,
         idx: NodeIndex,
         data: &VertexData,
     ) -> Self {
-        let mut new = Self::new_impl(
+        let mut new = Self::new(
             old.graph.clone(),
             idx,
             &old.key,
             data,
             old.world_pos,
-            old.selected_range.clone(),
             old.generation,
+            old.view,
         );
+        new.selected_range = old.selected_range.clone();
         new.manually_moved = old.manually_moved;
         new
     }
-    pub(crate) fn new_impl(
-        graph: Graph,
+    /// Reuse the node of a vertex with unchanged children at a new index,
+    /// taking the new `data` for its parents
+    pub(crate) fn reindexed(
+        old: &Self,
         idx: NodeIndex,
-        key: &VertexKey,
         data: &VertexData,
-        world_pos: Pos2,
-        selected_range: Option<SelectionState>,
-        generation: usize,
     ) -> Self {
-        let (name, child_patterns) = {
-            let graph = &**graph.read();
-            let name = graph.vertex_data_string(data.clone());
-            let child_patterns = ChildPatternsVis::new(graph, data);
-            (name, child_patterns)
-        };
-        Self {
-            key: *key,
-            graph,
-            idx,
-            name,
-            data: data.clone(),
-            world_pos,
-            manually_moved: false,
-            child_patterns,
-            selected_range,
-            generation,
-            cached_size: Vec2::new(150.0, 80.0), // Default size
-            child_rects: HashMap::new(),
-        }
+        let mut new = old.clone();
+        new.idx = idx;
+        new.data = data.clone();
+        new.cached_size = new.measure();
+        new
     }
 
-    /// Draw the node as a styled frame at the given screen position
-    /// Returns the response and the screen-space rect
-    pub(crate) fn show(
-        &mut self,
-        ui: &mut Ui,
-        screen_pos: Pos2,
-        zoom: f32,
-        viewport_rect: Rect,
-    ) -> Option<NodeResponse> {
-        let _node_id = egui::Id::new(format!(
-            "node_{}_{}",
-            self.generation,
-            self.idx.index()
-        ));
-
-        // Build properties dictionary
-        let properties: Vec<(&str, String)> = vec![
+    /// Rect of the full frame in world coordinates
+    pub(crate) fn world_rect(&self) -> Rect {
+        Rect::from_min_size(self.world_pos, self.cached_size)
+    }
+
+    fn properties(&self) -> Vec<(&'static str, String)> {
+        vec![
             ("idx", format!("{}", self.idx.index())),
             ("width", format!("{}", self.data.to_token().width.0)),
             ("parents", format!("{}", self.data.parents().len())),
             ("patterns", format!("{}", self.data.child_patterns().len())),
-        ];
+        ]
+    }
 
-        // Get child patterns with indices for display and edge connections
+    /// Size of the full frame in world coordinates
+    fn measure(&self) -> Vec2 {
         let patterns = self.child_patterns.patterns_with_indices();
+        self.metrics(&self.properties(), &patterns, 1.0).size
+    }
 
-        // Calculate node size based on content - two column layout
+    /// Sizes of the frame at `zoom`, based on content - two column layout
+    fn metrics(
+        &self,
+        properties: &[(&str, String)],
+        patterns: &[Vec<(String, usize)>],
+        zoom: f32,
+    ) -> Metrics {
         let padding = 6.0 * zoom;
         let title_height = 18.0 * zoom;
         let row_height = 16.0 * zoom;
@@ -221,26 +291,40 @@ impl NodeVis {
         let content_height = props_height.max(patterns_height);
         let node_height = title_height + content_height + padding;
 
-        self.cached_size = Vec2::new(node_width / zoom, node_height / zoom);
-
-        let node_rect =
-            Rect::from_min_size(screen_pos, Vec2::new(node_width, node_height));
-
-        // Check if node is visible in viewport
-        if !viewport_rect.intersects(node_rect) {
-            return None;
+        Metrics {
+            padding,
+            title_height,
+            row_height,
+            char_width,
+            key_value_gap,
+            child_frame_height,
+            child_frame_padding,
+            child_spacing,
+            column_gap,
+            max_key_width,
+            props_column_width,
+            size: Vec2::new(node_width, node_height),
         }
+    }
 
-        // Allocate the rect and get response
-        let response = ui.allocate_rect(node_rect, Sense::click_and_drag());
-
-        // Determine colors based on state
+    /// Fill, border color and border width of the node
+    fn colors(
+        &self,
+        interaction: Interaction,
+        highlight: Option<Highlight>,
+    ) -> (Color32, Color32, f32) {
         let is_labeled = self.graph.labels.read().unwrap().contains(&self.key);
-        let is_hovered = response.hovered();
-        let is_dragged = response.dragged();
+        let is_hovered = interaction == Interaction::Hovered;
+        let is_dragged = interaction == Interaction::Dragged;
+
+        // In the lattice, labels are what explains the partitioning, so
+        // other vertices are dimmed and labels outlined
+        let in_lattice = self.view == GraphView::Containment;
 
         let base_color = if is_labeled {
             Color32::from_rgb(20, 80, 40)
+        } else if in_lattice {
+            Color32::from_rgb(35, 38, 45)
         } else {
             Color32::from_rgb(45, 50, 60)
         };
@@ -261,14 +345,99 @@ impl NodeVis {
             base_color
         };
 
-        let border_color = if is_hovered || is_dragged {
-            Color32::from_rgb(100, 150, 200)
-        } else {
-            Color32::from_rgb(70, 80, 90)
+        let (border_color, border_width) = match highlight {
+            Some(Highlight::Current) => (Color32::from_rgb(255, 90, 200), 4.0),
+            Some(Highlight::Match) => (Color32::from_rgb(200, 120, 255), 3.0),
+            Some(Highlight::Found { complete: true }) => {
+                (Color32::from_rgb(80, 220, 120), 4.0)
+            },
+            Some(Highlight::Found { complete: false }) => {
+                (Color32::from_rgb(240, 160, 50), 4.0)
+            },
+            None if is_hovered || is_dragged => {
+                (Color32::from_rgb(100, 150, 200), 2.0)
+            },
+            None if is_labeled && in_lattice => {
+                (Color32::from_rgb(220, 180, 60), 2.0)
+            },
+            None => (Color32::from_rgb(70, 80, 90), 2.0),
         };
+        (fill_color, border_color, border_width)
+    }
+
+    /// Draw the node at the given screen position, as a styled frame at
+    /// full detail
+    pub(crate) fn show(
+        &mut self,
+        painter: &egui::Painter,
+        screen_pos: Pos2,
+        zoom: f32,
+        detail: Detail,
+        interaction: Interaction,
+        highlight: Option<Highlight>,
+    ) {
+        let node_rect =
+            Rect::from_min_size(screen_pos, self.cached_size * zoom);
+        let (fill_color, border_color, border_width) =
+            self.colors(interaction, highlight);
+        self.child_rects.clear();
+
+        match detail {
+            Detail::Dot => {
+                // Dim borders would vanish on the background
+                let color = if highlight.is_some()
+                    || interaction != Interaction::None
+                {
+                    border_color
+                } else {
+                    Color32::from_rgb(110, 125, 145)
+                };
+                painter.circle_filled(node_rect.center(), DOT_RADIUS, color);
+                return;
+            },
+            Detail::Box => {
+                painter.rect(
+                    node_rect,
+                    2,
+                    fill_color,
+                    Stroke::new(border_width, border_color),
+                    StrokeKind::Inside,
+                );
+                painter
+                    .with_clip_rect(painter.clip_rect().intersect(node_rect))
+                    .text(
+                        node_rect.center(),
+                        egui::Align2::CENTER_CENTER,
+                        &self.name,
+                        FontId::proportional(BOX_FONT_SIZE),
+                        Color32::WHITE,
+                    );
+                return;
+            },
+            Detail::Full => {},
+        }
+
+        let properties = self.properties();
+
+        // Get child patterns with indices for display and edge connections
+        let patterns = self.child_patterns.patterns_with_indices();
+        let Metrics {
+            padding,
+            title_height,
+            row_height,
+            char_width,
+            key_value_gap,
+            child_frame_height,
+            child_frame_padding,
+            child_spacing,
+            column_gap,
+            max_key_width,
+            props_column_width,
+            size,
+        } = self.metrics(&properties, &patterns, zoom);
+        let node_width = size.x;
 
         // Draw node background with rounded corners
-        let painter = ui.painter().with_clip_rect(viewport_rect);
         let rounding = (8.0 * zoom) as u8;
 
         // Shadow
@@ -285,7 +454,7 @@ impl NodeVis {
             node_rect,
             rounding,
             fill_color,
-            Stroke::new(2.0 * zoom, border_color),
+            Stroke::new(border_width * zoom, border_color),
             StrokeKind::Inside,
         );
 
@@ -360,12 +529,18 @@ impl NodeVis {
         }
 
         // Right column: child patterns - collect child rects for edge connections
-        self.child_rects.clear();
+
+        // Children covered by the range of a context search
+        let selected = self.selected_range.as_ref().and_then(|selection| {
+            self.child_patterns
+                .pattern_position(&selection.pattern_id)
+                .map(|position| (position, selection.range.clone()))
+        });
 
         if !patterns.is_empty() {
             let mut pattern_y = content_y;
 
-            for pattern in patterns.iter() {
+            for (p, pattern) in patterns.iter().enumerate() {
                 let mut child_x = patterns_x;
 
                 for (i, (child_name, child_idx)) in pattern.iter().enumerate() {
@@ -383,12 +558,22 @@ impl NodeVis {
                         .push(child_rect);
 
                     // Child frame background - alternate colors
-                    let frame_color = if i % 2 == 0 {
+                    let in_selection =
+                        selected.as_ref().is_some_and(|(position, range)| {
+                            *position == p && range.contains(&i)
+                        });
+                    let frame_color = if in_selection {
+                        Color32::from_rgb(95, 80, 40)
+                    } else if i % 2 == 0 {
                         Color32::from_rgb(55, 60, 70)
                     } else {
                         Color32::from_rgb(50, 55, 65)
                     };
-                    let frame_border = Color32::from_rgb(80, 90, 100);
+                    let frame_border = if in_selection {
+                        Color32::from_rgb(240, 200, 90)
+                    } else {
+                        Color32::from_rgb(80, 90, 100)
+                    };
 
                     painter.rect(
                         child_rect,
@@ -413,14 +598,5 @@ impl NodeVis {
                 pattern_y += child_frame_height + child_spacing;
             }
         }
-
-        // Collect ranges (simplified for now)
-        let ranges = IndexMap::new();
-
-        Some(NodeResponse {
-            response,
-            rect: node_rect,
-            ranges,
-        })
     }
 }
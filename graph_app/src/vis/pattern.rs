@@ -143,6 +143,14 @@ impl ChildPatternsVis {
         }
     }
     
+    /// Position of a pattern in the display order
+    pub(crate) fn pattern_position(
+        &self,
+        id: &PatternId,
+    ) -> Option<usize> {
+        self.patterns.iter().position(|(pid, _)| pid == id)
+    }
+
     /// Returns the number of patterns in this node
     pub(crate) fn pattern_count(&self) -> usize {
         self.patterns.len()
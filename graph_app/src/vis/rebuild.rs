@@ -0,0 +1,466 @@
+//! Rebuilding the canvas off the UI thread.
+//!
+//! Converting the graph, laying it out and preparing the nodes runs as a
+//! blocking task while the canvas keeps showing the previous nodes. The
+//! finished [`Rebuild`] replaces them on the next frame. Nodes of unchanged
+//! vertices are reused, and when only a few vertices are new they are placed
+//! next to their neighbours instead of laying out the whole graph again. The
+//! layered layout needs the measured nodes, so it also runs here, except for
+//! such incremental rebuilds, which keep the routes of the reused edges.
+
+use std::sync::{
+    Arc,
+    Mutex,
+};
+
+use context_trace::{
+    graph::vertex::{
+        has_vertex_key::HasVertexKey,
+        key::VertexKey,
+        wide::Wide,
+    },
+    HashMap,
+    HashSet,
+};
+use eframe::egui::{
+    self,
+    vec2,
+    Pos2,
+    Vec2,
+};
+use petgraph::{
+    graph::{
+        DiGraph,
+        EdgeIndex,
+        NodeIndex,
+    },
+    visit::EdgeRef,
+    Direction,
+};
+
+use super::{
+    graph::UpdateError,
+    layout::{
+        layered::{
+            layered_layout,
+            LayerNode,
+            Layering,
+        },
+        GraphLayout,
+    },
+    node::NodeVis,
+};
+use crate::{
+    graph::{
+        Graph,
+        GraphView,
+    },
+    task::{
+        CancellationHandle,
+        TaskHandle,
+    },
+};
+
+/// Graphs with fewer vertices are rebuilt on the UI thread
+pub(crate) const BACKGROUND_THRESHOLD: usize = 500;
+/// Most new vertices placed next to their neighbours, with more the whole
+/// graph is laid out again
+const INCREMENTAL_LIMIT: usize = 32;
+/// Vertical distance of a new vertex to its placed neighbours
+const NEW_NODE_OFFSET: f32 = 150.0;
+/// Horizontal gap between new vertices placed next to the same neighbours
+const NEW_NODE_SPACING: f32 = 30.0;
+
+/// What a rebuild keeps of the shown nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub(crate) enum Keep {
+    /// Nodes and positions, only new vertices are placed
+    #[default]
+    Positions,
+    /// Nodes, all vertices are laid out again
+    Nodes,
+    /// Nothing, the shown nodes belong to another graph
+    Nothing,
+}
+
+/// State of the canvas a rebuild starts from
+#[derive(Debug)]
+pub(crate) struct RebuildInput {
+    pub(crate) handle: Graph,
+    pub(crate) view: GraphView,
+    pub(crate) generation: usize,
+    pub(crate) keep: Keep,
+    /// Shown nodes, reused for vertices which are still in the graph
+    pub(crate) previous: HashMap<VertexKey, NodeVis>,
+    /// Positions and moved flags overriding the layout
+    pub(crate) saved_positions: HashMap<VertexKey, (Pos2, bool)>,
+    /// Routes of the shown edges by the keys of their ends
+    pub(crate) previous_bends: HashMap<(VertexKey, VertexKey), Vec<Pos2>>,
+    /// Layering of the layered layout, None for the other layouts
+    pub(crate) layered: Option<Layering>,
+}
+
+/// Layout and nodes replacing the canvas
+#[derive(Debug)]
+pub(crate) struct Rebuild {
+    pub(crate) layout: GraphLayout,
+    pub(crate) graph: DiGraph<NodeVis, ()>,
+    /// Bend points of the edges routed by the layered layout
+    pub(crate) bends: HashMap<EdgeIndex, Vec<Pos2>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn log_rerun(
+    handle: &Graph,
+    layout: &GraphLayout,
+) -> Result<(), UpdateError> {
+    let rec = handle
+        .rec
+        .as_ref()
+        .ok_or(UpdateError::NoRecordingStream)?;
+    rec.log_static("/graph", &layout.re_nodes())
+        .map_err(UpdateError::Stream)?;
+    rec.log_static("/graph", &layout.re_edges())
+        .map_err(UpdateError::Stream)?;
+    Ok(())
+}
+
+/// Position below the placed parents of a node, or above its children
+fn beside_neighbours(
+    graph: &DiGraph<NodeVis, ()>,
+    idx: NodeIndex,
+    placed: &HashSet<NodeIndex>,
+) -> Option<Pos2> {
+    let center = |direction| {
+        let positions: Vec<Vec2> = graph
+            .neighbors_directed(idx, direction)
+            .filter(|n| placed.contains(n))
+            .map(|n| graph[n].world_pos.to_vec2())
+            .collect();
+        (!positions.is_empty()).then(|| {
+            positions.iter().fold(Vec2::ZERO, |sum, p| sum + *p)
+                / positions.len() as f32
+        })
+    };
+    center(Direction::Incoming)
+        .map(|center| center + vec2(0.0, NEW_NODE_OFFSET))
+        .or_else(|| {
+            center(Direction::Outgoing)
+                .map(|center| center - vec2(0.0, NEW_NODE_OFFSET))
+        })
+        .map(Vec2::to_pos2)
+}
+
+/// Routes of the edges which were shown before, by their new index
+fn reused_bends(
+    graph: &DiGraph<NodeVis, ()>,
+    previous: &HashMap<(VertexKey, VertexKey), Vec<Pos2>>,
+) -> HashMap<EdgeIndex, Vec<Pos2>> {
+    graph
+        .edge_references()
+        .filter_map(|edge| {
+            let ends = (graph[edge.source()].key, graph[edge.target()].key);
+            Some((edge.id(), previous.get(&ends)?.clone()))
+        })
+        .collect()
+}
+
+/// Place the nodes in layers by the sizes of their frames, keeping the
+/// `pinned` nodes in place, returns the routes of the edges between the
+/// nodes which were placed
+fn apply_layered(
+    graph: &mut DiGraph<NodeVis, ()>,
+    layering: Layering,
+    pinned: &HashSet<NodeIndex>,
+) -> HashMap<EdgeIndex, Vec<Pos2>> {
+    let nodes: Vec<LayerNode> = graph
+        .node_weights()
+        .map(|node| LayerNode {
+            width: node.data.width(),
+            size: node.cached_size,
+        })
+        .collect();
+    let edges: Vec<(usize, usize)> = graph
+        .edge_references()
+        .map(|edge| (edge.source().index(), edge.target().index()))
+        .collect();
+    let layout = layered_layout(&nodes, &edges, layering);
+    for (idx, pos) in graph.node_indices().zip(layout.positions) {
+        if !pinned.contains(&idx) {
+            graph[idx].world_pos = pos;
+        }
+    }
+    graph
+        .edge_references()
+        .map(|edge| (edge.id(), edge.source(), edge.target()))
+        .zip(layout.bends)
+        .filter(|((_, source, target), _)| {
+            !pinned.contains(source) && !pinned.contains(target)
+        })
+        .map(|((id, _, _), bends)| (id, bends))
+        .collect()
+}
+
+/// Lay out the graph of `input`, None if cancelled
+pub(crate) fn rebuild(
+    input: RebuildInput,
+    cancellation: &CancellationHandle,
+) -> Option<Rebuild> {
+    let RebuildInput {
+        handle,
+        view,
+        generation,
+        keep,
+        previous,
+        saved_positions,
+        previous_bends,
+        layered,
+    } = input;
+    let cg = handle.view(view);
+    let pg = cg.to_petgraph().filter_map_owned(
+        |_idx, (_index, node)| {
+            if node.data.width() <= 1 {
+                None
+            } else {
+                Some((node.data.vertex_key(), node))
+            }
+        },
+        |_idx, e| (e.token.width() > 1).then_some(()),
+    );
+    if cancellation.is_cancelled() {
+        return None;
+    }
+    let layout = GraphLayout::generate(&cg, pg);
+    if cancellation.is_cancelled() {
+        return None;
+    }
+
+    // Try to update rerun, but don't fail if it's not available (native only)
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = log_rerun(&handle, &layout);
+
+    let new_count = layout
+        .nodes
+        .keys()
+        .filter(|key| !previous.contains_key(key))
+        .count();
+    let incremental = keep == Keep::Positions
+        && !previous.is_empty()
+        && new_count <= INCREMENTAL_LIMIT;
+
+    let query_result = handle.query_result();
+    let mut graph = layout.graph.map(
+        |i, (key, node)| {
+            let mut vis = match previous.get(key) {
+                Some(old)
+                    if old.data.child_patterns()
+                        == node.data.child_patterns() =>
+                {
+                    NodeVis::reindexed(old, i, &node.data)
+                },
+                Some(old) => NodeVis::from_old(old, i, &node.data),
+                None => NodeVis::new(
+                    handle.clone(),
+                    i,
+                    key,
+                    &node.data,
+                    Pos2::ZERO,
+                    generation,
+                    view,
+                ),
+            };
+            // Show the range matched by the last context search
+            vis.selected_range = query_result
+                .as_ref()
+                .filter(|result| result.root == *key)
+                .and_then(|result| result.selection.clone());
+            vis
+        },
+        |_, _| (),
+    );
+    if cancellation.is_cancelled() {
+        return None;
+    }
+
+    // Keep the nodes in place which do not need to be laid out
+    let mut placed = HashSet::default();
+    let mut pinned = HashSet::default();
+    for idx in graph.node_indices() {
+        let node = &mut graph[idx];
+        if let Some(&(pos, moved)) = saved_positions.get(&node.key) {
+            node.world_pos = pos;
+            node.manually_moved = moved;
+            pinned.insert(idx);
+        } else if node.manually_moved {
+            pinned.insert(idx);
+        } else if incremental && previous.contains_key(&node.key) {
+            placed.insert(idx);
+        }
+    }
+    placed.extend(pinned.iter().copied());
+    let unplaced: Vec<NodeIndex> = graph
+        .node_indices()
+        .filter(|idx| !placed.contains(idx))
+        .collect();
+    // new siblings share their neighbours, so they are placed in a row
+    let mut row_ends: HashMap<(u32, u32), f32> = HashMap::default();
+    for idx in unplaced {
+        let beside = incremental
+            .then(|| beside_neighbours(&graph, idx, &placed))
+            .flatten();
+        let pos = match beside {
+            Some(pos) => {
+                let end = row_ends
+                    .entry((pos.x.to_bits(), pos.y.to_bits()))
+                    .or_insert(0.0);
+                let shifted = pos + vec2(*end, 0.0);
+                *end += graph[idx].cached_size.x + NEW_NODE_SPACING;
+                shifted
+            },
+            None => layout.positions.get(&idx).copied().unwrap_or_default(),
+        };
+        graph[idx].world_pos = pos;
+        placed.insert(idx);
+    }
+    if cancellation.is_cancelled() {
+        return None;
+    }
+    let bends = match layered {
+        // laying out again would move the reused nodes
+        Some(_) if incremental => reused_bends(&graph, &previous_bends),
+        Some(layering) => apply_layered(&mut graph, layering, &pinned),
+        None => HashMap::default(),
+    };
+    Some(Rebuild {
+        layout,
+        graph,
+        bends,
+    })
+}
+
+/// Rebuild running as a blocking task, cancelled when dropped
+#[derive(Debug)]
+pub(crate) struct PendingRebuild {
+    task: TaskHandle,
+    result: Arc<Mutex<Option<Rebuild>>>,
+}
+
+impl PendingRebuild {
+    /// Start rebuilding, repainting `ctx` when done
+    pub(crate) fn spawn(
+        input: RebuildInput,
+        ctx: egui::Context,
+    ) -> Self {
+        let result = Arc::new(Mutex::new(None));
+        let slot = result.clone();
+        let task = TaskHandle::spawn_blocking(move |cancellation| {
+            let Some(rebuild) = rebuild(input, &cancellation) else {
+                return;
+            };
+            if !cancellation.is_cancelled() {
+                if let Ok(mut slot) = slot.lock() {
+                    *slot = Some(rebuild);
+                }
+                ctx.request_repaint();
+            }
+        });
+        Self { task, result }
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// Take the finished rebuild
+    pub(crate) fn take(&self) -> Option<Rebuild> {
+        self.result.lock().ok()?.take()
+    }
+}
+
+impl Drop for PendingRebuild {
+    fn drop(&mut self) {
+        self.task.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::build_graph1;
+
+    fn run(
+        handle: &Graph,
+        keep: Keep,
+        previous: &DiGraph<NodeVis, ()>,
+    ) -> Rebuild {
+        let input = RebuildInput {
+            handle: handle.clone(),
+            view: GraphView::default(),
+            generation: 1,
+            keep,
+            previous: previous
+                .node_weights()
+                .map(|node| (node.key, node.clone()))
+                .collect(),
+            saved_positions: HashMap::default(),
+            previous_bends: HashMap::default(),
+            layered: None,
+        };
+        rebuild(input, &CancellationHandle::new()).unwrap()
+    }
+
+    fn positions(graph: &DiGraph<NodeVis, ()>) -> HashMap<VertexKey, Pos2> {
+        graph
+            .node_weights()
+            .map(|node| (node.key, node.world_pos))
+            .collect()
+    }
+
+    #[test]
+    fn test_incremental_rebuild() {
+        let handle = Graph::from(build_graph1());
+        let first = run(&handle, Keep::Positions, &DiGraph::new());
+        assert!(first.graph.node_count() > 0);
+
+        // unchanged vertices keep their positions
+        let mut shifted = first.graph.clone();
+        for node in shifted.node_weights_mut() {
+            node.world_pos += vec2(1000.0, 0.0);
+        }
+        let kept = run(&handle, Keep::Positions, &shifted);
+        assert_eq!(positions(&kept.graph), positions(&shifted));
+
+        // laying out again returns to the layout positions
+        let relaid = run(&handle, Keep::Nodes, &shifted);
+        assert_eq!(positions(&relaid.graph), positions(&first.graph));
+    }
+
+    #[test]
+    fn test_new_siblings_spread() {
+        let handle = Graph::from(build_graph1());
+        let first = run(&handle, Keep::Positions, &DiGraph::new());
+        // forget the children of a parent, they are placed again below it
+        let parent = first
+            .graph
+            .node_indices()
+            .max_by_key(|idx| first.graph.neighbors(*idx).count())
+            .unwrap();
+        let children: HashSet<VertexKey> = first
+            .graph
+            .neighbors(parent)
+            .map(|child| first.graph[child].key)
+            .collect();
+        assert!(children.len() > 1);
+        let mut previous = first.graph.clone();
+        previous.retain_nodes(|graph, idx| !children.contains(&graph[idx].key));
+
+        let placed = positions(&run(&handle, Keep::Positions, &previous).graph);
+        let child_positions: Vec<Pos2> =
+            children.iter().map(|key| placed[key]).collect();
+        for (i, a) in child_positions.iter().enumerate() {
+            for b in &child_positions[i + 1..] {
+                assert_ne!(a, b, "new siblings are placed on each other");
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Uniform grid over the node rects in world coordinates.
+//!
+//! Finds the nodes inside the viewport and the node under the pointer by
+//! visiting only the cells they overlap instead of every node.
+
+use context_trace::HashMap;
+use eframe::egui::{
+    Pos2,
+    Rect,
+};
+use petgraph::graph::NodeIndex;
+
+/// Side length of a grid cell in world coordinates
+const CELL_SIZE: f32 = 400.0;
+
+#[derive(Debug, Default)]
+pub(crate) struct SpatialIndex {
+    /// Node rects by node index
+    rects: Vec<Rect>,
+    cells: HashMap<(i32, i32), Vec<NodeIndex>>,
+    /// Union of all node rects
+    bounds: Rect,
+}
+
+fn cell(pos: Pos2) -> (i32, i32) {
+    (
+        (pos.x / CELL_SIZE).floor() as i32,
+        (pos.y / CELL_SIZE).floor() as i32,
+    )
+}
+
+/// Cells overlapped by `rect`
+fn cells_of(rect: Rect) -> impl Iterator<Item = (i32, i32)> {
+    let (min_x, min_y) = cell(rect.min);
+    let (max_x, max_y) = cell(rect.max);
+    (min_x..=max_x).flat_map(move |x| (min_y..=max_y).map(move |y| (x, y)))
+}
+
+impl SpatialIndex {
+    /// Index the rects of the nodes, in node index order
+    pub(crate) fn new(rects: impl IntoIterator<Item = Rect>) -> Self {
+        let rects: Vec<Rect> = rects.into_iter().collect();
+        let mut cells: HashMap<(i32, i32), Vec<NodeIndex>> =
+            HashMap::default();
+        let mut bounds = Rect::NOTHING;
+        for (i, rect) in rects.iter().enumerate() {
+            for cell in cells_of(*rect) {
+                cells.entry(cell).or_default().push(NodeIndex::new(i));
+            }
+            bounds = bounds.union(*rect);
+        }
+        Self {
+            rects,
+            cells,
+            bounds,
+        }
+    }
+
+    pub(crate) fn rect(
+        &self,
+        idx: NodeIndex,
+    ) -> Option<Rect> {
+        self.rects.get(idx.index()).copied()
+    }
+
+    /// Nodes overlapping `rect`, in drawing order
+    pub(crate) fn query(
+        &self,
+        rect: Rect,
+    ) -> Vec<NodeIndex> {
+        let rect = rect.intersect(self.bounds);
+        if !rect.is_positive() {
+            return Vec::new();
+        }
+        let mut found: Vec<NodeIndex> = cells_of(rect)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .filter(|idx| self.rects[idx.index()].intersects(rect))
+            .collect();
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    /// Topmost node containing `pos`
+    pub(crate) fn at(
+        &self,
+        pos: Pos2,
+    ) -> Option<NodeIndex> {
+        self.cells
+            .get(&cell(pos))?
+            .iter()
+            .copied()
+            .filter(|idx| self.rects[idx.index()].contains(pos))
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use eframe::egui::vec2;
+
+    use super::*;
+
+    #[test]
+    fn test_spatial_index() {
+        let index = SpatialIndex::new([
+            Rect::from_min_size(Pos2::new(0.0, 0.0), vec2(100.0, 50.0)),
+            Rect::from_min_size(Pos2::new(50.0, 20.0), vec2(100.0, 50.0)),
+            Rect::from_min_size(Pos2::new(2000.0, 0.0), vec2(100.0, 50.0)),
+        ]);
+
+        // later nodes are drawn on top
+        assert_eq!(index.at(Pos2::new(10.0, 10.0)), Some(NodeIndex::new(0)));
+        assert_eq!(index.at(Pos2::new(60.0, 30.0)), Some(NodeIndex::new(1)));
+        assert_eq!(index.at(Pos2::new(500.0, 30.0)), None);
+
+        let viewport =
+            Rect::from_min_size(Pos2::new(-100.0, -100.0), vec2(500.0, 500.0));
+        assert_eq!(
+            index.query(viewport),
+            [NodeIndex::new(0), NodeIndex::new(1)],
+        );
+        let far =
+            Rect::from_min_size(Pos2::new(1900.0, 40.0), vec2(1000.0, 1000.0));
+        assert_eq!(index.query(far), [NodeIndex::new(2)]);
+    }
+}
@@ -19,11 +19,42 @@ impl ShowStatus<'_> {
     ) {
         egui::Window::new("Status").show(ctx, |ui| {
             ui.label(format!("Texts: \"{:#?}\"", self.insert_texts));
-            ProcessStatus::iter()
-                .skip(1)
-                .for_each(|pass| self.show_pass(ui, pass))
+            if self.stages().is_empty() {
+                ProcessStatus::iter()
+                    .skip(1)
+                    .for_each(|pass| self.show_pass(ui, pass))
+            } else {
+                self.show_pass(ui, ProcessStatus::Containment);
+                for (i, name) in self.stages().iter().enumerate() {
+                    self.show_stage(ui, i, name);
+                }
+                self.show_pass(ui, ProcessStatus::Partitions);
+                self.show_pass(ui, ProcessStatus::Finished);
+            }
         });
     }
+    fn show_stage(
+        &self,
+        ui: &mut Ui,
+        index: usize,
+        name: &str,
+    ) {
+        let in_stages = *self.pass() > ProcessStatus::Containment
+            && *self.pass() < ProcessStatus::Partitions;
+        let checked =
+            *self.pass() >= ProcessStatus::Partitions || *self.stage() > index;
+        let text = if checked {
+            format!("{} Stage: 100%", name)
+        } else if in_stages && *self.stage() == index {
+            let percent = (*self.steps() as f32 / *self.steps_total() as f32
+                * 100.0) as u32;
+            format!("{} Stage: {}%", name, percent)
+        } else {
+            format!("{} Stage", name)
+        };
+        let mut checked = checked;
+        ui.checkbox(&mut checked, text);
+    }
     fn show_pass(
         &self,
         ui: &mut Ui,
@@ -50,6 +81,7 @@ impl ShowStatus<'_> {
                     .map(|p| format!(" Pass: {}%", p))
                     .unwrap_or(String::from(" Pass")))
         );
-        ui.checkbox(&mut (checked.clone()), text);
+        let mut checked = checked;
+        ui.checkbox(&mut checked, text);
     }
 }
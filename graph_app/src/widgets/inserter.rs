@@ -4,6 +4,7 @@ use eframe::egui::{
     self,
     Ui,
 };
+use ngrams::graph::estimate::Estimate;
 use strum::IntoEnumIterator;
 
 use crate::algorithm::Algorithm;
@@ -15,6 +16,8 @@ pub(crate) struct InserterResponse {
     pub(crate) run_clicked: bool,
     /// User clicked the cancel button.
     pub(crate) cancel_clicked: bool,
+    /// User clicked the pause or resume button.
+    pub(crate) pause_clicked: bool,
     /// User clicked the clear graph button.
     pub(crate) clear_clicked: bool,
     /// User clicked the test async button (wasm only).
@@ -30,6 +33,12 @@ pub(crate) struct Inserter<'a> {
     texts: &'a mut Vec<String>,
     /// Whether a task is currently running.
     is_running: bool,
+    /// Whether the running task is paused.
+    is_paused: bool,
+    /// Whether the running task can be paused.
+    is_pausable: bool,
+    /// Predicted resource use of the input texts.
+    estimate: Option<&'a Estimate>,
 }
 
 impl<'a> Inserter<'a> {
@@ -37,14 +46,36 @@ impl<'a> Inserter<'a> {
         selected_algorithm: &'a mut Algorithm,
         texts: &'a mut Vec<String>,
         is_running: bool,
+        is_paused: bool,
     ) -> Self {
         Self {
             selected_algorithm,
             texts,
             is_running,
+            is_paused,
+            is_pausable: false,
+            estimate: None,
         }
     }
 
+    /// Offer to pause the running task.
+    pub(crate) fn pausable(
+        mut self,
+        is_pausable: bool,
+    ) -> Self {
+        self.is_pausable = is_pausable;
+        self
+    }
+
+    /// Show a resource estimate below the input texts.
+    pub(crate) fn estimate(
+        mut self,
+        estimate: Option<&'a Estimate>,
+    ) -> Self {
+        self.estimate = estimate;
+        self
+    }
+
     pub(crate) fn show(
         self,
         ui: &mut Ui,
@@ -97,6 +128,11 @@ impl<'a> Inserter<'a> {
             self.texts.push(String::new());
         }
 
+        if let Some(estimate) = self.estimate {
+            ui.add_space(5.0);
+            Self::show_estimate(ui, estimate);
+        }
+
         ui.add_space(10.0);
         ui.separator();
         ui.add_space(5.0);
@@ -123,8 +159,24 @@ impl<'a> Inserter<'a> {
                 if ui.button("⏹ Cancel").clicked() {
                     response.cancel_clicked = true;
                 }
-                ui.spinner();
-                ui.label("Processing...");
+                // wasm runs on the main thread and cannot wait while paused
+                #[cfg(not(target_arch = "wasm32"))]
+                if self.is_pausable {
+                    let pause_text = if self.is_paused {
+                        "▶ Resume"
+                    } else {
+                        "⏸ Pause"
+                    };
+                    if ui.button(pause_text).clicked() {
+                        response.pause_clicked = true;
+                    }
+                }
+                if self.is_paused {
+                    ui.label("Paused");
+                } else {
+                    ui.spinner();
+                    ui.label("Processing...");
+                }
             }
         });
 
@@ -143,4 +195,25 @@ impl<'a> Inserter<'a> {
 
         response
     }
+
+    fn show_estimate(
+        ui: &mut Ui,
+        estimate: &Estimate,
+    ) {
+        let distinct = estimate
+            .distinct
+            .iter()
+            .enumerate()
+            .map(|(i, d)| format!("{}-grams: ~{}", i + 1, d))
+            .collect::<Vec<_>>()
+            .join("\n");
+        ui.label(format!(
+            "Estimate: ~{} entries, ~{} edges, ~{:.1} MB, ~{:.1} s",
+            estimate.vocab_entries,
+            estimate.edges,
+            estimate.bytes as f64 / 1e6,
+            estimate.seconds,
+        ))
+        .on_hover_text(distinct);
+    }
 }
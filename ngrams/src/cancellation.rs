@@ -3,19 +3,98 @@
 //! This module provides a unified interface for checking cancellation
 //! that works both on native platforms (using tokio_util::sync::CancellationToken)
 //! and in WebAssembly (using Arc<AtomicBool>).
+//!
+//! Both variants also carry a pause flag. While it is set, passes block in
+//! `LabellingCtx::check_cancelled` between two nodes and continue with the
+//! same queue once it is cleared. On wasm the run shares the main thread
+//! with the UI, which could never clear the flag while the run waits, so
+//! the flag is ignored there.
+//!
+//! [`Limits`] stop a run without anyone cancelling it, each with its own
+//! [`CancelReason`].
 
 use std::sync::{
     Arc,
     atomic::{AtomicBool, Ordering},
 };
+use std::time::Duration;
+
+use web_time::Instant;
+
+use crate::graph::{
+    traversal::pass::{
+        CancelReason,
+        RunResult,
+    },
+    StatusHandle,
+};
 
 #[cfg(not(target_arch = "wasm32"))]
 use tokio_util::sync::CancellationToken;
 
+/// Resource limits checked between the steps of a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Wall clock time at which the run stops
+    pub deadline: Option<Instant>,
+    /// Maximum number of steps of a single pass, as counted by the status
+    pub max_steps: Option<usize>,
+    /// Maximum number of vocabulary entries, approximating memory use
+    pub max_vocab_entries: Option<usize>,
+}
+impl Limits {
+    /// Stop the run once `timeout` has passed from now
+    pub fn timeout(
+        self,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            deadline: Some(Instant::now() + timeout),
+            ..self
+        }
+    }
+    pub fn max_steps(
+        self,
+        max_steps: usize,
+    ) -> Self {
+        Self {
+            max_steps: Some(max_steps),
+            ..self
+        }
+    }
+    pub fn max_vocab_entries(
+        self,
+        max_vocab_entries: usize,
+    ) -> Self {
+        Self {
+            max_vocab_entries: Some(max_vocab_entries),
+            ..self
+        }
+    }
+    pub(crate) fn check(
+        &self,
+        steps: usize,
+        vocab_entries: usize,
+    ) -> RunResult<()> {
+        if self.deadline.is_some_and(|d| Instant::now() >= d) {
+            Err(CancelReason::DeadlineExceeded)
+        } else if self.max_steps.is_some_and(|max| steps > max) {
+            Err(CancelReason::StepLimitExceeded)
+        } else if self.max_vocab_entries.is_some_and(|max| vocab_entries > max)
+        {
+            Err(CancelReason::MemoryLimitExceeded)
+        } else {
+            Ok(())
+        }
+    }
+}
+
 /// A cancellation handle that can be checked for cancellation
 pub(crate) trait Cancellable {
     /// Check if cancellation has been requested
     fn is_cancelled(&self) -> bool;
+    /// Check if the run should wait before the next step
+    fn is_paused(&self) -> bool;
 }
 
 /// Native cancellation using tokio's CancellationToken
@@ -23,17 +102,27 @@ pub(crate) trait Cancellable {
 #[derive(Debug, Clone)]
 pub(crate) struct NativeCancellation {
     token: CancellationToken,
+    paused: Arc<AtomicBool>,
+    limits: Limits,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 impl NativeCancellation {
     pub(crate) fn new(token: CancellationToken) -> Self {
-        Self { token }
+        Self {
+            token,
+            paused: Default::default(),
+            limits: Limits::default(),
+        }
     }
     
     pub(crate) fn token(&self) -> &CancellationToken {
         &self.token
     }
+
+    pub(crate) fn pause_flag(&self) -> &Arc<AtomicBool> {
+        &self.paused
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -41,6 +130,9 @@ impl Cancellable for NativeCancellation {
     fn is_cancelled(&self) -> bool {
         self.token.is_cancelled()
     }
+    fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -54,22 +146,35 @@ impl From<CancellationToken> for NativeCancellation {
 #[derive(Debug, Clone)]
 pub(crate) struct WasmCancellation {
     cancelled: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    limits: Limits,
 }
 
 impl WasmCancellation {
     pub(crate) fn new(cancelled: Arc<AtomicBool>) -> Self {
-        Self { cancelled }
+        Self {
+            cancelled,
+            paused: Default::default(),
+            limits: Limits::default(),
+        }
     }
     
     pub(crate) fn flag(&self) -> &Arc<AtomicBool> {
         &self.cancelled
     }
+
+    pub(crate) fn pause_flag(&self) -> &Arc<AtomicBool> {
+        &self.paused
+    }
 }
 
 impl Cancellable for WasmCancellation {
     fn is_cancelled(&self) -> bool {
         self.cancelled.load(Ordering::SeqCst)
     }
+    fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
 }
 
 impl From<Arc<AtomicBool>> for WasmCancellation {
@@ -108,6 +213,111 @@ impl Cancellation {
     pub(crate) fn none() -> Self {
         Self::None
     }
+
+    /// The platform variant with a handle nobody else holds
+    #[cfg(not(target_arch = "wasm32"))]
+    fn uncancelled() -> Self {
+        Self::native(CancellationToken::new())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn uncancelled() -> Self {
+        Self::wasm(Default::default())
+    }
+
+    /// Share the pause flag with another handle, e.g. one owned by a UI
+    ///
+    /// Has no effect on [`Cancellation::None`], which cannot be paused.
+    pub fn with_pause_flag(
+        mut self,
+        paused: Arc<AtomicBool>,
+    ) -> Self {
+        match &mut self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Cancellation::Native(c) => c.paused = paused,
+            Cancellation::Wasm(c) => c.paused = paused,
+            Cancellation::None => {},
+        }
+        self
+    }
+
+    /// Stop the run when one of the limits is reached
+    ///
+    /// [`Cancellation::None`] is replaced by the platform variant, which is
+    /// never cancelled, since it has nowhere to keep the limits.
+    pub fn with_limits(
+        self,
+        limits: Limits,
+    ) -> Self {
+        let mut this = match self {
+            Cancellation::None => Self::uncancelled(),
+            other => other,
+        };
+        match &mut this {
+            #[cfg(not(target_arch = "wasm32"))]
+            Cancellation::Native(c) => c.limits = limits,
+            Cancellation::Wasm(c) => c.limits = limits,
+            Cancellation::None => unreachable!(),
+        }
+        this
+    }
+
+    pub fn limits(&self) -> Limits {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Cancellation::Native(c) => c.limits,
+            Cancellation::Wasm(c) => c.limits,
+            Cancellation::None => Limits::default(),
+        }
+    }
+
+    /// Wait while paused, then fail if the run was cancelled or reached
+    /// one of its limits
+    pub(crate) fn check(
+        &self,
+        status: &StatusHandle,
+        vocab_entries: usize,
+    ) -> RunResult<()> {
+        self.wait_while_paused();
+        if self.is_cancelled() {
+            Err(CancelReason::Cancelled)
+        } else {
+            self.limits().check(*status.steps(), vocab_entries)
+        }
+    }
+
+    fn pause_flag(&self) -> Option<&Arc<AtomicBool>> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Cancellation::Native(c) => Some(c.pause_flag()),
+            Cancellation::Wasm(c) => Some(c.pause_flag()),
+            Cancellation::None => None,
+        }
+    }
+
+    /// Let the run wait before its next step
+    pub fn pause(&self) {
+        if let Some(flag) = self.pause_flag() {
+            flag.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Continue a paused run
+    pub fn resume(&self) {
+        if let Some(flag) = self.pause_flag() {
+            flag.store(false, Ordering::SeqCst);
+        }
+    }
+
+    /// Block while paused, returns early when cancelled
+    ///
+    /// Returns immediately on wasm, see the module documentation.
+    pub(crate) fn wait_while_paused(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        while self.is_paused() && !self.is_cancelled() {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
 }
 
 impl Cancellable for Cancellation {
@@ -119,6 +329,14 @@ impl Cancellable for Cancellation {
             Cancellation::None => false,
         }
     }
+    fn is_paused(&self) -> bool {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Cancellation::Native(c) => c.is_paused(),
+            Cancellation::Wasm(c) => c.is_paused(),
+            Cancellation::None => false,
+        }
+    }
 }
 
 impl Default for Cancellation {
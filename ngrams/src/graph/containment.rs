@@ -15,16 +15,19 @@ use serde::{
     Serialize,
 };
 
-use crate::graph::{
-    vocabulary::{
-        entry::{
-            HasVertexEntries,
-            VocabEntry,
+use crate::{
+    cancellation::Cancellation,
+    graph::{
+        vocabulary::{
+            entry::{
+                HasVertexEntries,
+                VocabEntry,
+            },
+            NGramId,
+            Vocabulary,
         },
-        NGramId,
-        Vocabulary,
+        Corpus,
     },
-    Corpus,
 };
 use context_trace::{
     graph::vertex::{
@@ -70,6 +73,7 @@ pub(crate) struct TextLocation {
 pub(crate) struct CorpusCtx<'a> {
     pub(crate) corpus: &'a Corpus,
     pub(crate) status: &'a mut StatusHandle,
+    pub(crate) cancellation: &'a Cancellation,
 }
 #[derive(Debug, Clone, Copy, From, Deref)]
 pub(crate) struct TextLevelCtx<'a> {
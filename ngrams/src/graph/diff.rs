@@ -0,0 +1,180 @@
+//! Structural diff of two hypergraphs keyed by vertex string
+//!
+//! Used to review how changes to the labelling affect the output. The diff
+//! lists vertices added or removed, labels gained or lost and vertices whose
+//! child patterns differ. It renders as text with one change per line or as
+//! JSON.
+
+use std::fmt;
+
+use context_trace::{
+    graph::{
+        vertex::key::VertexKey,
+        Hypergraph,
+    },
+    HashSet,
+};
+use itertools::Itertools;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::graph::{
+    strings::StringGraph,
+    ParseResult,
+};
+
+/// Child patterns of a vertex present in both graphs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatternChange {
+    pub string: String,
+    pub added: Vec<Vec<String>>,
+    pub removed: Vec<Vec<String>>,
+}
+
+/// Changes from an old to a new graph, sorted by string
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub labels_gained: Vec<String>,
+    pub labels_lost: Vec<String>,
+    pub patterns: Vec<PatternChange>,
+}
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.labels_gained.is_empty()
+            && self.labels_lost.is_empty()
+            && self.patterns.is_empty()
+    }
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} removed, {} labels gained, {} labels lost, {} patterns changed",
+            self.added.len(),
+            self.removed.len(),
+            self.labels_gained.len(),
+            self.labels_lost.len(),
+            self.patterns.len(),
+        )
+    }
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("diffs always serialize")
+    }
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+fn pattern_string(pattern: &[String]) -> String {
+    pattern.iter().map(|c| format!("{:?}", c)).join(" ")
+}
+/// One change per line, prefixed by `+`/`-` for vertices, `+label`/
+/// `-label` for labels and `~` for vertices with changed patterns
+impl fmt::Display for GraphDiff {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        writeln!(f, "{}", self.summary())?;
+        for string in &self.added {
+            writeln!(f, "+ {:?}", string)?;
+        }
+        for string in &self.removed {
+            writeln!(f, "- {:?}", string)?;
+        }
+        for string in &self.labels_gained {
+            writeln!(f, "+label {:?}", string)?;
+        }
+        for string in &self.labels_lost {
+            writeln!(f, "-label {:?}", string)?;
+        }
+        for change in &self.patterns {
+            writeln!(f, "~ {:?}", change.string)?;
+            for pattern in &change.added {
+                writeln!(f, "    + {}", pattern_string(pattern))?;
+            }
+            for pattern in &change.removed {
+                writeln!(f, "    - {}", pattern_string(pattern))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl StringGraph {
+    /// Changes needed to turn this graph into `new`
+    pub fn diff(
+        &self,
+        new: &StringGraph,
+    ) -> GraphDiff {
+        let old = &self.vertices;
+        let new = &new.vertices;
+        let added = new
+            .keys()
+            .filter(|s| !old.contains_key(*s))
+            .cloned()
+            .collect();
+        let removed = old
+            .keys()
+            .filter(|s| !new.contains_key(*s))
+            .cloned()
+            .collect();
+        let labels_gained = new
+            .iter()
+            .filter(|(s, v)| v.label && !old.get(*s).is_some_and(|o| o.label))
+            .map(|(s, _)| s.clone())
+            .collect();
+        let labels_lost = old
+            .iter()
+            .filter(|(s, v)| v.label && !new.get(*s).is_some_and(|n| n.label))
+            .map(|(s, _)| s.clone())
+            .collect();
+        let patterns = old
+            .iter()
+            .filter_map(|(string, o)| {
+                let n = new.get(string)?;
+                (o.patterns != n.patterns).then(|| PatternChange {
+                    string: string.clone(),
+                    added: n
+                        .patterns
+                        .difference(&o.patterns)
+                        .cloned()
+                        .collect(),
+                    removed: o
+                        .patterns
+                        .difference(&n.patterns)
+                        .cloned()
+                        .collect(),
+                })
+            })
+            .collect();
+        GraphDiff {
+            added,
+            removed,
+            labels_gained,
+            labels_lost,
+            patterns,
+        }
+    }
+}
+
+/// Diff two hypergraphs with their labels
+pub fn diff_graphs(
+    old: (&Hypergraph, &HashSet<VertexKey>),
+    new: (&Hypergraph, &HashSet<VertexKey>),
+) -> GraphDiff {
+    StringGraph::from_hypergraph(old.0, old.1)
+        .diff(&StringGraph::from_hypergraph(new.0, new.1))
+}
+
+impl ParseResult {
+    /// Diff the partition graphs and labels of two results
+    pub fn diff(
+        &self,
+        new: &ParseResult,
+    ) -> GraphDiff {
+        diff_graphs((&self.graph, &self.labels), (&new.graph, &new.labels))
+    }
+}
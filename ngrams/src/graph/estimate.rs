@@ -0,0 +1,247 @@
+//! Pre-flight estimate of the resources a parse of a corpus needs
+//!
+//! The containment pass stores every distinct n-gram of every order, which
+//! grows quadratically with the text length. The estimator counts distinct
+//! n-grams per order with a k-minimum-values sketch over polynomial hashes,
+//! keeping `sketch_size` hashes per order. Orders with fewer distinct
+//! n-grams than the sketch size are counted exactly.
+//!
+//! Hashing every order of a text of length `L` takes `O(L²)`, so only the
+//! orders up to `exact_orders` are all counted. Above them the orders are
+//! sampled at doubling distances, each in `O(L)` using prefix hashes, and
+//! the orders in between are interpolated linearly. The occurrences are
+//! computed in closed form and stay exact.
+
+use std::collections::BTreeSet;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::graph::Corpus;
+
+/// Rough heap size of a vocabulary entry with its vertex and id, without
+/// the n-gram string and occurrences
+const ENTRY_BYTES: usize = 320;
+/// Rough size of a stored occurrence in the occurrence set
+const OCCURRENCE_BYTES: usize = 24;
+/// Rough size of a child token plus the parent entry pointing back
+const EDGE_BYTES: usize = 64;
+/// Rough time of the containment pass per n-gram occurrence
+const SECONDS_PER_STEP: f64 = 2e-6;
+
+/// Predicted size of a parse
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Estimate {
+    /// Highest n-gram order included
+    pub max_order: usize,
+    /// Estimated distinct n-grams per order, starting at order 1
+    pub distinct: Vec<usize>,
+    /// N-gram occurrences, i.e. steps of the containment pass (exact)
+    pub occurrences: usize,
+    /// Estimated vocabulary entries
+    pub vocab_entries: usize,
+    /// Estimated child patterns in the containment graph
+    pub patterns: usize,
+    /// Estimated child edges in the containment graph
+    pub edges: usize,
+    /// Estimated memory of the vocabulary
+    pub bytes: usize,
+    /// Estimated duration of the containment pass
+    pub seconds: f64,
+}
+
+/// K-minimum-values sketch of a set of 64 bit hashes
+#[derive(Debug, Clone)]
+struct Sketch {
+    size: usize,
+    min: BTreeSet<u64>,
+}
+impl Sketch {
+    fn new(size: usize) -> Self {
+        Self {
+            size: size.max(2),
+            min: BTreeSet::new(),
+        }
+    }
+    fn insert(
+        &mut self,
+        hash: u64,
+    ) {
+        if self.min.len() < self.size {
+            self.min.insert(hash);
+        } else if hash < *self.min.last().unwrap() && self.min.insert(hash) {
+            self.min.pop_last();
+        }
+    }
+    fn estimate(&self) -> usize {
+        if self.min.len() < self.size {
+            self.min.len()
+        } else {
+            let kth = *self.min.last().unwrap() as f64 / u64::MAX as f64;
+            ((self.size - 1) as f64 / kth).round() as usize
+        }
+    }
+}
+
+/// Final mixing step of splitmix64, spreads rolling hashes over the range
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// Orders to count, all up to `exact` then doubling up to `max`
+fn sampled_orders(
+    exact: usize,
+    max: usize,
+) -> Vec<usize> {
+    let exact = exact.max(1);
+    let mut orders: Vec<usize> = (1..=exact.min(max)).collect();
+    let mut n = exact;
+    while n < max {
+        n = (n * 2).min(max);
+        orders.push(n);
+    }
+    orders
+}
+
+/// Distinct counts of all orders up to the last sampled one, interpolating
+/// linearly between the sampled orders
+fn interpolate(
+    orders: &[usize],
+    sampled: &[usize],
+) -> Vec<usize> {
+    let mut distinct = Vec::new();
+    // orders start at 1, so nothing is interpolated from order 0
+    let (mut prev_n, mut prev_d) = (0, 0);
+    for (&n, &d) in orders.iter().zip(sampled) {
+        for m in prev_n + 1..n {
+            let t = (m - prev_n) as f64 / (n - prev_n) as f64;
+            let value = prev_d as f64 + t * (d as f64 - prev_d as f64);
+            distinct.push(value.round() as usize);
+        }
+        distinct.push(d);
+        (prev_n, prev_d) = (n, d);
+    }
+    distinct
+}
+
+/// Estimates resource use of parsing corpora
+#[derive(Debug, Clone)]
+pub struct Estimator {
+    /// Number of hashes kept per order
+    pub sketch_size: usize,
+    /// Highest n-gram order, defaults to the longest text
+    pub max_order: Option<usize>,
+    /// Orders counted one by one, higher orders are sampled
+    pub exact_orders: usize,
+}
+impl Default for Estimator {
+    fn default() -> Self {
+        Self {
+            sketch_size: 1024,
+            max_order: None,
+            exact_orders: 32,
+        }
+    }
+}
+impl Estimator {
+    pub fn max_order(
+        self,
+        max_order: Option<usize>,
+    ) -> Self {
+        Self { max_order, ..self }
+    }
+    pub fn sketch_size(
+        self,
+        sketch_size: usize,
+    ) -> Self {
+        Self {
+            sketch_size,
+            ..self
+        }
+    }
+    pub fn exact_orders(
+        self,
+        exact_orders: usize,
+    ) -> Self {
+        Self {
+            exact_orders,
+            ..self
+        }
+    }
+    pub fn estimate(
+        &self,
+        corpus: &Corpus,
+    ) -> Estimate {
+        const BASE: u64 = 0x100000001b3;
+        let texts: Vec<Vec<char>> =
+            corpus.iter().map(|t| t.chars().collect()).collect();
+        let longest = texts.iter().map(Vec::len).max().unwrap_or(0);
+        let max_order = self.max_order.unwrap_or(longest).min(longest);
+        let orders = sampled_orders(self.exact_orders, max_order);
+        let mut sketches = vec![Sketch::new(self.sketch_size); orders.len()];
+        let mut occurrences = 0;
+        for text in &texts {
+            // n-grams of all orders up to k starting at each position
+            let k = max_order.min(text.len());
+            occurrences += k * (text.len() + 1) - k * (k + 1) / 2;
+            // hash of the n-gram at `start` is
+            // prefix[start + n] - prefix[start] * BASE^n
+            let mut prefix = Vec::with_capacity(text.len() + 1);
+            prefix.push(0u64);
+            for &c in text {
+                let last = *prefix.last().unwrap();
+                prefix.push(last.wrapping_mul(BASE).wrapping_add(c as u64 + 1));
+            }
+            for (&n, sketch) in orders.iter().zip(&mut sketches) {
+                if n > text.len() {
+                    break;
+                }
+                let power = BASE.wrapping_pow(n as u32);
+                for start in 0..=(text.len() - n) {
+                    let hash = prefix[start + n]
+                        .wrapping_sub(prefix[start].wrapping_mul(power));
+                    sketch.insert(mix(hash));
+                }
+            }
+        }
+        let sampled: Vec<usize> =
+            sketches.iter().map(Sketch::estimate).collect();
+        let distinct = interpolate(&orders, &sampled);
+        let vocab_entries = distinct.iter().sum();
+        let wide: usize = distinct.iter().skip(2).sum();
+        let bigrams = distinct.get(1).copied().unwrap_or(0);
+        let patterns = bigrams + 2 * wide;
+        let edges = 2 * patterns;
+        let string_bytes: usize = distinct
+            .iter()
+            .enumerate()
+            .map(|(i, d)| d * (i + 1))
+            .sum();
+        Estimate {
+            max_order,
+            occurrences,
+            vocab_entries,
+            patterns,
+            edges,
+            bytes: vocab_entries * ENTRY_BYTES
+                + string_bytes
+                + occurrences * OCCURRENCE_BYTES
+                + edges * EDGE_BYTES,
+            seconds: occurrences as f64 * SECONDS_PER_STEP,
+            distinct,
+        }
+    }
+}
+impl Corpus {
+    /// Estimate the resources needed to parse this corpus
+    pub fn estimate(
+        &self,
+        max_order: Option<usize>,
+    ) -> Estimate {
+        Estimator::default().max_order(max_order).estimate(self)
+    }
+}
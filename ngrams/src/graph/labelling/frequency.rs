@@ -11,7 +11,13 @@ use tokio_util::sync::CancellationToken;
 
 use crate::graph::{
     containment::TextLocation,
-    labelling::LabellingCtx,
+    labelling::{
+        pipeline::{
+            FrequencyCriterion,
+            FrequencyParams,
+        },
+        LabellingCtx,
+    },
     traversal::{
         direction::{
             BottomUp,
@@ -48,6 +54,7 @@ use context_trace::{
         wide::Wide,
         VertexIndex,
     },
+    HashMap,
     HashSet,
 };
 
@@ -56,9 +63,15 @@ pub(crate) struct FrequencyCtx<'b> {
     #[deref]
     #[deref_mut]
     pub(crate) ctx: &'b mut LabellingCtx,
+    #[new(default)]
+    pub(crate) params: FrequencyParams,
+    /// Total n-gram counts per width
+    #[new(default)]
+    width_totals: HashMap<usize, usize>,
 }
 
 impl TraversalPass for FrequencyCtx<'_> {
+    type Ctx = LabellingCtx;
     type Node = VertexKey;
     type NextNode = NGramId;
     type Queue = SortedQueue;
@@ -66,14 +79,14 @@ impl TraversalPass for FrequencyCtx<'_> {
         self.ctx
     }
     fn start_queue(&mut self) -> RunResult<Self::Queue> {
+        self.count_widths();
         let start = TopDown::starting_nodes(self.vocab());
 
         let mut queue = SortedQueue::default();
         for node in start.iter() {
             queue.extend_layer(self.on_node(node)?.unwrap_or_default());
         }
-        self.corpus
-            .image
+        self.image
             .labels
             .extend(start.iter().map(HasVertexKey::vertex_key));
 
@@ -111,7 +124,16 @@ impl TraversalPass for FrequencyCtx<'_> {
         Ok(())
     }
 }
-impl FrequencyCtx<'_> {
+impl<'b> FrequencyCtx<'b> {
+    pub(crate) fn with_params(
+        ctx: &'b mut LabellingCtx,
+        params: FrequencyParams,
+    ) -> Self {
+        Self {
+            params,
+            ..Self::new(ctx)
+        }
+    }
     pub(crate) fn entry_next(
         &self,
         entry: &VertexCtx,
@@ -125,14 +147,96 @@ impl FrequencyCtx<'_> {
         &self,
         entry: &VertexCtx,
     ) -> RunResult<bool> {
-        FrequencyCover::from_entry(self, entry).map(|cover| {
-            cover
-                .iter()
-                .any(|p| {
-                    self.vocab().get_vertex(p).unwrap().count() < entry.count()
-                })
-                .then(|| println!("{}", entry.ngram))
-                .is_some()
-        })
+        if entry.count() < self.params.min_count {
+            return Ok(false);
+        }
+        let frequent = match self.params.criterion {
+            FrequencyCriterion::Cover => self.cover_ratio(entry)? < 1.0,
+            FrequencyCriterion::CoverRatio(ratio) =>
+                self.cover_ratio(entry)? < ratio,
+            FrequencyCriterion::MinCount(min) => entry.count() >= min,
+            FrequencyCriterion::Pmi(threshold) =>
+                self.pmi(entry).is_some_and(|pmi| pmi >= threshold),
+            FrequencyCriterion::BranchingEntropy(threshold) =>
+                self.branching_entropy(entry) >= threshold,
+        };
+        if frequent {
+            println!("{}", entry.ngram);
+        }
+        Ok(frequent)
+    }
+    /// Smallest count of an n-gram in the frequency cover relative to the
+    /// count of the entry, infinite for empty covers
+    fn cover_ratio(
+        &self,
+        entry: &VertexCtx,
+    ) -> RunResult<f64> {
+        let cover = FrequencyCover::from_entry(self, entry)?;
+        Ok(cover
+            .iter()
+            .map(|p| self.vocab().get_vertex(p).unwrap().count() as f64)
+            .map(|c| c / entry.count() as f64)
+            .fold(f64::INFINITY, f64::min))
+    }
+    /// Total count of all n-grams per width
+    fn count_widths(&mut self) {
+        let vocab = self.ctx.vocab();
+        let mut totals = HashMap::default();
+        for entry in vocab.entries.values() {
+            *totals.entry(entry.ngram.chars().count()).or_insert(0) +=
+                entry.count();
+        }
+        self.width_totals = totals;
+    }
+    /// Probability of an n-gram among all n-grams of the same width
+    fn probability(
+        &self,
+        key: &VertexKey,
+    ) -> f64 {
+        let vertex = self.vocab().get_vertex(key).unwrap();
+        let total = self
+            .width_totals
+            .get(&vertex.width().0)
+            .copied()
+            .unwrap_or_default();
+        vertex.count() as f64 / total.max(1) as f64
+    }
+    /// Pointwise mutual information of the child pattern with the highest
+    /// independent probability
+    fn pmi(
+        &self,
+        entry: &VertexCtx,
+    ) -> Option<f64> {
+        let p = self.probability(&entry.data.vertex_key());
+        let containment = &self.vocab().containment;
+        entry
+            .data
+            .child_patterns()
+            .values()
+            .map(|pattern| {
+                let independent: f64 = pattern
+                    .iter()
+                    .map(|c| containment.expect_key_for_index(c))
+                    .map(|c| self.probability(&c))
+                    .product();
+                (p / independent).ln()
+            })
+            .reduce(f64::min)
+    }
+    fn branching_entropy(
+        &self,
+        entry: &VertexCtx,
+    ) -> f64 {
+        let counts = BottomUp::next_nodes(entry)
+            .iter()
+            .map(|p| self.vocab().get_vertex(p).unwrap().count() as f64)
+            .collect_vec();
+        let total: f64 = counts.iter().sum();
+        counts
+            .iter()
+            .filter(|c| **c > 0.0)
+            .map(|c| c / total)
+            .map(|p| -p * p.ln())
+            .sum()
     }
 }
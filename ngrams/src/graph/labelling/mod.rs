@@ -1,7 +1,3 @@
-use derive_more::derive::{
-    Deref,
-    DerefMut,
-};
 use derive_new::new;
 use itertools::Itertools;
 use serde::{
@@ -23,7 +19,7 @@ use crate::{
     graph::{
         partitions::PartitionsCtx,
         traversal::pass::{
-            CancelReason,
+            PassCtx,
             RunResult,
             TraversalPass,
         },
@@ -32,6 +28,8 @@ use crate::{
             Vocabulary,
         },
         Corpus,
+        PausedLabels,
+        PausedLabelsSlot,
         Status,
     },
     storage::{
@@ -39,7 +37,6 @@ use crate::{
         Storage,
         StorageError,
     },
-    tests::TestCorpus,
 };
 use context_trace::{
     graph::{
@@ -52,9 +49,38 @@ use context_trace::{
     HashSet,
 };
 
+impl PassCtx for LabellingCtx {
+    type Graph = Vocabulary;
+    fn graph(&self) -> &Vocabulary {
+        self.vocab()
+    }
+    fn status(&self) -> &StatusHandle {
+        LabellingCtx::status(self)
+    }
+    fn check_cancelled(&self) -> RunResult<()> {
+        LabellingCtx::check_cancelled(self)
+    }
+}
+
+/// A pass defined outside of this crate
+///
+/// Custom passes run on the [`LabellingCtx`] after the built in labelling
+/// passes and before the partition pass, so labels they add are included
+/// in the partition graph. Implementations usually build a
+/// [`TraversalPass`] over the vocabulary in [`CustomPass::run`].
+pub trait CustomPass: std::fmt::Debug + Send + Sync {
+    fn name(&self) -> &str;
+    fn run(
+        &self,
+        ctx: &mut LabellingCtx,
+    ) -> RunResult<()>;
+}
+
 pub(crate) mod frequency;
 use frequency::FrequencyCtx;
 
+pub mod pipeline;
+
 pub(crate) mod wrapper;
 use wrapper::WrapperCtx;
 
@@ -69,7 +95,7 @@ impl From<Vocabulary> for LabellingImage {
     }
 }
 #[derive(Debug, Serialize, Deserialize)]
-pub(crate) struct LabellingImage {
+pub struct LabellingImage {
     pub(crate) vocab: Vocabulary,
     pub(crate) labels: HashSet<VertexKey>,
 }
@@ -97,19 +123,36 @@ impl LabellingImage {
     pub(crate) fn from_corpus(
         corpus: &Corpus,
         status: &mut StatusHandle,
+        cancellation: &Cancellation,
     ) -> RunResult<Self> {
+        let (image, result) =
+            Self::partial_from_corpus(corpus, status, cancellation);
+        result.map(|_| image)
+    }
+    /// Read or build the image, keeping the vocabulary built so far when
+    /// the containment pass stops
+    pub(crate) fn partial_from_corpus(
+        corpus: &Corpus,
+        status: &mut StatusHandle,
+        cancellation: &Cancellation,
+    ) -> (Self, RunResult<()>) {
         // On native, try to read from storage cache first (only if cache is enabled)
         #[cfg(not(target_arch = "wasm32"))]
         if CacheConfig::is_enabled() {
             let key = corpus.name.clone();
             if let Ok(image) = Self::read_from_storage(&key) {
-                println!("Containment Pass already processed.");
-                return Ok(image);
+                if image.vocab.preprocessing == corpus.preprocessing {
+                    println!("Containment Pass already processed.");
+                    return (image, Ok(()));
+                }
+                println!("Cached image was preprocessed differently.");
             }
         }
 
         // Create fresh from corpus
-        Ok(Self::from(Vocabulary::from_corpus(corpus, status)?))
+        let (vocab, result) =
+            Vocabulary::partial_from_corpus(corpus, status, cancellation);
+        (Self::from(vocab), result)
     }
 
     /// Write to the target storage location for this image (only if cache is enabled)
@@ -121,13 +164,15 @@ impl LabellingImage {
         }
     }
 }
-#[derive(Debug, Deref, DerefMut, new)]
-pub(crate) struct LabellingCtx {
-    #[deref]
-    #[deref_mut]
-    pub(crate) corpus: TestCorpus,
+/// State of a labelling run, passed to every pass
+#[derive(Debug, new)]
+pub struct LabellingCtx {
+    pub(crate) image: LabellingImage,
+    pub(crate) corpus: Corpus,
     pub(crate) status: StatusHandle,
     pub(crate) cancellation: Cancellation,
+    #[new(default)]
+    pub(crate) paused_labels: Option<PausedLabelsSlot>,
 }
 impl LabellingCtx {
     pub(crate) fn from_corpus(
@@ -135,30 +180,61 @@ impl LabellingCtx {
         cancellation: impl Into<Cancellation>,
     ) -> RunResult<Self> {
         let mut status = StatusHandle::default();
+        let cancellation = cancellation.into();
         Ok(Self {
-            corpus: TestCorpus::new(
-                LabellingImage::from_corpus(&corpus, &mut status)?,
-                corpus,
-            ),
+            image: LabellingImage::from_corpus(
+                &corpus,
+                &mut status,
+                &cancellation,
+            )?,
+            corpus,
             status,
-            cancellation: cancellation.into(),
+            cancellation,
+            paused_labels: None,
         })
     }
-    pub(crate) fn check_cancelled(&self) -> RunResult<()> {
-        if self.cancellation.is_cancelled() {
-            Err(CancelReason::Cancelled)
-        } else {
-            Ok(())
+    /// Waits while the run is paused and fails once it is cancelled or
+    /// reaches one of its limits
+    pub fn check_cancelled(&self) -> RunResult<()> {
+        if self.cancellation.is_paused() {
+            self.publish_paused();
+        }
+        self.cancellation
+            .check(&self.status, self.vocab().entries.len())
+    }
+    /// Share the labels found so far with whoever shows the paused run
+    fn publish_paused(&self) {
+        if let Some(slot) = &self.paused_labels {
+            *slot.write().unwrap() = Some(PausedLabels {
+                containment: self.vocab().containment.clone(),
+                labels: self.labels().clone(),
+            });
         }
     }
-    pub(crate) fn vocab(&self) -> &'_ Vocabulary {
-        &self.corpus.image.vocab
+    pub fn vocab(&self) -> &'_ Vocabulary {
+        &self.image.vocab
+    }
+    pub fn labels(&self) -> &'_ HashSet<VertexKey> {
+        &self.image.labels
+    }
+    pub fn labels_mut(&mut self) -> &'_ mut HashSet<VertexKey> {
+        &mut self.image.labels
     }
-    pub(crate) fn labels(&self) -> &'_ HashSet<VertexKey> {
-        &self.corpus.image.labels
+    /// Corpus the vocabulary was built from
+    pub fn corpus(&self) -> &Corpus {
+        &self.corpus
     }
-    pub(crate) fn labels_mut(&mut self) -> &'_ mut HashSet<VertexKey> {
-        &mut self.corpus.image.labels
+    pub fn status(&self) -> &StatusHandle {
+        &self.status
+    }
+    /// Run a pass registered through [`crate::graph::ParseOptions`]
+    pub fn run_custom(
+        &mut self,
+        pass: &dyn CustomPass,
+    ) -> RunResult<()> {
+        println!("Running {} Pass", pass.name());
+        pass.run(self)?;
+        self.check_cancelled()
     }
     pub(crate) fn label_freq(&mut self) -> RunResult<()> {
         if *self.status.pass() < ProcessStatus::Frequency {
@@ -179,8 +255,15 @@ impl LabellingCtx {
         Ok(())
     }
     pub(crate) fn label_part(&mut self) -> RunResult<Hypergraph> {
+        let (graph, result) = self.label_part_partial();
+        result.map(|_| graph)
+    }
+    /// Run the partition pass and keep the graph built so far when it stops
+    pub(crate) fn label_part_partial(
+        &mut self
+    ) -> (Hypergraph, RunResult<()>) {
         let mut ctx = PartitionsCtx::from(&mut *self);
-        ctx.run()?;
-        Ok(ctx.graph)
+        let result = ctx.run();
+        (ctx.graph, result)
     }
 }
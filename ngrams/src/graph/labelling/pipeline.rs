@@ -0,0 +1,273 @@
+//! Configurable sequence of labelling stages
+//!
+//! A [`Pipeline`] lists the stages run between the containment and the
+//! partition pass. It can be built in code or read from JSON, e.g.
+//!
+//! ```json
+//! { "stages": [
+//!     { "Frequency": { "min_count": 1 } },
+//!     "Wrappers",
+//!     { "Frequency": { "min_count": 3, "criterion": { "Pmi": 0.5 } } },
+//!     { "Prune": { "min_count": 2, "min_width": 1 } },
+//!     { "Custom": "Bigram" }
+//! ] }
+//! ```
+
+use std::sync::Arc;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use context_trace::{
+    graph::vertex::{
+        has_vertex_key::HasVertexKey,
+        key::VertexKey,
+        wide::Wide,
+    },
+    HashSet,
+};
+
+use crate::graph::{
+    labelling::{
+        frequency::FrequencyCtx,
+        wrapper::WrapperCtx,
+        CustomPass,
+        LabellingCtx,
+    },
+    traversal::pass::{
+        CancelReason,
+        RunResult,
+        TraversalPass,
+    },
+    vocabulary::{
+        entry::HasVertexEntries,
+        ProcessStatus,
+    },
+};
+
+/// Decides whether the frequency pass labels an n-gram
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum FrequencyCriterion {
+    /// Some n-gram of the frequency cover occurs less often
+    #[default]
+    Cover,
+    /// The n-gram occurs at least this many times
+    MinCount(usize),
+    /// Some n-gram of the frequency cover occurs less than `ratio` times
+    /// as often. A ratio of 1 is the same as [`Self::Cover`].
+    CoverRatio(f64),
+    /// Pointwise mutual information of the weakest child pattern,
+    /// `ln(P(w) / (P(c1) * .. * P(ck)))`, is at least the threshold
+    Pmi(f64),
+    /// Entropy of the counts of the parents one character wider is at
+    /// least the threshold
+    BranchingEntropy(f64),
+}
+
+/// Parameters of a frequency labelling stage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrequencyParams {
+    /// N-grams occurring fewer times are never labelled
+    pub min_count: usize,
+    pub criterion: FrequencyCriterion,
+}
+impl Default for FrequencyParams {
+    fn default() -> Self {
+        Self {
+            min_count: 1,
+            criterion: FrequencyCriterion::default(),
+        }
+    }
+}
+impl FrequencyParams {
+    pub fn criterion(criterion: FrequencyCriterion) -> Self {
+        Self {
+            criterion,
+            ..Default::default()
+        }
+    }
+}
+
+/// Parameters of a pruning stage
+///
+/// Removes labels which occur less than `min_count` times or are narrower
+/// than `min_width`. Leaves and roots are always kept.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PruneParams {
+    pub min_count: usize,
+    pub min_width: usize,
+}
+impl Default for PruneParams {
+    fn default() -> Self {
+        Self {
+            min_count: 1,
+            min_width: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Stage {
+    Frequency(FrequencyParams),
+    Wrappers,
+    Prune(PruneParams),
+    /// A [`CustomPass`] registered under this name
+    Custom(String),
+}
+impl Stage {
+    pub fn name(&self) -> String {
+        match self {
+            Self::Frequency(_) => "Frequency".to_string(),
+            Self::Wrappers => "Wrappers".to_string(),
+            Self::Prune(_) => "Prune".to_string(),
+            Self::Custom(name) => name.clone(),
+        }
+    }
+    /// Pass recorded in the status while the stage runs
+    fn pass(&self) -> Option<ProcessStatus> {
+        match self {
+            Self::Frequency(_) => Some(ProcessStatus::Frequency),
+            Self::Wrappers => Some(ProcessStatus::Wrappers),
+            Self::Prune(_) | Self::Custom(_) => None,
+        }
+    }
+}
+
+/// Labelling stages in the order they run
+///
+/// The default pipeline runs the frequency and the wrapper pass.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pipeline {
+    pub stages: Vec<Stage>,
+}
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self::empty()
+            .frequency(FrequencyParams::default())
+            .wrappers()
+    }
+}
+impl Pipeline {
+    /// A pipeline without labelling stages
+    pub fn empty() -> Self {
+        Self { stages: Vec::new() }
+    }
+    pub fn stage(
+        mut self,
+        stage: Stage,
+    ) -> Self {
+        self.stages.push(stage);
+        self
+    }
+    pub fn frequency(
+        self,
+        params: FrequencyParams,
+    ) -> Self {
+        self.stage(Stage::Frequency(params))
+    }
+    pub fn wrappers(self) -> Self {
+        self.stage(Stage::Wrappers)
+    }
+    pub fn prune(
+        self,
+        params: PruneParams,
+    ) -> Self {
+        self.stage(Stage::Prune(params))
+    }
+    pub fn custom(
+        self,
+        name: impl ToString,
+    ) -> Self {
+        self.stage(Stage::Custom(name.to_string()))
+    }
+    pub fn stage_names(&self) -> Vec<String> {
+        self.stages.iter().map(Stage::name).collect()
+    }
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self)
+            .expect("pipelines always serialize")
+    }
+}
+
+impl LabellingCtx {
+    /// Run all stages of a pipeline, resolving custom stages by name
+    pub fn run_pipeline(
+        &mut self,
+        pipeline: &Pipeline,
+        passes: &[Arc<dyn CustomPass>],
+    ) -> RunResult<()> {
+        // Passes reached by a loaded image are not run again
+        let resumed = *self.status.pass();
+        self.status.set_stages(pipeline.stage_names());
+        for (i, stage) in pipeline.stages.iter().enumerate() {
+            self.check_cancelled()?;
+            self.status.next_stage(i);
+            if stage.pass().is_some_and(|pass| pass <= resumed) {
+                println!("{} Pass already processed.", stage.name());
+                continue;
+            }
+            self.run_stage(stage, passes)?;
+            let _ = self.image.write_to_target_file();
+        }
+        self.status.next_stage(pipeline.stages.len());
+        Ok(())
+    }
+    pub fn run_stage(
+        &mut self,
+        stage: &Stage,
+        passes: &[Arc<dyn CustomPass>],
+    ) -> RunResult<()> {
+        match stage {
+            Stage::Frequency(params) =>
+                FrequencyCtx::with_params(self, params.clone()).run(),
+            Stage::Wrappers => WrapperCtx::new(self).run(),
+            Stage::Prune(params) => {
+                self.prune_labels(params);
+                Ok(())
+            },
+            Stage::Custom(name) => {
+                let pass = passes
+                    .iter()
+                    .find(|p| p.name() == name)
+                    .ok_or_else(|| {
+                        println!("No pass registered as {:?}", name);
+                        CancelReason::Error
+                    })?
+                    .clone();
+                self.run_custom(pass.as_ref())
+            },
+        }
+    }
+    fn prune_labels(
+        &mut self,
+        params: &PruneParams,
+    ) {
+        println!("Prune Pass");
+        let vocab = self.vocab();
+        let keep = |key: &VertexKey| {
+            let vertex = vocab.expect_vertex(key);
+            vertex.count() >= params.min_count
+                && vertex.width().0 >= params.min_width
+        };
+        let fixed: HashSet<_> = vocab
+            .leaves
+            .iter()
+            .chain(vocab.roots.iter())
+            .map(HasVertexKey::vertex_key)
+            .collect();
+        let labels: Vec<_> = self
+            .labels()
+            .iter()
+            .filter(|key| fixed.contains(key) || keep(key))
+            .copied()
+            .collect();
+        *self.labels_mut() = labels.into_iter().collect();
+    }
+}
@@ -69,6 +69,7 @@ impl VisitTracking for WrapperCtx<'_> {
     }
 }
 impl TraversalPass for WrapperCtx<'_> {
+    type Ctx = LabellingCtx;
     type Node = VertexKey;
     type NextNode = VertexKey;
     type Queue = LayeredQueue<Self>;
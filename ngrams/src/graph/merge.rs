@@ -0,0 +1,182 @@
+//! Merging parse results of several corpora into one graph
+//!
+//! Vertices with the same string are unified, their child patterns and
+//! labels combined. Disagreements are reported as [`MergeConflict`]s but do
+//! not stop the merge: the merged vertex keeps all patterns and is labelled
+//! if either side labels it.
+//!
+//! Labels depend on n-gram counts over the whole corpus, so merging the
+//! results of two corpora only gives the result of parsing both together
+//! when they share no n-grams, or when both parsed the same texts. Otherwise
+//! the merge labels what either side labels, while parsing both together
+//! may label shared n-grams and their neighbours differently. The merged
+//! containment graph is always the containment graph of the combined
+//! corpus.
+
+use std::fmt;
+
+use context_trace::{
+    graph::{
+        vertex::key::VertexKey,
+        Hypergraph,
+    },
+    HashSet,
+};
+
+use crate::{
+    graph::{
+        strings::{
+            StringGraph,
+            StringVertex,
+        },
+        ParseResult,
+    },
+    io::import::{
+        Imported,
+        ValidationError,
+    },
+};
+
+/// A vertex which the merged graphs describe differently
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeConflict {
+    /// Both sides decompose the vertex, but into different patterns
+    Patterns {
+        string: String,
+        left: Vec<Vec<String>>,
+        right: Vec<Vec<String>>,
+    },
+    /// Only one side labels the vertex
+    Label {
+        string: String,
+        left: bool,
+        right: bool,
+    },
+}
+impl fmt::Display for MergeConflict {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::Patterns {
+                string,
+                left,
+                right,
+            } => write!(
+                f,
+                "{:?} has patterns {:?} on the left and {:?} on the right",
+                string, left, right
+            ),
+            Self::Label {
+                string,
+                left,
+                right,
+            } => write!(
+                f,
+                "{:?} is {} on the left and {} on the right",
+                string,
+                if *left { "labelled" } else { "unlabelled" },
+                if *right { "labelled" } else { "unlabelled" },
+            ),
+        }
+    }
+}
+
+/// Result of a merge with the conflicts found on the way
+#[derive(Debug, Clone)]
+pub struct Merged<T> {
+    pub merged: T,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl StringGraph {
+    pub fn merge(
+        &self,
+        other: &StringGraph,
+    ) -> Merged<StringGraph> {
+        let mut merged = self.clone();
+        let mut conflicts = Vec::new();
+        for (string, right) in &other.vertices {
+            let left = merged.vertices.entry(string.clone()).or_insert_with(
+                || StringVertex {
+                    label: right.label,
+                    ..Default::default()
+                },
+            );
+            if !left.patterns.is_empty()
+                && !right.patterns.is_empty()
+                && left.patterns != right.patterns
+            {
+                conflicts.push(MergeConflict::Patterns {
+                    string: string.clone(),
+                    left: left.patterns.iter().cloned().collect(),
+                    right: right.patterns.iter().cloned().collect(),
+                });
+            }
+            if left.label != right.label {
+                conflicts.push(MergeConflict::Label {
+                    string: string.clone(),
+                    left: left.label,
+                    right: right.label,
+                });
+            }
+            left.patterns.extend(right.patterns.iter().cloned());
+            left.label |= right.label;
+        }
+        Merged { merged, conflicts }
+    }
+}
+
+/// Merge two hypergraphs with their labels into a new hypergraph
+pub fn merge_graphs(
+    left: (&Hypergraph, &HashSet<VertexKey>),
+    right: (&Hypergraph, &HashSet<VertexKey>),
+) -> Result<Merged<Imported>, ValidationError> {
+    let Merged { merged, conflicts } =
+        StringGraph::from_hypergraph(left.0, left.1)
+            .merge(&StringGraph::from_hypergraph(right.0, right.1));
+    Ok(Merged {
+        merged: merged.build()?,
+        conflicts,
+    })
+}
+
+impl ParseResult {
+    /// Merge the containment and partition graphs of two results
+    ///
+    /// The merged partition graph shares the keys of the merged containment
+    /// graph, like the graphs of a single parse. A partial result makes the
+    /// merged result partial at the earlier pass.
+    pub fn merge(
+        &self,
+        other: &ParseResult,
+    ) -> Result<Merged<ParseResult>, ValidationError> {
+        let none = HashSet::default();
+        let Merged {
+            merged: containment,
+            conflicts,
+        } = merge_graphs(
+            (&self.containment, &none),
+            (&other.containment, &none),
+        )?;
+        let left = StringGraph::from_hypergraph(&self.graph, &self.labels);
+        let right = StringGraph::from_hypergraph(&other.graph, &other.labels);
+        let Merged {
+            merged: graph,
+            conflicts: graph_conflicts,
+        } = left.merge(&right);
+        let containment = containment.graph;
+        let Imported { graph, labels } = graph.build_with_keys(&containment)?;
+        let merged = ParseResult {
+            graph,
+            labels,
+            containment,
+            partial: self.partial.into_iter().chain(other.partial).min(),
+        };
+        Ok(Merged {
+            merged,
+            conflicts: conflicts.into_iter().chain(graph_conflicts).collect(),
+        })
+    }
+}
@@ -0,0 +1,108 @@
+//! Summary numbers of a [`ParseResult`] for comparing labelling settings
+
+use context_trace::graph::vertex::wide::Wide;
+use itertools::Itertools;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::graph::ParseResult;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseMetrics {
+    pub labels: usize,
+    pub containment_vertices: usize,
+    pub graph_vertices: usize,
+    /// Child patterns of all vertices in the partition graph
+    pub patterns: usize,
+    /// Parent child edges in the partition graph
+    pub edges: usize,
+    pub mean_label_width: f64,
+    pub max_label_width: usize,
+}
+impl From<&ParseResult> for ParseMetrics {
+    fn from(result: &ParseResult) -> Self {
+        let widths = result
+            .labels
+            .iter()
+            .map(|key| result.containment.expect_vertex_data(*key).width().0)
+            .collect_vec();
+        let (patterns, edges) = result
+            .graph
+            .vertex_keys()
+            .map(|key| result.graph.expect_vertex_data(key))
+            .fold((0, 0), |(patterns, edges), data| {
+                let children = data.child_patterns();
+                (
+                    patterns + children.len(),
+                    edges + children.values().map(|p| p.len()).sum::<usize>(),
+                )
+            });
+        Self {
+            labels: result.labels.len(),
+            containment_vertices: result.containment.vertex_count(),
+            graph_vertices: result.graph.vertex_count(),
+            patterns,
+            edges,
+            mean_label_width: widths.iter().sum::<usize>() as f64
+                / widths.len().max(1) as f64,
+            max_label_width: widths.iter().copied().max().unwrap_or_default(),
+        }
+    }
+}
+impl ParseMetrics {
+    const ROWS: [&'static str; 7] = [
+        "labels",
+        "containment vertices",
+        "graph vertices",
+        "patterns",
+        "edges",
+        "mean label width",
+        "max label width",
+    ];
+    fn values(&self) -> [String; 7] {
+        [
+            self.labels.to_string(),
+            self.containment_vertices.to_string(),
+            self.graph_vertices.to_string(),
+            self.patterns.to_string(),
+            self.edges.to_string(),
+            format!("{:.2}", self.mean_label_width),
+            self.max_label_width.to_string(),
+        ]
+    }
+    /// Plain text table with one column per named result
+    pub fn compare_table<'a>(
+        results: impl IntoIterator<Item = (&'a str, &'a ParseMetrics)>
+    ) -> String {
+        let columns = results
+            .into_iter()
+            .map(|(name, metrics)| {
+                std::iter::once(name.to_string())
+                    .chain(metrics.values())
+                    .collect_vec()
+            })
+            .collect_vec();
+        let widths = columns
+            .iter()
+            .map(|c| c.iter().map(|v| v.len()).max().unwrap_or(0))
+            .collect_vec();
+        let names = std::iter::once("").chain(Self::ROWS).collect_vec();
+        let name_width = names.iter().map(|n| n.len()).max().unwrap_or(0);
+        names
+            .iter()
+            .enumerate()
+            .map(|(row, name)| {
+                let cells = columns
+                    .iter()
+                    .zip(&widths)
+                    .map(|(column, w)| format!("{:>w$}", column[row], w = w))
+                    .join("  ");
+                format!("{:<w$}  {}", name, cells, w = name_width)
+                    .trim_end()
+                    .to_string()
+            })
+            .join("\n")
+    }
+}
@@ -40,22 +40,36 @@ use crate::{
     cancellation::Cancellation,
     graph::{
         labelling::{
+            pipeline::Pipeline,
+            CustomPass,
             LabellingCtx,
             LabellingImage,
         },
-        traversal::pass::RunResult,
+        preprocess::{
+            PreprocessStep,
+            Preprocessor,
+        },
+        traversal::pass::{
+            CancelReason,
+            RunResult,
+        },
         vocabulary::{
             entry::HasVertexEntries,
             ProcessStatus,
             Vocabulary,
         },
     },
-    tests::TestCorpus,
 };
 
 pub(crate) mod containment;
-pub(crate) mod labelling;
+pub mod diff;
+pub mod estimate;
+pub mod labelling;
+pub mod merge;
+pub mod metrics;
 pub(crate) mod partitions;
+pub mod preprocess;
+pub mod strings;
 pub mod traversal;
 pub(crate) mod utils;
 pub mod vocabulary;
@@ -113,6 +127,20 @@ impl StatusHandle {
             &s.steps_total
         })
     }
+    pub(crate) fn set_stages(
+        &self,
+        stages: Vec<String>,
+    ) {
+        let mut status = self.data.write().unwrap();
+        status.stages = stages;
+        status.stage = 0;
+    }
+    pub(crate) fn next_stage(
+        &self,
+        stage: usize,
+    ) {
+        self.data.write().unwrap().stage = stage;
+    }
 }
 #[derive(Debug, Getters)]
 pub struct Status {
@@ -121,6 +149,11 @@ pub struct Status {
     pass: ProcessStatus,
     steps: usize,
     steps_total: usize,
+    /// Names of the labelling pipeline stages
+    stages: Vec<String>,
+    /// Index of the running stage, equal to the number of stages when
+    /// all have finished
+    stage: usize,
 }
 impl Default for Status {
     fn default() -> Self {
@@ -129,10 +162,16 @@ impl Default for Status {
             pass: Default::default(),
             steps: 0,
             steps_total: 1,
+            stages: Vec::new(),
+            stage: 0,
         }
     }
 }
 impl Status {
+    /// Name of the running pipeline stage
+    pub fn stage_name(&self) -> Option<&str> {
+        self.stages.get(self.stage).map(String::as_str)
+    }
     pub fn new(insert_texts: impl IntoIterator<Item = impl ToString>) -> Self {
         Self {
             insert_texts: insert_texts
@@ -159,6 +198,8 @@ pub struct Corpus {
     pub(crate) name: String,
     #[deref]
     pub(crate) texts: Vec<String>,
+    #[serde(default)]
+    pub(crate) preprocessing: Vec<PreprocessStep>,
 }
 impl Corpus {
     pub fn new(
@@ -170,8 +211,23 @@ impl Corpus {
         Self {
             name: name.to_string(),
             texts: texts.into_iter().map(|s| s.to_string()).collect(),
+            preprocessing: Vec::new(),
         }
     }
+    /// Normalise the texts with the given preprocessor
+    pub fn preprocess(
+        self,
+        preprocessor: &Preprocessor,
+    ) -> Self {
+        preprocessor.apply(self)
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Preprocessing steps applied to the texts so far
+    pub fn preprocessing(&self) -> &[PreprocessStep] {
+        &self.preprocessing
+    }
     
     /// Get the storage key for this corpus
     pub(crate) fn storage_key(&self) -> &str {
@@ -180,31 +236,146 @@ impl Corpus {
 }
 pub(crate) type AbortSender = std::sync::mpsc::Sender<()>;
 pub(crate) type AbortReceiver = std::sync::mpsc::Receiver<()>;
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParseResult {
+    /// Partition graph, empty when cancelled before the partition pass
     pub graph: Hypergraph,
     pub containment: Hypergraph,
     pub labels: HashSet<VertexKey>,
+    /// Pass reached when the run was stopped, `None` for complete results
+    #[serde(default)]
+    pub partial: Option<ProcessStatus>,
+}
+/// Labels of a paused run with the lattice their keys refer to
+#[derive(Debug, Clone)]
+pub struct PausedLabels {
+    pub containment: Hypergraph,
+    pub labels: HashSet<VertexKey>,
+}
+/// Slot a run publishes its [`PausedLabels`] to whenever it is paused
+pub type PausedLabelsSlot = Arc<RwLock<Option<PausedLabels>>>;
+impl ParseResult {
+    pub fn is_partial(&self) -> bool {
+        self.partial.is_some()
+    }
+    /// Whether the run reached the partition pass and `graph` is its
+    /// (possibly partial) result
+    pub fn has_graph(&self) -> bool {
+        !matches!(self.partial, Some(pass) if pass < ProcessStatus::Partitions)
+    }
+}
+/// Options of [`parse_corpus_with_options`]
+///
+/// The containment and partition passes always run, the labelling stages
+/// in between are given by the pipeline. Custom stages refer to the
+/// registered passes by name.
+///
+/// With `partial` set, a run stopped by a cancellation or one of its
+/// [`Limits`](crate::cancellation::Limits) returns the lattice, labels and
+/// partition graph collected so far instead of the [`CancelReason`]. Runs
+/// stopped in the containment pass keep the n-grams counted so far without
+/// labels. Failing runs still return their error.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    pub pipeline: Pipeline,
+    pub passes: Vec<Arc<dyn CustomPass>>,
+    pub partial: bool,
+    pub paused_labels: Option<PausedLabelsSlot>,
+}
+impl ParseOptions {
+    pub fn new(pipeline: Pipeline) -> Self {
+        Self {
+            pipeline,
+            passes: Vec::new(),
+            partial: false,
+            paused_labels: None,
+        }
+    }
+    /// Return partial results on cancellation
+    pub fn partial(
+        mut self,
+        partial: bool,
+    ) -> Self {
+        self.partial = partial;
+        self
+    }
+    /// Publish the labels found so far to `slot` while the run is paused
+    pub fn publish_paused(
+        mut self,
+        slot: PausedLabelsSlot,
+    ) -> Self {
+        self.paused_labels = Some(slot);
+        self
+    }
+    /// Register a custom pass without adding it to the pipeline
+    pub fn register(
+        mut self,
+        pass: impl CustomPass + 'static,
+    ) -> Self {
+        self.passes.push(Arc::new(pass));
+        self
+    }
+    /// Register a custom pass and run it at the end of the pipeline
+    pub fn with_pass(
+        mut self,
+        pass: impl CustomPass + 'static,
+    ) -> Self {
+        self.pipeline = self.pipeline.custom(pass.name());
+        self.register(pass)
+    }
 }
 pub fn parse_corpus(
+    corpus: Corpus,
+    status: StatusHandle,
+    cancellation: impl Into<Cancellation>,
+) -> RunResult<ParseResult> {
+    parse_corpus_with_options(
+        corpus,
+        status,
+        cancellation,
+        ParseOptions::default(),
+    )
+}
+pub fn parse_corpus_with_options(
     corpus: Corpus,
     mut status: StatusHandle,
     cancellation: impl Into<Cancellation>,
+    options: ParseOptions,
 ) -> RunResult<ParseResult> {
-    let image = LabellingImage::from_corpus(&corpus, &mut status)?;
-    let test_corpus = TestCorpus::new(image, corpus);
-    let mut ctx = LabellingCtx::new(test_corpus, status, cancellation.into());
-
-    ctx.label_freq()?;
-
-    ctx.label_wrap()?;
+    let cancellation = cancellation.into();
+    let stop = |result: RunResult<()>| match result {
+        Err(reason) if options.partial && reason.is_interruption() => Ok(true),
+        result => result.map(|_| false),
+    };
+    let (image, result) = LabellingImage::partial_from_corpus(
+        &corpus,
+        &mut status,
+        &cancellation,
+    );
+    let mut stopped = stop(result)?;
+    let mut ctx = LabellingCtx::new(image, corpus, status, cancellation);
+    ctx.paused_labels = options.paused_labels.clone();
 
-    let graph = ctx.label_part()?;
+    if !stopped {
+        stopped = stop(ctx.run_pipeline(&options.pipeline, &options.passes))?;
+    }
+    let graph = if stopped {
+        Hypergraph::default()
+    } else {
+        let (graph, result) = ctx.label_part_partial();
+        stopped = stop(result)?;
+        graph
+    };
+    let partial = stopped.then(|| *ctx.status.pass());
+    if let Some(pass) = partial {
+        println!("Stopped in {:?} pass, keeping partial result", pass);
+    }
 
-    let LabellingImage { vocab, labels } = ctx.corpus.image;
+    let LabellingImage { vocab, labels } = ctx.image;
     Ok(ParseResult {
         graph,
         labels,
         containment: vocab.containment,
+        partial,
     })
 }
@@ -93,6 +93,7 @@ impl VisitTracking for AccumulateCtx<'_> {
     }
 }
 impl TraversalPass for AccumulateCtx<'_> {
+    type Ctx = LabellingCtx;
     type Node = NGramId;
     type NextNode = NGramId;
     type Queue = LinearQueue<Self>;
@@ -115,6 +115,7 @@ impl VisitTracking for PartitionsCtx<'_> {
     }
 }
 impl TraversalPass for PartitionsCtx<'_> {
+    type Ctx = LabellingCtx;
     type Node = NGramId;
     type NextNode = NGramId;
     type Queue = LinearQueue<Self>;
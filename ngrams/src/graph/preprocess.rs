@@ -0,0 +1,218 @@
+//! Corpus preprocessing applied before the containment pass
+//!
+//! Every distinct character sequence becomes its own leaf in the
+//! vocabulary, so variants in case, whitespace or unicode composition are
+//! normalised here before `Vocabulary::from_corpus` runs. The steps applied
+//! to a corpus are recorded on it and stored with the labelling image.
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::graph::Corpus;
+
+/// Unicode normalisation form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UnicodeForm {
+    /// Canonical composition
+    Nfc,
+    /// Compatibility composition
+    Nfkc,
+}
+
+/// Character classes used by [`PreprocessStep::FilterChars`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CharClass {
+    Alphabetic,
+    Numeric,
+    Whitespace,
+    Punctuation,
+    /// Everything not matched by the other classes
+    Other,
+}
+impl CharClass {
+    pub fn of(c: char) -> Self {
+        if c.is_alphabetic() {
+            Self::Alphabetic
+        } else if c.is_numeric() {
+            Self::Numeric
+        } else if c.is_whitespace() {
+            Self::Whitespace
+        } else if c.is_ascii_punctuation() || is_unicode_punctuation(c) {
+            Self::Punctuation
+        } else {
+            Self::Other
+        }
+    }
+}
+fn is_unicode_punctuation(c: char) -> bool {
+    matches!(
+        c,
+        '\u{2010}'..='\u{2027}'
+            | '\u{2030}'..='\u{205E}'
+            | '\u{3001}'..='\u{3003}'
+            | '\u{00A1}'
+            | '\u{00AB}'
+            | '\u{00BB}'
+            | '\u{00BF}'
+    )
+}
+
+/// A single normalisation step
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PreprocessStep {
+    /// Apply a unicode normalisation form
+    Unicode(UnicodeForm),
+    /// Fold all characters to lower case
+    CaseFold,
+    /// Replace whitespace runs with a single space and trim the ends
+    CollapseWhitespace,
+    /// Drop all characters not in one of the given classes
+    FilterChars(Vec<CharClass>),
+    /// Split texts into sentences, breaking sentences longer than
+    /// `max_len` characters at the last whitespace before the limit
+    SplitSentences { max_len: Option<usize> },
+}
+impl PreprocessStep {
+    pub fn apply(
+        &self,
+        text: &str,
+    ) -> Vec<String> {
+        match self {
+            Self::Unicode(UnicodeForm::Nfc) => vec![text.nfc().collect()],
+            Self::Unicode(UnicodeForm::Nfkc) => vec![text.nfkc().collect()],
+            Self::CaseFold => vec![text.to_lowercase()],
+            Self::CollapseWhitespace =>
+                vec![text.split_whitespace().collect::<Vec<_>>().join(" ")],
+            Self::FilterChars(keep) => vec![text
+                .chars()
+                .filter(|c| keep.contains(&CharClass::of(*c)))
+                .collect()],
+            Self::SplitSentences { max_len } => split_sentences(text)
+                .into_iter()
+                .flat_map(|s| match max_len {
+                    Some(max) => split_long(&s, *max),
+                    None => vec![s],
+                })
+                .collect(),
+        }
+    }
+}
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        current.push(c);
+        let at_boundary = matches!(c, '.' | '!' | '?')
+            && chars.peek().map(|n| n.is_whitespace()).unwrap_or(true);
+        if at_boundary {
+            sentences.push(std::mem::take(&mut current));
+        }
+    }
+    sentences.push(current);
+    sentences
+        .into_iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+fn split_long(
+    sentence: &str,
+    max_len: usize,
+) -> Vec<String> {
+    let max_len = max_len.max(1);
+    let mut parts = Vec::new();
+    let mut rest: Vec<char> = sentence.chars().collect();
+    while rest.len() > max_len {
+        let cut = rest[..=max_len]
+            .iter()
+            .rposition(|c| c.is_whitespace())
+            .filter(|&i| i > 0)
+            .unwrap_or(max_len);
+        parts.push(rest[..cut].iter().collect::<String>().trim().to_string());
+        rest = rest[cut..].to_vec();
+    }
+    parts.push(rest.into_iter().collect::<String>().trim().to_string());
+    parts.into_iter().filter(|s| !s.is_empty()).collect()
+}
+
+/// Configurable normaliser for corpus texts
+///
+/// Steps run in the order they were added. Texts which end up empty are
+/// removed from the corpus.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Preprocessor {
+    steps: Vec<PreprocessStep>,
+}
+impl Preprocessor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn step(
+        mut self,
+        step: PreprocessStep,
+    ) -> Self {
+        self.steps.push(step);
+        self
+    }
+    pub fn unicode(
+        self,
+        form: UnicodeForm,
+    ) -> Self {
+        self.step(PreprocessStep::Unicode(form))
+    }
+    pub fn case_fold(self) -> Self {
+        self.step(PreprocessStep::CaseFold)
+    }
+    pub fn collapse_whitespace(self) -> Self {
+        self.step(PreprocessStep::CollapseWhitespace)
+    }
+    pub fn filter_chars(
+        self,
+        keep: impl IntoIterator<Item = CharClass>,
+    ) -> Self {
+        self.step(PreprocessStep::FilterChars(keep.into_iter().collect()))
+    }
+    pub fn split_sentences(
+        self,
+        max_len: Option<usize>,
+    ) -> Self {
+        self.step(PreprocessStep::SplitSentences { max_len })
+    }
+    pub fn steps(&self) -> &[PreprocessStep] {
+        &self.steps
+    }
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+    /// Normalise a single text, possibly splitting it into several
+    pub fn apply_text(
+        &self,
+        text: &str,
+    ) -> Vec<String> {
+        self.steps
+            .iter()
+            .fold(vec![text.to_string()], |texts, step| {
+                texts.iter().flat_map(|t| step.apply(t)).collect()
+            })
+            .into_iter()
+            .filter(|t| !t.is_empty())
+            .collect()
+    }
+    /// Normalise all texts of a corpus and record the applied steps on it
+    pub fn apply(
+        &self,
+        mut corpus: Corpus,
+    ) -> Corpus {
+        corpus.texts = corpus
+            .texts
+            .iter()
+            .flat_map(|t| self.apply_text(t))
+            .collect();
+        corpus.preprocessing.extend(self.steps.iter().cloned());
+        corpus
+    }
+}
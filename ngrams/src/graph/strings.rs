@@ -0,0 +1,186 @@
+//! View of a [`Hypergraph`] keyed by vertex strings
+//!
+//! Vertex keys differ between runs, so graphs from different parses are
+//! compared and combined through their strings. Child patterns are stored
+//! as the strings of their children.
+
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+
+use context_trace::{
+    graph::{
+        vertex::{
+            data::VertexDataBuilder,
+            has_vertex_index::HasVertexIndex,
+            key::VertexKey,
+            pattern::{
+                id::PatternId,
+                Pattern,
+            },
+            token::Token,
+            ChildPatterns,
+        },
+        Hypergraph,
+    },
+    HashMap,
+    HashSet,
+};
+use itertools::Itertools;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::io::import::{
+    build_specs,
+    Imported,
+    ValidationError,
+    VertexSpec,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StringVertex {
+    pub patterns: BTreeSet<Vec<String>>,
+    pub label: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StringGraph {
+    pub vertices: BTreeMap<String, StringVertex>,
+}
+impl StringGraph {
+    pub fn from_hypergraph(
+        graph: &Hypergraph,
+        labels: &HashSet<VertexKey>,
+    ) -> Self {
+        let vertices = graph
+            .vertex_keys()
+            .map(|key| {
+                let data = graph.expect_vertex_data(key);
+                let patterns = data
+                    .child_patterns()
+                    .values()
+                    .map(|pattern| {
+                        pattern
+                            .iter()
+                            .map(|c| {
+                                graph.vertex_key_string(
+                                    &graph.expect_key_for_index(c),
+                                )
+                            })
+                            .collect()
+                    })
+                    .collect();
+                (
+                    graph.vertex_key_string(&key),
+                    StringVertex {
+                        patterns,
+                        label: labels.contains(&key),
+                    },
+                )
+            })
+            .collect();
+        Self { vertices }
+    }
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.vertices
+            .iter()
+            .filter(|(_, v)| v.label)
+            .map(|(s, _)| s.as_str())
+    }
+    /// Build a new hypergraph with fresh keys
+    pub fn build(&self) -> Result<Imported, ValidationError> {
+        let specs = self
+            .vertices
+            .iter()
+            .map(|(string, vertex)| {
+                (
+                    string.clone(),
+                    VertexSpec {
+                        patterns: vertex.patterns.iter().cloned().collect(),
+                        is_label: vertex.label,
+                    },
+                )
+            })
+            .collect();
+        build_specs(&specs)
+    }
+    /// Build a hypergraph reusing the keys of the vertices with the same
+    /// string in `keys`, as the partition graph shares the keys of the
+    /// containment graph
+    ///
+    /// Fails if a string has no vertex in `keys` or a pattern refers to a
+    /// string without a vertex in `self`.
+    pub fn build_with_keys(
+        &self,
+        keys: &Hypergraph,
+    ) -> Result<Imported, ValidationError> {
+        let key_of: HashMap<String, VertexKey> = keys
+            .vertex_keys()
+            .map(|key| (keys.vertex_key_string(&key), key))
+            .collect();
+        let key_of = |string: &str| {
+            key_of
+                .get(string)
+                .copied()
+                .ok_or_else(|| ValidationError::MissingKey(string.to_string()))
+        };
+        let mut graph = Hypergraph::default();
+        // tokens of the inserted vertices, children are looked up here
+        // rather than in `graph` so unknown children fail instead of panic
+        let mut inserted: HashMap<&str, Token> = HashMap::default();
+        let by_width = self
+            .vertices
+            .iter()
+            .sorted_by_key(|(string, _)| string.chars().count());
+        for (string, vertex) in by_width {
+            let key = key_of(string)?;
+            let width = string.chars().count();
+            let builder = VertexDataBuilder::default().width(width).key(key);
+            let data = graph.finish_vertex_builder(builder);
+            let token = match keys.get_atom_by_key(&key) {
+                Some(atom) if width == 1 => graph.insert_atom_data(atom, data),
+                _ => graph.insert_vertex_data(data),
+            };
+            inserted.insert(string, token);
+            if vertex.patterns.is_empty() {
+                continue;
+            }
+            let patterns: ChildPatterns = vertex
+                .patterns
+                .iter()
+                .map(|pattern| {
+                    let tokens = pattern
+                        .iter()
+                        .map(|c| match inserted.get(c.as_str()) {
+                            Some(token) => Ok(*token),
+                            None => Err(ValidationError::UnknownChild {
+                                parent: string.clone(),
+                                child: c.clone(),
+                            }),
+                        })
+                        .collect::<Result<Vec<_>, ValidationError>>()?;
+                    Ok((PatternId::default(), Pattern::from(tokens)))
+                })
+                .collect::<Result<_, ValidationError>>()?;
+            // the vertex and all children were inserted above
+            let children = graph
+                .with_vertex_mut(key, |data| {
+                    *data.child_patterns_mut() = patterns;
+                    data.all_localized_children_iter()
+                        .into_iter()
+                        .map(|(l, c)| (l, *c))
+                        .collect_vec()
+                })
+                .unwrap();
+            for (loc, child) in children {
+                let child = graph.expect_key_for_index(child.vertex_index());
+                graph.with_vertex_mut(child, |v| v.add_parent(loc)).unwrap();
+            }
+        }
+        let labels = self.labels().map(key_of).collect::<Result<_, _>>()?;
+        Ok(Imported { graph, labels })
+    }
+}
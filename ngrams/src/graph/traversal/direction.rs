@@ -13,15 +13,34 @@ use context_trace::graph::vertex::{
     VertexIndex,
 };
 
+/// Graph of nodes in layers, traversed up from its leaves or down from its
+/// roots
+pub trait TraversalGraph
+{
+    type Node;
+    /// Nodes of the lowest layer, where bottom up traversals start
+    fn leaf_nodes(&self) -> VecDeque<Self::Node>;
+    /// Nodes of the highest layer, where top down traversals start
+    fn root_nodes(&self) -> VecDeque<Self::Node>;
+}
+/// Node of a [`TraversalGraph`] with access to its neighbours
+pub trait TraversalEntry
+{
+    type Node;
+    /// Nodes one layer above this node
+    fn parent_nodes(&self) -> Vec<Self::Node>;
+    /// Nodes below this node with their offset in it
+    fn child_nodes(&self) -> Vec<(usize, Self::Node)>;
+}
 
-pub(crate) struct BottomUp;
-pub(crate) struct TopDown;
+pub struct BottomUp;
+pub struct TopDown;
 
-pub(crate) trait TraversalDirection
+pub trait TraversalDirection
 {
-    type Next;
-    fn starting_nodes(vocab: &Vocabulary) -> VecDeque<NGramId>;
-    fn next_nodes(entry: &VertexCtx<'_>) -> Vec<Self::Next>;
+    type Next<N>;
+    fn starting_nodes<G: TraversalGraph>(graph: &G) -> VecDeque<G::Node>;
+    fn next_nodes<E: TraversalEntry>(entry: &E) -> Vec<Self::Next<E::Node>>;
     fn order_top_bottom<T>(
         prev: T,
         next: T,
@@ -29,10 +48,10 @@ pub(crate) trait TraversalDirection
 }
 impl TraversalDirection for BottomUp
 {
-    type Next = NGramId;
-    fn starting_nodes(vocab: &Vocabulary) -> VecDeque<NGramId>
+    type Next<N> = N;
+    fn starting_nodes<G: TraversalGraph>(graph: &G) -> VecDeque<G::Node>
     {
-        FromIterator::from_iter(vocab.leaves.iter().cloned())
+        graph.leaf_nodes()
     }
     fn order_top_bottom<T>(
         prev: T,
@@ -41,28 +60,17 @@ impl TraversalDirection for BottomUp
     {
         (next, prev)
     }
-    fn next_nodes(entry: &VertexCtx<'_>) -> Vec<Self::Next>
+    fn next_nodes<E: TraversalEntry>(entry: &E) -> Vec<Self::Next<E::Node>>
     {
-        entry
-            .data
-            .parents()
-            .iter()
-            .filter(|(&id, p)| p.width() == entry.data.width() + 1)
-            .map(|(id, p)| {
-                NGramId::new(
-                    entry.vocab.containment.expect_key_for_index(id),
-                    p.width().0,
-                )
-            })
-            .collect_vec()
+        entry.parent_nodes()
     }
 }
 impl TraversalDirection for TopDown
 {
-    type Next = (usize, NGramId); // (off, id)
-    fn starting_nodes(vocab: &Vocabulary) -> VecDeque<NGramId>
+    type Next<N> = (usize, N); // (off, id)
+    fn starting_nodes<G: TraversalGraph>(graph: &G) -> VecDeque<G::Node>
     {
-        FromIterator::from_iter(vocab.roots.iter().cloned())
+        graph.root_nodes()
     }
     fn order_top_bottom<T>(
         prev: T,
@@ -71,9 +79,45 @@ impl TraversalDirection for TopDown
     {
         (prev, next)
     }
-    fn next_nodes(entry: &VertexCtx<'_>) -> Vec<Self::Next>
+    fn next_nodes<E: TraversalEntry>(entry: &E) -> Vec<Self::Next<E::Node>>
     {
-        entry
+        entry.child_nodes()
+    }
+}
+
+impl TraversalGraph for Vocabulary
+{
+    type Node = NGramId;
+    fn leaf_nodes(&self) -> VecDeque<NGramId>
+    {
+        FromIterator::from_iter(self.leaves.iter().cloned())
+    }
+    fn root_nodes(&self) -> VecDeque<NGramId>
+    {
+        FromIterator::from_iter(self.roots.iter().cloned())
+    }
+}
+impl TraversalEntry for VertexCtx<'_>
+{
+    type Node = NGramId;
+    fn parent_nodes(&self) -> Vec<NGramId>
+    {
+        self
+            .data
+            .parents()
+            .iter()
+            .filter(|(&id, p)| p.width() == self.data.width() + 1)
+            .map(|(id, p)| {
+                NGramId::new(
+                    self.vocab.containment.expect_key_for_index(id),
+                    p.width().0,
+                )
+            })
+            .collect_vec()
+    }
+    fn child_nodes(&self) -> Vec<(usize, NGramId)>
+    {
+        self
             .data
             .top_down_containment_nodes()
             .into_iter()
@@ -82,12 +126,11 @@ impl TraversalDirection for TopDown
                     // sub index can be used as offset because child patterns have special structure
                     subi,
                     NGramId::new(
-                        entry.vocab.containment.expect_key_for_index(c),
+                        self.vocab.containment.expect_key_for_index(c),
                         c.width().0,
                     ),
                 )
             })
             .collect()
-
     }
-}
\ No newline at end of file
+}
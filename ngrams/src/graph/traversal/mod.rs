@@ -1,4 +1,4 @@
-pub(crate) mod direction;
+pub mod direction;
 pub mod pass;
-pub(crate) mod queue;
-pub(crate) mod visited;
+pub mod queue;
+pub mod visited;
@@ -9,11 +9,13 @@ use tokio_util::sync::CancellationToken;
 
 use crate::graph::{
     labelling::LabellingCtx,
+    traversal::direction::TraversalGraph,
     vocabulary::{
         entry::VertexCtx,
         NGramId,
         Vocabulary,
     },
+    StatusHandle,
 };
 use context_trace::graph::vertex::{
     token::Token,
@@ -28,17 +30,56 @@ use super::{
         VisitorCollection,
     },
 };
-pub(crate) trait PassNode: Eq + PartialEq + Debug + Clone + Hash {}
+pub trait PassNode: Eq + PartialEq + Debug + Clone + Hash {}
 impl<N: Eq + PartialEq + Debug + Clone + Hash> PassNode for N {}
 #[derive(Debug)]
 pub enum CancelReason {
     Cancelled,
     Error,
     EmptyVocabulary,
+    /// The deadline of the [`Limits`](crate::cancellation::Limits) passed
+    DeadlineExceeded,
+    /// A pass took more steps than allowed
+    StepLimitExceeded,
+    /// The vocabulary grew beyond the allowed number of entries
+    MemoryLimitExceeded,
+}
+impl CancelReason {
+    /// Whether the run was stopped by a cancellation or one of its limits
+    /// rather than failing
+    pub fn is_interruption(&self) -> bool {
+        matches!(
+            self,
+            Self::Cancelled
+                | Self::DeadlineExceeded
+                | Self::StepLimitExceeded
+                | Self::MemoryLimitExceeded
+        )
+    }
 }
 #[must_use]
-pub(crate) type RunResult<T> = Result<T, CancelReason>;
-pub(crate) trait TraversalPass: Sized {
+pub type RunResult<T> = Result<T, CancelReason>;
+
+/// Context shared by the passes of a run
+///
+/// Passes reach the graph they traverse through the context, so they work
+/// on any [`TraversalGraph`], not only on the [`Vocabulary`].
+pub trait PassCtx {
+    type Graph: TraversalGraph;
+    fn graph(&self) -> &Self::Graph;
+    /// Progress of the run
+    fn status(&self) -> &StatusHandle;
+    /// Return an error if the run should stop
+    fn check_cancelled(&self) -> RunResult<()>;
+}
+
+/// Traversal over the nodes of a context
+///
+/// Passes start with the nodes of [`Self::start_queue`] and call
+/// [`Self::on_node`] for every node popped from the queue which passes
+/// [`Self::node_condition`]. The returned nodes are queued next.
+pub trait TraversalPass: Sized {
+    type Ctx: PassCtx;
     type Node: PassNode + Copy;
     type NextNode: PassNode + Into<Self::Node>;
     type Queue: Queue<Self>;
@@ -47,7 +88,7 @@ pub(crate) trait TraversalPass: Sized {
         &mut self,
         node: &Self::Node,
     ) -> RunResult<Option<Vec<Self::NextNode>>>;
-    fn ctx(&self) -> &LabellingCtx;
+    fn ctx(&self) -> &Self::Ctx;
     fn node_condition(
         &mut self,
         node: Self::Node,
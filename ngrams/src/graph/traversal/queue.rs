@@ -13,16 +13,13 @@ use context_trace::graph::vertex::{
     VertexIndex,
 };
 
-use crate::graph::{
-    labelling::frequency::FrequencyCtx,
-    traversal::pass::TraversalPass
-};
+use crate::graph::traversal::pass::TraversalPass;
 use derive_more::{
     Deref,
     DerefMut,
 };
 
-pub(crate) trait Queue<P: TraversalPass>: FromIterator<P::NextNode> {
+pub trait Queue<P: TraversalPass>: FromIterator<P::NextNode> {
     fn extend_layer(&mut self, iter: impl IntoIterator<Item = <P as TraversalPass>::NextNode>);
     fn finish_layer(&mut self);
     fn pop_front(&mut self) -> Option<P::NextNode>;
@@ -30,7 +27,7 @@ pub(crate) trait Queue<P: TraversalPass>: FromIterator<P::NextNode> {
 }
 type NodeQueue<P> = VecDeque<<P as TraversalPass>::NextNode>;
 #[derive(Debug, Deref, Default)]
-pub(crate) struct LayeredQueue<P: TraversalPass> {
+pub struct LayeredQueue<P: TraversalPass> {
     #[deref]
     queue: NodeQueue<P>,
     layer: NodeQueue<P>,
@@ -61,7 +58,7 @@ impl<P: TraversalPass> Queue<P> for LayeredQueue<P> {
 }
 
 #[derive(Debug, Deref, Default)]
-pub(crate) struct LinearQueue<P: TraversalPass> {
+pub struct LinearQueue<P: TraversalPass> {
     queue: NodeQueue<P>,
 }
 impl<P: TraversalPass> FromIterator<P::NextNode> for LinearQueue<P> {
@@ -85,8 +82,9 @@ impl<P: TraversalPass> Queue<P> for LinearQueue<P> {
     }
 }
 
+/// Queue of n-grams ordered from widest to narrowest, without duplicates
 #[derive(Debug, Deref, Default)]
-pub(crate) struct SortedQueue {
+pub struct SortedQueue {
     pub(crate) queue: VecDeque<NGramId>,
 }
 impl FromIterator<NGramId> for SortedQueue {
@@ -99,7 +97,7 @@ impl FromIterator<NGramId> for SortedQueue {
     }
 }
 
-impl Queue<FrequencyCtx<'_>> for SortedQueue
+impl<P: TraversalPass<NextNode = NGramId>> Queue<P> for SortedQueue
 {
     fn extend_layer(
         &mut self,
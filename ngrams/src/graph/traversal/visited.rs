@@ -21,11 +21,11 @@ use context_trace::{
 
 use super::pass::PassNode;
 
-pub(crate) trait VisitTracking: TraversalPass {
+pub trait VisitTracking: TraversalPass {
     type Collection: VisitorCollection<Self::Node>;
     fn visited_mut(&mut self) -> &mut Self::Collection;
 }
-pub(crate) trait VisitorCollection<N: PassNode> {
+pub trait VisitorCollection<N: PassNode> {
     type Ref<'t>: VisitorCollection<N> where N: 't;
     fn insert(&mut self, node: N) -> bool;
 }
@@ -101,6 +101,7 @@ impl<'a> ChildCoverPass<'a> {
     }
 }
 impl TraversalPass for ChildCoverPass<'_> {
+    type Ctx = LabellingCtx;
     type Node = (usize, NGramId);
     type NextNode = (usize, NGramId);
     type Queue = LayeredQueue<Self>;
@@ -101,6 +101,7 @@ impl<'a> ParentCoverPass<'a> {
     }
 }
 impl TraversalPass for ParentCoverPass<'_> {
+    type Ctx = LabellingCtx;
     type Node = (usize, NGramId);
     type NextNode = (usize, NGramId);
     type Queue = LayeredQueue<Self>;
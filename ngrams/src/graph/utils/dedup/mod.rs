@@ -119,6 +119,7 @@ impl<'a> ChildDedupPass<'a> {
     }
 }
 impl TraversalPass for ChildDedupPass<'_> {
+    type Ctx = LabellingCtx;
     type Node = (VertexKey, usize, NGramId);
     type NextNode = (VertexKey, usize, NGramId);
     type Queue = LinearQueue<Self>;
@@ -37,7 +37,7 @@ use std::{
 };
 
 #[derive(Debug, Deref, Clone, Serialize, Deserialize, PartialEq, Eq)]
-pub(crate) struct VocabEntry {
+pub struct VocabEntry {
     //pub(crate) id: NGramId,
     pub(crate) occurrences: HashSet<TextLocation>,
     // positions of largest smaller ngrams
@@ -47,7 +47,7 @@ pub(crate) struct VocabEntry {
 }
 
 impl VocabEntry {
-    pub(crate) fn count(&self) -> usize {
+    pub fn count(&self) -> usize {
         self.occurrences.len()
     }
     //pub(crate) fn needs_node(&self) -> bool {
@@ -56,12 +56,23 @@ impl VocabEntry {
     //}
 }
 #[derive(Debug, Deref, Clone)]
-pub(crate) struct VertexCtx<'a> {
+pub struct VertexCtx<'a> {
     pub(crate) data: VertexData,
     #[deref]
     pub(crate) entry: &'a VocabEntry,
     pub(crate) vocab: &'a Vocabulary,
 }
+impl<'a> VertexCtx<'a> {
+    pub fn data(&self) -> &VertexData {
+        &self.data
+    }
+    pub fn entry(&self) -> &'a VocabEntry {
+        self.entry
+    }
+    pub fn vocab(&self) -> &'a Vocabulary {
+        self.vocab
+    }
+}
 impl Wide for VertexCtx<'_> {
     fn width(&self) -> TokenWidth {
         self.data.width()
@@ -73,14 +84,14 @@ impl HasVertexKey for VertexCtx<'_> {
     }
 }
 #[derive(Debug, Deref)]
-pub(crate) struct VertexCtxMut<'a> {
+pub struct VertexCtxMut<'a> {
     pub(crate) data: VertexData,
     #[deref]
     pub(crate) entry: &'a mut VocabEntry,
 }
 // define how to access a graph
 // useful if you store extra labels for nodes by which to query
-pub(crate) trait HasVertexEntries<K: ?Sized + Debug> {
+pub trait HasVertexEntries<K: ?Sized + Debug> {
     fn entry(
         &mut self,
         key: K,
@@ -1,17 +1,21 @@
-use crate::graph::{
-    containment::{
-        CorpusCtx,
-        TextLevelCtx,
-    },
-    traversal::direction::{
-        TopDown,
-        TraversalDirection,
-    },
-    vocabulary::entry::{
-        HasVertexEntries,
-        VocabEntry,
+use crate::{
+    cancellation::Cancellation,
+    graph::{
+        containment::{
+            CorpusCtx,
+            TextLevelCtx,
+        },
+        preprocess::PreprocessStep,
+        traversal::direction::{
+            TopDown,
+            TraversalDirection,
+        },
+        vocabulary::entry::{
+            HasVertexEntries,
+            VocabEntry,
+        },
+        Corpus,
     },
-    Corpus,
 };
 use context_trace::{
     graph::{
@@ -68,7 +72,7 @@ use super::{
     StatusHandle,
 };
 
-pub(crate) mod entry;
+pub mod entry;
 
 #[derive(
     Debug,
@@ -84,7 +88,7 @@ pub(crate) mod entry;
     Serialize,
     Deserialize,
 )]
-pub(crate) struct NGramId {
+pub struct NGramId {
     #[deref]
     pub(crate) key: VertexKey,
     pub(crate) width: usize,
@@ -136,7 +140,7 @@ impl Ord for ProcessStatus {
 #[derive(
     Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Deref,
 )]
-pub(crate) struct Vocabulary {
+pub struct Vocabulary {
     //#[deref]
     pub(crate) containment: Hypergraph,
     pub(crate) name: String,
@@ -145,17 +149,54 @@ pub(crate) struct Vocabulary {
     pub(crate) leaves: HashSet<NGramId>,
     pub(crate) roots: HashSet<NGramId>,
     pub(crate) entries: HashMap<VertexKey, VocabEntry>,
+    #[serde(default)]
+    pub(crate) preprocessing: Vec<PreprocessStep>,
 }
 
 impl Vocabulary {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Graph of all n-grams and their containment edges
+    pub fn containment(&self) -> &Hypergraph {
+        &self.containment
+    }
+    /// Single character n-grams
+    pub fn leaves(&self) -> &HashSet<NGramId> {
+        &self.leaves
+    }
+    /// N-grams spanning a full text
+    pub fn roots(&self) -> &HashSet<NGramId> {
+        &self.roots
+    }
+    pub fn entries(&self) -> &HashMap<VertexKey, VocabEntry> {
+        &self.entries
+    }
     pub(crate) fn from_corpus(
         corpus: &Corpus,
         status: &mut StatusHandle,
+        cancellation: &Cancellation,
     ) -> Result<Self, super::traversal::pass::CancelReason> {
+        let (vocab, result) =
+            Self::partial_from_corpus(corpus, status, cancellation);
+        result.map(|_| vocab)
+    }
+    /// Build the vocabulary, keeping the n-grams found so far when the
+    /// containment pass stops
+    pub(crate) fn partial_from_corpus(
+        corpus: &Corpus,
+        status: &mut StatusHandle,
+        cancellation: &Cancellation,
+    ) -> (Self, Result<(), super::traversal::pass::CancelReason>) {
         let mut vocab: Vocabulary = Default::default();
         vocab.name.clone_from(&corpus.name);
-        vocab.containment_pass(&CorpusCtx { corpus, status })?;
-        Ok(vocab)
+        vocab.preprocessing.clone_from(&corpus.preprocessing);
+        let result = vocab.containment_pass(&CorpusCtx {
+            corpus,
+            status,
+            cancellation,
+        });
+        (vocab, result)
     }
 
     pub(crate) fn containment_pass(
@@ -172,16 +213,17 @@ impl Vocabulary {
             N * (N - 1),
         );
         Itertools::cartesian_product((1..=N), ctx.corpus.iter().enumerate())
-            .for_each(|(n, (i, text))| {
+            .try_for_each(|(n, (i, text))| {
+                ctx.cancellation.check(ctx.status, self.entries.len())?;
                 TextLevelCtx {
                     corpus_ctx: ctx,
                     texti: i,
                     text,
                     n,
                 }
-                .on_nlevel(self)
-            });
-        Ok(())
+                .on_nlevel(self);
+                Ok(())
+            })
     }
     //pub(crate) fn clean(&mut self) -> HashSet<NGramId> {
     //    let drained: HashSet<_> = self.entries
@@ -0,0 +1,66 @@
+//! Reading corpora from text files
+
+use std::str::FromStr;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+use crate::graph::Corpus;
+
+/// Layout of a corpus file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CorpusFormat {
+    /// One text per line
+    #[default]
+    Lines,
+    /// Leipzig corpora collection sentence files, `<id>\t<sentence>`
+    Leipzig,
+}
+impl FromStr for CorpusFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "lines" | "plain" => Ok(Self::Lines),
+            "leipzig" => Ok(Self::Leipzig),
+            other => Err(format!("unknown corpus format {:?}", other)),
+        }
+    }
+}
+impl CorpusFormat {
+    /// Parse one line into a text, skipping empty lines
+    fn text<'a>(
+        &self,
+        line: &'a str,
+    ) -> Option<&'a str> {
+        let text = match self {
+            Self::Lines => line,
+            Self::Leipzig => line.split_once('\t').map(|(_, s)| s)?,
+        };
+        let text = text.trim_end_matches(['\r', '\n']);
+        (!text.trim().is_empty()).then_some(text)
+    }
+}
+
+/// Read a corpus from the content of a corpus file
+pub fn read_corpus(
+    name: impl ToString,
+    content: &str,
+    format: CorpusFormat,
+) -> Corpus {
+    Corpus::new(name, content.lines().filter_map(|l| format.text(l)))
+}
+
+/// Read a corpus file, named after the file stem
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_corpus_file(
+    path: impl AsRef<Path>,
+    format: CorpusFormat,
+) -> std::io::Result<Corpus> {
+    let path = path.as_ref();
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let content = std::fs::read_to_string(path)?;
+    Ok(read_corpus(name, &content, format))
+}
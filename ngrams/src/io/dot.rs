@@ -0,0 +1,77 @@
+//! Graphviz DOT export of hypergraphs
+
+use std::io::{
+    self,
+    Write,
+};
+
+use context_trace::graph::Hypergraph;
+
+use crate::io::{
+    export::{
+        ExportGraph,
+        ExportOptions,
+    },
+    import::escape_label,
+};
+
+pub(crate) fn escape_dot(s: &str) -> String {
+    s.chars()
+        .fold(String::with_capacity(s.len()), |mut out, c| {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                c => out.push(c),
+            }
+            out
+        })
+}
+
+/// Write a hypergraph as a DOT digraph
+///
+/// Node labels are the escaped vertex strings; `vertex_width` and
+/// `is_label` are written as extra attributes and labelled vertices are
+/// drawn bold. Edges are labelled with the position of the child in its
+/// pattern.
+pub fn write_dot(
+    graph: &Hypergraph,
+    options: &ExportOptions,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let export = ExportGraph::new(graph, options);
+    writeln!(writer, "digraph {{")?;
+    for node in &export.nodes {
+        writeln!(
+            writer,
+            r#"    {} [ label = "{}" vertex_width = "{}" is_label = "{}"{} ]"#,
+            node.id,
+            escape_dot(&escape_label(&node.string)),
+            node.width,
+            node.label,
+            if node.label { r#" style = "bold""# } else { "" },
+        )?;
+    }
+    for edge in &export.edges {
+        writeln!(
+            writer,
+            r#"    {} -> {} [ label = "{}" pattern = "{}" ]"#,
+            edge.source,
+            edge.target,
+            edge.position,
+            escape_dot(&edge.pattern),
+        )?;
+    }
+    writeln!(writer, "}}")?;
+    Ok(())
+}
+
+pub fn to_dot(
+    graph: &Hypergraph,
+    options: &ExportOptions,
+) -> String {
+    let mut bytes = Vec::new();
+    write_dot(graph, options, &mut bytes)
+        .expect("writing to a Vec can not fail");
+    String::from_utf8(bytes).expect("DOT output is valid UTF-8")
+}
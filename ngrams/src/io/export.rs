@@ -0,0 +1,150 @@
+//! Flat node/edge view of a [`Hypergraph`] shared by the exporters
+
+use std::collections::VecDeque;
+
+use context_trace::{
+    graph::{
+        vertex::{
+            has_vertex_index::HasVertexIndex,
+            has_vertex_key::HasVertexKey,
+            key::VertexKey,
+            wide::Wide,
+        },
+        Hypergraph,
+    },
+    HashMap,
+    HashSet,
+};
+
+/// Options for exporting a hypergraph
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Leave out width 1 vertices and the edges pointing to them
+    pub skip_atoms: bool,
+    /// Only export vertices at most this many edges below a root
+    pub max_depth: Option<usize>,
+    /// Vertices exported with the label flag set
+    pub labels: HashSet<VertexKey>,
+}
+impl ExportOptions {
+    pub fn skip_atoms(
+        mut self,
+        skip: bool,
+    ) -> Self {
+        self.skip_atoms = skip;
+        self
+    }
+    pub fn max_depth(
+        mut self,
+        depth: impl Into<Option<usize>>,
+    ) -> Self {
+        self.max_depth = depth.into();
+        self
+    }
+    pub fn labels(
+        mut self,
+        labels: impl IntoIterator<Item = VertexKey>,
+    ) -> Self {
+        self.labels = labels.into_iter().collect();
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ExportNode {
+    pub(crate) id: usize,
+    pub(crate) string: String,
+    pub(crate) width: usize,
+    pub(crate) label: bool,
+}
+/// Edge from a parent to a child at `position` in one of its patterns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ExportEdge {
+    pub(crate) source: usize,
+    pub(crate) target: usize,
+    pub(crate) pattern: String,
+    pub(crate) position: usize,
+}
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ExportGraph {
+    pub(crate) nodes: Vec<ExportNode>,
+    pub(crate) edges: Vec<ExportEdge>,
+}
+impl ExportGraph {
+    pub(crate) fn new(
+        graph: &Hypergraph,
+        options: &ExportOptions,
+    ) -> Self {
+        let mut nodes = HashMap::default();
+        let mut edges = Vec::new();
+        let mut children = HashMap::<usize, Vec<usize>>::default();
+        for key in graph.vertex_keys() {
+            let data = graph.expect_vertex_data(key);
+            let id = *data.vertex_index();
+            let width = data.width().0;
+            if options.skip_atoms && width == 1 {
+                continue;
+            }
+            nodes.insert(
+                id,
+                ExportNode {
+                    id,
+                    string: graph.vertex_key_string(&key),
+                    width,
+                    label: options.labels.contains(&key),
+                },
+            );
+            for (pid, pattern) in data.child_patterns().iter() {
+                for (position, child) in pattern.iter().enumerate() {
+                    if options.skip_atoms && child.width.0 == 1 {
+                        continue;
+                    }
+                    let target = *child.index;
+                    children.entry(id).or_default().push(target);
+                    edges.push(ExportEdge {
+                        source: id,
+                        target,
+                        pattern: format!("{:?}", pid),
+                        position,
+                    });
+                }
+            }
+        }
+        if let Some(max_depth) = options.max_depth {
+            let depths = Self::depths(&nodes, &children);
+            nodes.retain(|id, _| {
+                depths.get(id).map(|d| *d <= max_depth).unwrap_or(false)
+            });
+        }
+        edges.retain(|e| {
+            nodes.contains_key(&e.source) && nodes.contains_key(&e.target)
+        });
+        let mut nodes: Vec<_> = nodes.into_values().collect();
+        nodes.sort_by_key(|n| n.id);
+        Self { nodes, edges }
+    }
+    /// Shortest distance of every vertex from a vertex without parents
+    fn depths(
+        nodes: &HashMap<usize, ExportNode>,
+        children: &HashMap<usize, Vec<usize>>,
+    ) -> HashMap<usize, usize> {
+        let has_parent: HashSet<usize> =
+            children.values().flatten().copied().collect();
+        let mut depths = HashMap::default();
+        let mut queue: VecDeque<_> = nodes
+            .keys()
+            .filter(|id| !has_parent.contains(id))
+            .map(|id| (*id, 0))
+            .collect();
+        while let Some((id, depth)) = queue.pop_front() {
+            if depths.contains_key(&id) {
+                continue;
+            }
+            depths.insert(id, depth);
+            for child in children.get(&id).into_iter().flatten() {
+                queue.push_back((*child, depth + 1));
+            }
+        }
+        depths
+    }
+}
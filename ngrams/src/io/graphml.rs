@@ -0,0 +1,104 @@
+//! GraphML export of hypergraphs, readable by yEd and other graph tools
+
+use std::io::{
+    self,
+    Write,
+};
+
+use context_trace::graph::Hypergraph;
+
+use crate::io::{
+    export::{
+        ExportGraph,
+        ExportOptions,
+    },
+    import::escape_label,
+};
+
+pub(crate) fn escape_xml(s: &str) -> String {
+    s.chars()
+        .fold(String::with_capacity(s.len()), |mut out, c| {
+            match c {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                '"' => out.push_str("&quot;"),
+                '\'' => out.push_str("&apos;"),
+                c => out.push(c),
+            }
+            out
+        })
+}
+
+/// Write a hypergraph as GraphML
+///
+/// Vertices carry the escaped `string`, `width` and `label` attributes,
+/// edges point from parent to child and carry the `pattern` id and
+/// `position` in the pattern.
+pub fn write_graphml(
+    graph: &Hypergraph,
+    options: &ExportOptions,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let export = ExportGraph::new(graph, options);
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    for (id, for_, name, ty) in [
+        ("string", "node", "string", "string"),
+        ("width", "node", "width", "int"),
+        ("label", "node", "label", "boolean"),
+        ("pattern", "edge", "pattern", "string"),
+        ("position", "edge", "position", "int"),
+    ] {
+        writeln!(
+            writer,
+            r#"  <key id="{id}" for="{for_}" attr.name="{name}" attr.type="{ty}"/>"#
+        )?;
+    }
+    writeln!(writer, r#"  <graph id="G" edgedefault="directed">"#)?;
+    for node in &export.nodes {
+        writeln!(writer, r#"    <node id="n{}">"#, node.id)?;
+        writeln!(
+            writer,
+            r#"      <data key="string">{}</data>"#,
+            escape_xml(&escape_label(&node.string))
+        )?;
+        writeln!(writer, r#"      <data key="width">{}</data>"#, node.width)?;
+        writeln!(writer, r#"      <data key="label">{}</data>"#, node.label)?;
+        writeln!(writer, "    </node>")?;
+    }
+    for (i, edge) in export.edges.iter().enumerate() {
+        writeln!(
+            writer,
+            r#"    <edge id="e{}" source="n{}" target="n{}">"#,
+            i, edge.source, edge.target
+        )?;
+        writeln!(
+            writer,
+            r#"      <data key="pattern">{}</data>"#,
+            escape_xml(&edge.pattern)
+        )?;
+        writeln!(
+            writer,
+            r#"      <data key="position">{}</data>"#,
+            edge.position
+        )?;
+        writeln!(writer, "    </edge>")?;
+    }
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")?;
+    Ok(())
+}
+
+pub fn to_graphml(
+    graph: &Hypergraph,
+    options: &ExportOptions,
+) -> String {
+    let mut bytes = Vec::new();
+    write_graphml(graph, options, &mut bytes)
+        .expect("writing to a Vec can not fail");
+    String::from_utf8(bytes).expect("GraphML output is valid UTF-8")
+}
@@ -0,0 +1,743 @@
+//! Import of GraphML and DOT files into a [`Hypergraph`]
+//!
+//! Files are first read into an [`ImportGraph`] of labelled nodes and
+//! ordered edges. Nodes are identified by their string, so several nodes
+//! with the same string (as drawn in yEd) become one vertex.
+//!
+//! Child patterns are taken from two sources:
+//! - node labels with `|` separated parts, one pattern per line
+//!   (`xab|y` or `ab|c\na|bc`)
+//! - outgoing edges, ordered by their `position` (or numeric `label`) and
+//!   grouped by their `pattern` attribute. The edges of a pattern either
+//!   all have a position or none, then they keep their order in the file.
+//!
+//! A backslash in a label makes the next character literal, so `a\|b` is
+//! the string `a|b` and `\'a'` is the string `'a'` rather than the atom
+//! `a`. Leading and trailing whitespace is dropped unless escaped. The
+//! exporters escape vertex strings this way.
+//!
+//! Every pattern has to spell out the string of its parent. Children
+//! without a node of their own are built from atoms.
+//!
+//! Sequence graphs, whose nodes are all atoms or `Start`/`End` markers (as
+//! in `test/assets/seq_graph.dot`), may have cycles and do not spell out
+//! their nodes. Their edges are imported as transitions instead: every edge
+//! between two atoms becomes the vertex of the two atoms, edges from `Start`
+//! and to `End` are dropped.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+use context_trace::{
+    graph::{
+        vertex::{
+            atom::Atom,
+            key::VertexKey,
+            token::Token,
+        },
+        Hypergraph,
+    },
+    HashMap,
+    HashSet,
+};
+use itertools::Itertools;
+
+/// Node read from a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportNode {
+    pub id: String,
+    pub label: String,
+    pub is_label: bool,
+}
+/// Edge read from a file, pointing from parent to child
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportEdge {
+    pub source: String,
+    pub target: String,
+    pub pattern: Option<String>,
+    pub position: Option<usize>,
+}
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportGraph {
+    pub nodes: Vec<ImportNode>,
+    pub edges: Vec<ImportEdge>,
+}
+
+/// A problem with the structure of an imported graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    DuplicateNode(String),
+    EmptyLabel(String),
+    UnknownNode { edge: usize, node: String },
+    /// The children of a pattern do not spell out the parent string
+    PatternMismatch { parent: String, pattern: Vec<String> },
+    /// A pattern needs at least two children
+    SingleChild { parent: String, child: String },
+    /// The parent string is reachable from its own children
+    Cycle(String),
+    /// The graph to take the keys from has no vertex with the string
+    MissingKey(String),
+    /// A pattern refers to a string without a vertex in the graph
+    UnknownChild { parent: String, child: String },
+    /// Some edges of a pattern have a position and some do not
+    MixedPositions(String),
+}
+impl fmt::Display for ValidationError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Self::DuplicateNode(id) => write!(f, "Duplicate node id {}", id),
+            Self::EmptyLabel(id) => write!(f, "Node {} has no label", id),
+            Self::UnknownNode { edge, node } =>
+                write!(f, "Edge {} refers to unknown node {}", edge, node),
+            Self::PatternMismatch { parent, pattern } => write!(
+                f,
+                "Pattern [{}] does not spell out {:?}",
+                pattern.iter().map(|s| format!("{:?}", s)).join(", "),
+                parent
+            ),
+            Self::SingleChild { parent, child } => write!(
+                f,
+                "Pattern of {:?} has the single child {:?}",
+                parent, child
+            ),
+            Self::Cycle(s) => write!(f, "{:?} is contained in itself", s),
+            Self::MissingKey(s) =>
+                write!(f, "No vertex {:?} to take the key from", s),
+            Self::UnknownChild { parent, child } => write!(
+                f,
+                "Pattern of {:?} has the child {:?} without a vertex",
+                parent, child
+            ),
+            Self::MixedPositions(s) => write!(
+                f,
+                "Pattern of {:?} has edges with and without a position",
+                s
+            ),
+        }
+    }
+}
+
+/// Error type for importing graphs
+#[derive(Debug)]
+pub enum ImportError {
+    /// IO error (native only)
+    Io(std::io::Error),
+    /// The file could not be parsed
+    Parse(String),
+    /// The file was parsed but does not describe a valid hypergraph
+    Invalid(Vec<ValidationError>),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            ImportError::Io(e) => write!(f, "IO error: {}", e),
+            ImportError::Parse(e) => write!(f, "Parse error: {}", e),
+            ImportError::Invalid(errors) => {
+                write!(f, "Invalid graph ({} errors)", errors.len())?;
+                for e in errors {
+                    write!(f, "\n  {}", e)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<std::io::Error> for ImportError {
+    fn from(e: std::io::Error) -> Self {
+        ImportError::Io(e)
+    }
+}
+
+/// An imported hypergraph and the vertices flagged as labels
+#[derive(Debug)]
+pub struct Imported {
+    pub graph: Hypergraph,
+    pub labels: HashSet<VertexKey>,
+}
+
+/// Characters of a label, with whether a backslash made them literal
+fn label_chars(label: &str) -> Vec<(char, bool)> {
+    let mut chars = label.chars();
+    let mut out = Vec::new();
+    while let Some(c) = chars.next() {
+        out.push(match c {
+            '\\' => chars.next().map_or((c, false), |e| (e, true)),
+            c => (c, false),
+        });
+    }
+    out
+}
+/// Drop the unescaped whitespace around label characters
+fn trim_unescaped(chars: &[(char, bool)]) -> &[(char, bool)] {
+    let kept = |(c, escaped): &(char, bool)| *escaped || !c.is_whitespace();
+    let start = chars.iter().position(kept).unwrap_or(chars.len());
+    let end = chars.iter().rposition(kept).map_or(start, |i| i + 1);
+    &chars[start..end]
+}
+/// Split label characters at the unescaped `separator`
+fn split_unescaped(
+    chars: &[(char, bool)],
+    separator: char,
+) -> Vec<&[(char, bool)]> {
+    chars
+        .split(|&(c, escaped)| c == separator && !escaped)
+        .collect()
+}
+fn literal(chars: &[(char, bool)]) -> String {
+    chars.iter().map(|(c, _)| c).collect()
+}
+/// Whether label characters start with the unescaped `prefix`
+fn starts_unescaped(
+    chars: &[(char, bool)],
+    prefix: &str,
+) -> bool {
+    chars.len() >= prefix.chars().count()
+        && chars
+            .iter()
+            .zip(prefix.chars())
+            .all(|(&(c, escaped), p)| c == p && !escaped)
+}
+
+const ELEMENT: &str = "Element(";
+
+/// Decode atom notations used by the DOT assets, `Element('a')` and `'a'`
+fn decode_atom(label: &str) -> String {
+    let chars = label_chars(label);
+    let chars = trim_unescaped(&chars);
+    let inner = match chars.split_last() {
+        Some((&(')', false), rest)) if starts_unescaped(rest, ELEMENT) =>
+            &rest[ELEMENT.len()..],
+        _ => chars,
+    };
+    match inner {
+        [('\'', false), (c, _), ('\'', false)] => c.to_string(),
+        _ => literal(inner),
+    }
+}
+
+/// Escape a vertex string so that it is read back unchanged from a label
+pub(crate) fn escape_label(string: &str) -> String {
+    let notation = string.starts_with('\'')
+        || string.starts_with(ELEMENT)
+        || is_sequence_marker(string);
+    let last = string.chars().count().saturating_sub(1);
+    let mut out = String::with_capacity(string.len());
+    for (i, c) in string.chars().enumerate() {
+        let escape = matches!(c, '\\' | '|' | '\n')
+            || (i == 0 && notation)
+            || ((i == 0 || i == last) && c.is_whitespace());
+        if escape {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Markers of the first and last element of a sequence graph
+fn is_sequence_marker(label: &str) -> bool {
+    matches!(label.trim(), "Start" | "End")
+}
+
+/// Patterns written in a label, one per line with `|` separated children
+fn label_patterns(label: &str) -> (String, Vec<Vec<String>>) {
+    let chars = label_chars(label);
+    let lines = split_unescaped(&chars, '\n')
+        .into_iter()
+        .map(trim_unescaped)
+        .filter(|l| !l.is_empty())
+        .collect_vec();
+    if lines.iter().any(|l| l.contains(&('|', false))) {
+        let parts = |line: &[(char, bool)]| {
+            split_unescaped(line, '|').into_iter().map(literal).collect_vec()
+        };
+        let string = parts(lines[0]).concat();
+        let patterns = lines
+            .iter()
+            .map(|l| parts(l))
+            .filter(|p| p.len() > 1)
+            .collect();
+        (string, patterns)
+    } else {
+        (decode_atom(label), Vec::new())
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct VertexSpec {
+    pub(crate) patterns: Vec<Vec<String>>,
+    pub(crate) is_label: bool,
+}
+
+/// Insert vertices given by string into a new hypergraph
+///
+/// Patterns have to spell out their parent, vertices without patterns are
+/// built from atoms.
+pub(crate) fn build_specs(
+    specs: &BTreeMap<String, VertexSpec>
+) -> Result<Imported, ValidationError> {
+    let graph = Hypergraph::default();
+    let mut builder = GraphBuilder {
+        graph: &graph,
+        specs,
+        tokens: HashMap::default(),
+        visiting: HashSet::default(),
+    };
+    let mut labels = HashSet::default();
+    for (string, spec) in specs {
+        let token = builder.insert(string)?;
+        if spec.is_label {
+            labels.insert(graph.expect_key_for_index(token));
+        }
+    }
+    Ok(Imported { graph, labels })
+}
+
+impl ImportGraph {
+    /// Check the graph and collect the patterns of every vertex string
+    fn vertex_specs(
+        &self
+    ) -> Result<BTreeMap<String, VertexSpec>, Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        let mut strings = HashMap::<&str, String>::default();
+        let mut specs = BTreeMap::<String, VertexSpec>::new();
+        // outgoing edges of nodes with patterns in their label only repeat
+        // those patterns
+        let mut labelled_patterns = HashSet::<&str>::default();
+        for node in &self.nodes {
+            let (string, patterns) = label_patterns(&node.label);
+            if string.is_empty() {
+                errors.push(ValidationError::EmptyLabel(node.id.clone()));
+                continue;
+            }
+            if strings.insert(&node.id, string.clone()).is_some() {
+                errors.push(ValidationError::DuplicateNode(node.id.clone()));
+            }
+            if !patterns.is_empty() {
+                labelled_patterns.insert(&node.id);
+            }
+            let spec = specs.entry(string).or_default();
+            spec.patterns.extend(patterns);
+            spec.is_label |= node.is_label;
+        }
+        let mut edge_patterns = BTreeMap::<
+            (&str, Option<&str>),
+            Vec<(Option<usize>, usize, String)>,
+        >::new();
+        for (i, edge) in self.edges.iter().enumerate() {
+            let ends = [&edge.source, &edge.target].map(|id| {
+                strings.get(id.as_str()).cloned().ok_or_else(|| {
+                    ValidationError::UnknownNode {
+                        edge: i,
+                        node: id.clone(),
+                    }
+                })
+            });
+            match ends {
+                [Ok(parent), Ok(child)] =>
+                    // edges between nodes of the same string only connect
+                    // alternative drawings of one vertex
+                    if parent != child
+                        && !labelled_patterns.contains(edge.source.as_str())
+                    {
+                        edge_patterns
+                            .entry((
+                                edge.source.as_str(),
+                                edge.pattern.as_deref(),
+                            ))
+                            .or_default()
+                            .push((edge.position, i, child));
+                    },
+                [source, target] => errors.extend(
+                    [source.err(), target.err()].into_iter().flatten(),
+                ),
+            }
+        }
+        for ((source, _), mut children) in edge_patterns {
+            let positioned =
+                children.iter().filter(|(p, _, _)| p.is_some()).count();
+            if positioned != 0 && positioned != children.len() {
+                errors.push(ValidationError::MixedPositions(
+                    strings[source].clone(),
+                ));
+                continue;
+            }
+            children.sort();
+            let pattern = children.into_iter().map(|(_, _, c)| c).collect();
+            if let Some(spec) = specs.get_mut(&strings[source]) {
+                spec.patterns.push(pattern);
+            }
+        }
+        for (string, spec) in specs.iter_mut() {
+            spec.patterns = std::mem::take(&mut spec.patterns)
+                .into_iter()
+                .unique()
+                .collect();
+            for pattern in &spec.patterns {
+                if pattern.len() == 1 {
+                    errors.push(ValidationError::SingleChild {
+                        parent: string.clone(),
+                        child: pattern[0].clone(),
+                    });
+                } else if pattern.concat() != *string {
+                    errors.push(ValidationError::PatternMismatch {
+                        parent: string.clone(),
+                        pattern: pattern.clone(),
+                    });
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(specs)
+        } else {
+            Err(errors)
+        }
+    }
+    /// Whether all nodes are atoms or sequence markers, connected by edges
+    pub fn is_sequence_graph(&self) -> bool {
+        !self.edges.is_empty()
+            && self.nodes.iter().all(|node| {
+                is_sequence_marker(&node.label)
+                    || decode_atom(&node.label).chars().count() == 1
+            })
+    }
+    /// Collect the atoms and the transitions between them
+    fn sequence_specs(
+        &self
+    ) -> Result<BTreeMap<String, VertexSpec>, Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        let mut atoms = HashMap::<&str, Option<String>>::default();
+        let mut specs = BTreeMap::<String, VertexSpec>::new();
+        for node in &self.nodes {
+            let atom = (!is_sequence_marker(&node.label))
+                .then(|| decode_atom(&node.label));
+            if let Some(atom) = &atom {
+                let spec = specs.entry(atom.clone()).or_default();
+                spec.is_label |= node.is_label;
+            }
+            if atoms.insert(&node.id, atom).is_some() {
+                errors.push(ValidationError::DuplicateNode(node.id.clone()));
+            }
+        }
+        for (i, edge) in self.edges.iter().enumerate() {
+            let ends = [&edge.source, &edge.target].map(|id| {
+                atoms.get(id.as_str()).cloned().ok_or_else(|| {
+                    ValidationError::UnknownNode {
+                        edge: i,
+                        node: id.clone(),
+                    }
+                })
+            });
+            match ends {
+                [Ok(Some(source)), Ok(Some(target))] => {
+                    let pattern = vec![source, target];
+                    specs
+                        .entry(pattern.concat())
+                        .or_default()
+                        .patterns
+                        .push(pattern);
+                },
+                [Ok(_), Ok(_)] => {},
+                [source, target] => errors.extend(
+                    [source.err(), target.err()].into_iter().flatten(),
+                ),
+            }
+        }
+        for spec in specs.values_mut() {
+            spec.patterns = std::mem::take(&mut spec.patterns)
+                .into_iter()
+                .unique()
+                .collect();
+        }
+        if errors.is_empty() {
+            Ok(specs)
+        } else {
+            Err(errors)
+        }
+    }
+    /// Validate the graph and insert it into a new hypergraph
+    pub fn build(&self) -> Result<Imported, ImportError> {
+        let specs = if self.is_sequence_graph() {
+            self.sequence_specs()
+        } else {
+            self.vertex_specs()
+        }
+        .map_err(ImportError::Invalid)?;
+        build_specs(&specs).map_err(|e| ImportError::Invalid(vec![e]))
+    }
+}
+
+struct GraphBuilder<'a> {
+    graph: &'a Hypergraph,
+    specs: &'a BTreeMap<String, VertexSpec>,
+    tokens: HashMap<String, Token>,
+    visiting: HashSet<String>,
+}
+impl GraphBuilder<'_> {
+    fn insert(
+        &mut self,
+        string: &str,
+    ) -> Result<Token, ValidationError> {
+        if let Some(token) = self.tokens.get(string) {
+            return Ok(*token);
+        }
+        if !self.visiting.insert(string.to_string()) {
+            return Err(ValidationError::Cycle(string.to_string()));
+        }
+        let mut chars = string.chars();
+        let token = match (chars.next(), chars.next()) {
+            (Some(c), None) => self.graph.insert_atom(Atom::Element(c)),
+            _ => {
+                let patterns = match self.specs.get(string) {
+                    Some(spec) if !spec.patterns.is_empty() =>
+                        spec.patterns.clone(),
+                    _ => vec![string.chars().map(String::from).collect()],
+                };
+                let patterns: Vec<Vec<Token>> = patterns
+                    .iter()
+                    .map(|p| p.iter().map(|c| self.insert(c)).collect())
+                    .collect::<Result<_, _>>()?;
+                self.graph.insert_patterns(patterns)
+            },
+        };
+        self.visiting.remove(string);
+        self.tokens.insert(string.to_string(), token);
+        Ok(token)
+    }
+}
+
+fn parse_bool(s: &str) -> bool {
+    matches!(s.trim(), "true" | "1")
+}
+
+/// Read a GraphML file, as written by [`super::write_graphml`] or yEd
+pub fn read_graphml(text: &str) -> Result<ImportGraph, ImportError> {
+    let doc = roxmltree::Document::parse(text)
+        .map_err(|e| ImportError::Parse(e.to_string()))?;
+    let keys: HashMap<&str, &str> = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("key"))
+        .filter_map(|n| Some((n.attribute("id")?, n.attribute("attr.name")?)))
+        .collect();
+    let data = |node: roxmltree::Node<'_, '_>, name: &str| {
+        node.children()
+            .filter(|c| c.has_tag_name("data"))
+            .find(|c| {
+                c.attribute("key").and_then(|k| keys.get(k)) == Some(&name)
+            })
+            .map(|c| c.text().unwrap_or_default().to_string())
+    };
+    let graph = doc
+        .descendants()
+        .find(|n| n.has_tag_name("graph"))
+        .ok_or_else(|| ImportError::Parse("missing <graph> element".into()))?;
+    let mut import = ImportGraph::default();
+    for node in graph.children().filter(|n| n.has_tag_name("node")) {
+        let id = node
+            .attribute("id")
+            .ok_or_else(|| ImportError::Parse("node without id".into()))?;
+        let label = data(node, "string").or_else(|| {
+            node.descendants()
+                .filter(|n| n.tag_name().name() == "NodeLabel")
+                .filter_map(|n| n.text())
+                .map(str::trim)
+                .find(|t| !t.is_empty())
+                .map(ToString::to_string)
+        });
+        import.nodes.push(ImportNode {
+            id: id.to_string(),
+            label: label.unwrap_or_default(),
+            is_label: data(node, "label")
+                .map(|l| parse_bool(&l))
+                .unwrap_or(false),
+        });
+    }
+    for edge in graph.children().filter(|n| n.has_tag_name("edge")) {
+        let end = |name: &str| {
+            edge.attribute(name).map(ToString::to_string).ok_or_else(|| {
+                ImportError::Parse(format!("edge without {}", name))
+            })
+        };
+        import.edges.push(ImportEdge {
+            source: end("source")?,
+            target: end("target")?,
+            pattern: data(edge, "pattern"),
+            position: data(edge, "position")
+                .and_then(|p| p.trim().parse().ok()),
+        });
+    }
+    Ok(import)
+}
+
+/// Split a DOT statement into tokens, keeping quoted strings together
+fn dot_tokens(text: &str) -> Result<Vec<String>, ImportError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() || c == ',' || c == ';' => {},
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some(e) => s.push(e),
+                            None => break,
+                        },
+                        Some(c) => s.push(c),
+                        None =>
+                            return Err(ImportError::Parse(
+                                "unterminated string".into(),
+                            )),
+                    }
+                }
+                tokens.push(format!("\"{}", s));
+            },
+            '[' | ']' | '=' | '{' | '}' => tokens.push(c.to_string()),
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push("->".to_string());
+            },
+            c => {
+                let mut s = c.to_string();
+                while let Some(&n) = chars.peek() {
+                    if n.is_alphanumeric() || n == '_' || n == '.' {
+                        s.push(n);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(s);
+            },
+        }
+    }
+    Ok(tokens)
+}
+fn unquote(token: &str) -> String {
+    token.strip_prefix('"').unwrap_or(token).to_string()
+}
+
+/// Read a DOT digraph, as written by [`super::write_dot`] or petgraph
+///
+/// Edge `label`s are read as the position of the child in the pattern.
+pub fn read_dot(text: &str) -> Result<ImportGraph, ImportError> {
+    let tokens = dot_tokens(text)?;
+    let start = tokens
+        .iter()
+        .position(|t| t == "{")
+        .ok_or_else(|| ImportError::Parse("missing '{'".into()))?;
+    let mut import = ImportGraph::default();
+    let mut rest = &tokens[start + 1..];
+    let mut known = HashSet::default();
+    while let Some(first) = rest.first() {
+        if first == "}" {
+            break;
+        }
+        let mut ids = vec![unquote(first)];
+        rest = &rest[1..];
+        while rest.first().map(String::as_str) == Some("->") {
+            let id = rest.get(1).ok_or_else(|| {
+                ImportError::Parse("edge without target".into())
+            })?;
+            ids.push(unquote(id));
+            rest = &rest[2..];
+        }
+        let mut attrs = HashMap::<String, String>::default();
+        if rest.first().map(String::as_str) == Some("[") {
+            let end = rest.iter().position(|t| t == "]").ok_or_else(|| {
+                ImportError::Parse("unterminated attribute list".into())
+            })?;
+            for kv in rest[1..end].chunks(3) {
+                match kv {
+                    [k, eq, v] if eq == "=" => {
+                        attrs.insert(unquote(k), unquote(v));
+                    },
+                    _ =>
+                        return Err(ImportError::Parse(format!(
+                            "invalid attribute list of {}",
+                            ids[0]
+                        ))),
+                }
+            }
+            rest = &rest[end + 1..];
+        }
+        if let [id] = &ids[..] {
+            known.insert(id.clone());
+            import.nodes.push(ImportNode {
+                id: id.clone(),
+                label: attrs.get("label").cloned().unwrap_or(id.clone()),
+                is_label: attrs
+                    .get("is_label")
+                    .map(|l| parse_bool(l))
+                    .unwrap_or(false),
+            });
+        } else {
+            let position = attrs
+                .get("position")
+                .or(attrs.get("label"))
+                .and_then(|p| p.trim().parse().ok());
+            for (source, target) in ids.into_iter().tuple_windows() {
+                import.edges.push(ImportEdge {
+                    source,
+                    target,
+                    pattern: attrs.get("pattern").cloned(),
+                    position,
+                });
+            }
+        }
+    }
+    // nodes only mentioned in edges are labelled by their id
+    let implicit = import
+        .edges
+        .iter()
+        .flat_map(|e| [e.source.clone(), e.target.clone()])
+        .filter(|id| known.insert(id.clone()))
+        .collect_vec();
+    import.nodes.extend(implicit.into_iter().map(|id| ImportNode {
+        label: id.clone(),
+        id,
+        is_label: false,
+    }));
+    Ok(import)
+}
+
+/// Import a GraphML or DOT graph from a string
+pub fn import_graphml(text: &str) -> Result<Imported, ImportError> {
+    read_graphml(text)?.build()
+}
+pub fn import_dot(text: &str) -> Result<Imported, ImportError> {
+    read_dot(text)?.build()
+}
+
+/// Import a `.graphml` or `.dot`/`.gv` file
+#[cfg(not(target_arch = "wasm32"))]
+pub fn import_file(path: impl AsRef<Path>) -> Result<Imported, ImportError> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)?;
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("graphml" | "xml") => import_graphml(&text),
+        Some("dot" | "gv") => import_dot(&text),
+        other => Err(ImportError::Parse(format!(
+            "unknown graph format {:?}",
+            other.unwrap_or_default()
+        ))),
+    }
+}
@@ -0,0 +1,41 @@
+//! Import and export of parse results and hypergraphs
+//!
+//! The functions in this module work on readers and writers so they can be
+//! used on every platform. Path based helpers are only available on native.
+
+pub mod corpus;
+pub mod dot;
+pub mod export;
+pub mod graphml;
+pub mod import;
+pub mod result;
+
+pub use corpus::{
+    read_corpus,
+    CorpusFormat,
+};
+pub use dot::{
+    to_dot,
+    write_dot,
+};
+pub use export::ExportOptions;
+pub use graphml::{
+    to_graphml,
+    write_graphml,
+};
+pub use import::{
+    import_dot,
+    import_graphml,
+    read_dot,
+    read_graphml,
+    ImportError,
+    ImportGraph,
+    Imported,
+    ValidationError,
+};
+pub use result::{
+    Encoding,
+    ResultIoError,
+    FORMAT_NAME,
+    FORMAT_VERSION,
+};
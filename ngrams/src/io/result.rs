@@ -0,0 +1,226 @@
+//! Versioned file format for [`ParseResult`]
+//!
+//! A saved result is a map with a `header` identifying the format and its
+//! version, and the `result` itself. It can be encoded as CBOR or JSON; the
+//! encoding is detected when loading.
+
+use std::io::{
+    self,
+    Read,
+    Write,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    fs::File,
+    io::{
+        BufReader,
+        BufWriter,
+    },
+    path::Path,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::graph::ParseResult;
+
+/// Format identifier written to the header of every saved result
+pub const FORMAT_NAME: &str = "ngrams-parse-result";
+/// Version of the saved format, increased on incompatible changes
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Encoding of a saved parse result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    #[default]
+    Cbor,
+    Json,
+}
+impl Encoding {
+    /// Guess the encoding from a file extension, defaulting to CBOR
+    pub fn from_extension(ext: &str) -> Self {
+        match ext.to_ascii_lowercase().as_str() {
+            "json" => Self::Json,
+            _ => Self::Cbor,
+        }
+    }
+    /// Detect the encoding of saved data. JSON files start with an object.
+    pub fn detect(bytes: &[u8]) -> Self {
+        match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{') => Self::Json,
+            _ => Self::Cbor,
+        }
+    }
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Cbor => "cbor",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Error type for saving and loading parse results
+#[derive(Debug)]
+pub enum ResultIoError {
+    /// IO error
+    Io(io::Error),
+    /// Serialization error
+    Encode(String),
+    /// Deserialization error
+    Decode(String),
+    /// The data is not a saved parse result
+    InvalidHeader(String),
+    /// The data was saved with an unsupported format version
+    UnsupportedVersion { found: u32, supported: u32 },
+}
+
+impl std::fmt::Display for ResultIoError {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            ResultIoError::Io(e) => write!(f, "IO error: {}", e),
+            ResultIoError::Encode(e) => write!(f, "Encoding error: {}", e),
+            ResultIoError::Decode(e) => write!(f, "Decoding error: {}", e),
+            ResultIoError::InvalidHeader(name) =>
+                write!(f, "Not a parse result file (format {:?})", name),
+            ResultIoError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "Unsupported format version {} (supported: {})",
+                found, supported
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ResultIoError {}
+
+impl From<io::Error> for ResultIoError {
+    fn from(e: io::Error) -> Self {
+        ResultIoError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Header {
+    pub format: String,
+    pub version: u32,
+}
+impl Default for Header {
+    fn default() -> Self {
+        Self {
+            format: FORMAT_NAME.to_string(),
+            version: FORMAT_VERSION,
+        }
+    }
+}
+impl Header {
+    fn validate(&self) -> Result<(), ResultIoError> {
+        if self.format != FORMAT_NAME {
+            Err(ResultIoError::InvalidHeader(self.format.clone()))
+        } else if self.version != FORMAT_VERSION {
+            Err(ResultIoError::UnsupportedVersion {
+                found: self.version,
+                supported: FORMAT_VERSION,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SavedRef<'a> {
+    header: Header,
+    result: &'a ParseResult,
+}
+#[derive(Deserialize)]
+struct Saved {
+    header: Header,
+    result: ParseResult,
+}
+/// Only the header, used to check the version before decoding the result
+#[derive(Deserialize)]
+struct SavedHeader {
+    header: Header,
+}
+
+fn decode<T: serde::de::DeserializeOwned>(
+    bytes: &[u8],
+    encoding: Encoding,
+) -> Result<T, ResultIoError> {
+    match encoding {
+        Encoding::Cbor => ciborium::from_reader(bytes)
+            .map_err(|e| ResultIoError::Decode(format!("{:?}", e))),
+        Encoding::Json => serde_json::from_slice(bytes)
+            .map_err(|e| ResultIoError::Decode(e.to_string())),
+    }
+}
+
+impl ParseResult {
+    /// Write the result with a version header
+    pub fn write_to(
+        &self,
+        writer: impl Write,
+        encoding: Encoding,
+    ) -> Result<(), ResultIoError> {
+        let saved = SavedRef {
+            header: Header::default(),
+            result: self,
+        };
+        match encoding {
+            Encoding::Cbor => ciborium::into_writer(&saved, writer)
+                .map_err(|e| ResultIoError::Encode(format!("{:?}", e))),
+            Encoding::Json => serde_json::to_writer(writer, &saved)
+                .map_err(|e| ResultIoError::Encode(e.to_string())),
+        }
+    }
+    pub fn to_bytes(
+        &self,
+        encoding: Encoding,
+    ) -> Result<Vec<u8>, ResultIoError> {
+        let mut bytes = Vec::new();
+        self.write_to(&mut bytes, encoding)?;
+        Ok(bytes)
+    }
+    /// Read a saved result, detecting its encoding
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ResultIoError> {
+        let encoding = Encoding::detect(bytes);
+        decode::<SavedHeader>(bytes, encoding)?.header.validate()?;
+        let saved: Saved = decode(bytes, encoding)?;
+        Ok(saved.result)
+    }
+    pub fn read_from(mut reader: impl Read) -> Result<Self, ResultIoError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+    /// Save to a file, choosing the encoding from its extension
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<(), ResultIoError> {
+        let path = path.as_ref();
+        let encoding = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(Encoding::from_extension)
+            .unwrap_or_default();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer, encoding)?;
+        writer.flush()?;
+        Ok(())
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ResultIoError> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+}
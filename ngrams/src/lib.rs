@@ -14,13 +14,17 @@ pub(crate) use {
 pub mod cancellation;
 pub mod config;
 pub mod graph;
+pub mod io;
 #[cfg(not(debug_assertions))]
 mod shared;
 pub(crate) mod storage;
 pub(crate) mod tests;
 
 pub use crate::{
-    cancellation::Cancellation,
+    cancellation::{
+        Cancellation,
+        Limits,
+    },
     config::CacheConfig,
     graph::{
         Status,
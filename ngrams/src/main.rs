@@ -0,0 +1,345 @@
+//! Command line tool to label a corpus and store the results
+//!
+//! ```text
+//! ngrams corpus.txt --format leipzig --output out/corpus --cache
+//! ```
+//!
+//! Writes `result.cbor` (or `result.json`), `labels.txt` and `stats.json`
+//! to the output directory. Ctrl-C cancels the running pass, with
+//! `--partial` the labels found until then are still written. Runs stopped
+//! by `--timeout`, `--max-steps` or `--max-vocab` exit with code 3.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod cli {
+    use std::{
+        io::Write,
+        path::{
+            Path,
+            PathBuf,
+        },
+        process::ExitCode,
+        sync::{
+            atomic::{
+                AtomicBool,
+                Ordering,
+            },
+            Arc,
+        },
+        time::{
+            Duration,
+            Instant,
+        },
+    };
+
+    use clap::Parser;
+    use itertools::Itertools;
+    use tokio_util::sync::CancellationToken;
+
+    use ngrams::{
+        graph::{
+            labelling::pipeline::{
+                Pipeline,
+                Stage,
+            },
+            metrics::ParseMetrics,
+            parse_corpus_with_options,
+            traversal::pass::CancelReason,
+            Corpus,
+            ParseOptions,
+            ParseResult,
+            Status,
+            StatusHandle,
+        },
+        io::{
+            corpus::read_corpus_file,
+            CorpusFormat,
+            Encoding,
+        },
+        CacheConfig,
+        Cancellation,
+        Limits,
+    };
+
+    #[derive(Debug, Parser)]
+    #[command(author, version, about = "Label the n-grams of a corpus")]
+    pub(crate) struct Args {
+        /// Corpus file
+        input: PathBuf,
+        /// Corpus file layout: `lines` or `leipzig`
+        #[arg(short, long, default_value = "lines")]
+        format: CorpusFormat,
+        /// Output directory, defaults to `out/<corpus name>`
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Only read the first N texts
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+        /// Skip the frequency labelling pass
+        #[arg(long)]
+        skip_frequency: bool,
+        /// Skip the wrapper labelling pass
+        #[arg(long)]
+        skip_wrappers: bool,
+        /// JSON pipeline description, replaces the default passes
+        #[arg(long, conflicts_with_all = ["skip_frequency", "skip_wrappers"])]
+        pipeline: Option<PathBuf>,
+        /// Read and write intermediate results in the cache directory
+        #[arg(long)]
+        cache: bool,
+        /// Write the result as JSON instead of CBOR
+        #[arg(long)]
+        json: bool,
+        /// Keep the labels found so far when cancelled
+        #[arg(long)]
+        partial: bool,
+        /// Stop after this many seconds
+        #[arg(long)]
+        timeout: Option<u64>,
+        /// Stop when a single pass takes more steps
+        #[arg(long)]
+        max_steps: Option<usize>,
+        /// Stop when the vocabulary has more entries
+        #[arg(long)]
+        max_vocab: Option<usize>,
+        /// Print the estimated resource use as JSON instead of parsing
+        #[arg(long)]
+        estimate: bool,
+        /// Earlier result to compare with, writes `diff.txt` and `diff.json`
+        #[arg(long)]
+        diff_against: Option<PathBuf>,
+    }
+
+    /// Print the status line until `done` is set
+    fn print_progress(
+        status: StatusHandle,
+        done: Arc<AtomicBool>,
+    ) {
+        while !done.load(Ordering::Relaxed) {
+            {
+                let status = status.read().unwrap();
+                print!(
+                    "\r{:?}: {}/{}          ",
+                    status.pass(),
+                    status.steps(),
+                    status.steps_total()
+                );
+            }
+            let _ = std::io::stdout().flush();
+            std::thread::sleep(Duration::from_millis(250));
+        }
+        println!();
+    }
+
+    fn read_pipeline(path: &Path) -> Result<Pipeline, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Pipeline::from_json(&json).map_err(|e| e.to_string())
+    }
+
+    fn write_outputs(
+        dir: &Path,
+        result: &ParseResult,
+        encoding: Encoding,
+        stats: serde_json::Value,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(dir)?;
+        result.save(dir.join("result").with_extension(encoding.extension()))?;
+        let labels = result
+            .labels
+            .iter()
+            .map(|key| result.containment.vertex_key_string(key))
+            .sorted()
+            .join("\n");
+        std::fs::write(dir.join("labels.txt"), labels + "\n")?;
+        std::fs::write(
+            dir.join("stats.json"),
+            serde_json::to_string_pretty(&stats)?,
+        )?;
+        Ok(())
+    }
+
+    /// Diff against an earlier result, returns the summary
+    fn write_diff(
+        dir: &Path,
+        old: &Path,
+        result: &ParseResult,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let diff = ParseResult::load(old)?.diff(result);
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join("diff.txt"), diff.to_string())?;
+        std::fs::write(dir.join("diff.json"), diff.to_json())?;
+        Ok(diff.summary())
+    }
+
+    pub(crate) async fn main() -> ExitCode {
+        let args = Args::parse();
+        CacheConfig::set_enabled(args.cache);
+
+        let mut corpus = match read_corpus_file(&args.input, args.format) {
+            Ok(corpus) => corpus,
+            Err(err) => {
+                eprintln!("Failed to read {}: {}", args.input.display(), err);
+                return ExitCode::FAILURE;
+            },
+        };
+        if let Some(limit) = args.limit {
+            corpus = Corpus::new(
+                corpus.name().to_string(),
+                corpus.iter().take(limit),
+            );
+        }
+        let name = corpus.name().to_string();
+        let text_count = corpus.len();
+        println!("Read {} texts from {}", text_count, args.input.display());
+        if args.estimate {
+            let estimate = corpus.estimate(None);
+            println!("{}", serde_json::to_string_pretty(&estimate).unwrap());
+            return ExitCode::SUCCESS;
+        }
+
+        let token = CancellationToken::new();
+        tokio::spawn({
+            let token = token.clone();
+            async move {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    eprintln!("\nCancelling...");
+                    token.cancel();
+                }
+            }
+        });
+
+        let status = StatusHandle::from(Status::new(Vec::<String>::new()));
+        let done = Arc::new(AtomicBool::new(false));
+        let progress = std::thread::spawn({
+            let status = status.clone();
+            let done = done.clone();
+            move || print_progress(status, done)
+        });
+        let pipeline = match &args.pipeline {
+            Some(path) => match read_pipeline(path) {
+                Ok(pipeline) => pipeline,
+                Err(err) => {
+                    eprintln!("Invalid pipeline {}: {}", path.display(), err);
+                    return ExitCode::FAILURE;
+                },
+            },
+            None => Pipeline {
+                stages: Pipeline::default()
+                    .stages
+                    .into_iter()
+                    .filter(|stage| match stage {
+                        Stage::Frequency(_) => !args.skip_frequency,
+                        Stage::Wrappers => !args.skip_wrappers,
+                        _ => true,
+                    })
+                    .collect(),
+            },
+        };
+        let stages = pipeline.stage_names();
+        let options = ParseOptions::new(pipeline).partial(args.partial);
+        let limits = Limits {
+            deadline: None,
+            max_steps: args.max_steps,
+            max_vocab_entries: args.max_vocab,
+        };
+        let limits = match args.timeout {
+            Some(secs) => limits.timeout(Duration::from_secs(secs)),
+            None => limits,
+        };
+        let start = Instant::now();
+        let result = tokio::task::spawn_blocking(move || {
+            parse_corpus_with_options(
+                corpus,
+                status,
+                Cancellation::from(token).with_limits(limits),
+                options,
+            )
+        })
+        .await;
+        done.store(true, Ordering::Relaxed);
+        let _ = progress.join();
+        let elapsed = start.elapsed();
+
+        let result = match result {
+            Ok(Ok(result)) => result,
+            Ok(Err(CancelReason::Cancelled)) => {
+                eprintln!("Cancelled after {:.1?}", elapsed);
+                return ExitCode::from(130);
+            },
+            Ok(Err(
+                reason @ (CancelReason::DeadlineExceeded
+                | CancelReason::StepLimitExceeded
+                | CancelReason::MemoryLimitExceeded),
+            )) => {
+                eprintln!("Stopped after {:.1?}: {:?}", elapsed, reason);
+                return ExitCode::from(3);
+            },
+            Ok(Err(reason)) => {
+                eprintln!("Parsing failed: {:?}", reason);
+                return ExitCode::FAILURE;
+            },
+            Err(err) => {
+                eprintln!("Parsing panicked: {}", err);
+                return ExitCode::FAILURE;
+            },
+        };
+
+        let stats = serde_json::json!({
+            "corpus": name,
+            "texts": text_count,
+            "metrics": ParseMetrics::from(&result),
+            "stages": stages,
+            "partial": result.partial,
+            "seconds": elapsed.as_secs_f64(),
+        });
+        let dir = args
+            .output
+            .unwrap_or_else(|| PathBuf::from_iter(["out", name.as_str()]));
+        let encoding = if args.json {
+            Encoding::Json
+        } else {
+            Encoding::Cbor
+        };
+        if let Some(path) = &args.diff_against {
+            match write_diff(&dir, path, &result) {
+                Ok(summary) => {
+                    println!("Compared with {}: {}", path.display(), summary)
+                },
+                Err(err) => {
+                    eprintln!(
+                        "Failed to compare with {}: {}",
+                        path.display(),
+                        err
+                    );
+                    return ExitCode::FAILURE;
+                },
+            }
+        }
+        match write_outputs(&dir, &result, encoding, stats) {
+            Ok(()) => {
+                if let Some(pass) = result.partial {
+                    println!("Cancelled in {:?} pass, result is partial", pass);
+                }
+                println!(
+                    "Found {} labels in {:.1?}, written to {}",
+                    result.labels.len(),
+                    elapsed,
+                    dir.display()
+                );
+                ExitCode::SUCCESS
+            },
+            Err(err) => {
+                eprintln!("Failed to write {}: {}", dir.display(), err);
+                ExitCode::FAILURE
+            },
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() -> std::process::ExitCode {
+    cli::main().await
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
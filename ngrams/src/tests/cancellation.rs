@@ -0,0 +1,236 @@
+use std::sync::{
+    atomic::{
+        AtomicBool,
+        Ordering,
+    },
+    Arc,
+};
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+use pretty_assertions::assert_eq;
+
+use crate::{
+    cancellation::{
+        Cancellation,
+        Limits,
+    },
+    graph::{
+        labelling::{
+            pipeline::{
+                FrequencyParams,
+                Pipeline,
+            },
+            CustomPass,
+            LabellingCtx,
+        },
+        parse_corpus_with_options,
+        traversal::pass::{
+            CancelReason,
+            RunResult,
+        },
+        vocabulary::ProcessStatus,
+        Corpus,
+        ParseOptions,
+        ParseResult,
+        PausedLabelsSlot,
+        Status,
+        StatusHandle,
+    },
+    tests::{
+        pipeline::label_strings,
+        OTTOS_MOPS_CORPUS,
+    },
+};
+
+/// Requests cancellation when it runs
+#[derive(Debug)]
+struct Stop(Arc<AtomicBool>);
+impl CustomPass for Stop {
+    fn name(&self) -> &str {
+        "Stop"
+    }
+    fn run(
+        &self,
+        _ctx: &mut LabellingCtx,
+    ) -> RunResult<()> {
+        self.0.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+fn parse_stopped(
+    name: &str,
+    partial: bool,
+) -> RunResult<ParseResult> {
+    let flag = Arc::new(AtomicBool::new(false));
+    let pipeline = Pipeline::empty()
+        .frequency(FrequencyParams::default())
+        .custom("Stop")
+        .wrappers();
+    let options = ParseOptions::new(pipeline)
+        .register(Stop(flag.clone()))
+        .partial(partial);
+    parse_corpus_with_options(
+        Corpus::new(name, OTTOS_MOPS_CORPUS),
+        StatusHandle::default(),
+        Cancellation::from(flag),
+        options,
+    )
+}
+
+#[test]
+pub(crate) fn test_partial_result() {
+    assert!(matches!(
+        parse_stopped("test_partial_discarded", false),
+        Err(CancelReason::Cancelled)
+    ));
+
+    let result = parse_stopped("test_partial_kept", true).unwrap();
+    assert!(result.is_partial());
+    assert_eq!(result.partial, Some(ProcessStatus::Frequency));
+    assert_eq!(result.graph.vertex_count(), 0);
+    assert!(!result.has_graph());
+    assert!(!result.labels.is_empty());
+    assert!(result.containment.vertex_count() > 0);
+
+    let bytes = result.to_bytes(crate::io::Encoding::Json).unwrap();
+    let read = ParseResult::from_bytes(&bytes).unwrap();
+    assert_eq!(read.partial, result.partial);
+}
+
+/// Pauses the run when it starts
+#[derive(Debug)]
+struct Pause(Cancellation);
+impl CustomPass for Pause {
+    fn name(&self) -> &str {
+        "Pause"
+    }
+    fn run(
+        &self,
+        _ctx: &mut LabellingCtx,
+    ) -> RunResult<()> {
+        self.0.pause();
+        Ok(())
+    }
+}
+
+#[test]
+pub(crate) fn test_pause_resume() {
+    let pipeline = Pipeline::default().custom("Pause").wrappers();
+    let cancellation = Cancellation::from(CancellationToken::new())
+        .with_pause_flag(Arc::new(AtomicBool::new(false)));
+    let status = StatusHandle::from(Status::new(OTTOS_MOPS_CORPUS));
+    let published = PausedLabelsSlot::default();
+    let run = std::thread::spawn({
+        let options = ParseOptions::new(pipeline.clone())
+            .register(Pause(cancellation.clone()))
+            .publish_paused(published.clone());
+        let cancellation = cancellation.clone();
+        let status = status.clone();
+        move || {
+            parse_corpus_with_options(
+                Corpus::new("test_pause_paused", OTTOS_MOPS_CORPUS),
+                status,
+                cancellation,
+                options,
+            )
+        }
+    });
+    // wait until the pause stage ran
+    while *status.read().unwrap().stage() < 2 {
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    std::thread::sleep(Duration::from_millis(50));
+    let paused_at = *status.steps();
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(*status.steps(), paused_at);
+    assert!(!run.is_finished());
+    // the labels of the frequency stage are shown while paused
+    let shown = published.write().unwrap().take().unwrap();
+    assert!(!shown.labels.is_empty());
+
+    cancellation.resume();
+    let paused = run.join().unwrap().unwrap();
+    assert!(!paused.is_partial());
+    assert!(shown.labels.is_subset(&paused.labels));
+    assert_eq!(
+        shown.containment.vertex_count(),
+        paused.containment.vertex_count(),
+    );
+
+    let options = ParseOptions::new(pipeline)
+        .register(Pause(Cancellation::None));
+    let unpaused = parse_corpus_with_options(
+        Corpus::new("test_pause_unpaused", OTTOS_MOPS_CORPUS),
+        StatusHandle::default(),
+        Cancellation::None,
+        options,
+    )
+    .unwrap();
+    assert_eq!(label_strings(&paused), label_strings(&unpaused));
+}
+
+#[test]
+pub(crate) fn test_partial_limits() {
+    // the vocabulary limit stops the containment pass, which keeps the
+    // n-grams counted so far
+    let options = ParseOptions::default().partial(true);
+    let result = parse_corpus_with_options(
+        Corpus::new("test_partial_limits", OTTOS_MOPS_CORPUS),
+        StatusHandle::default(),
+        Cancellation::None.with_limits(Limits::default().max_vocab_entries(3)),
+        options.clone(),
+    )
+    .unwrap();
+    assert_eq!(result.partial, Some(ProcessStatus::Containment));
+    assert!(!result.has_graph());
+    assert!(result.labels.is_empty());
+    assert!(result.containment.vertex_count() > 0);
+
+    // errors other than interruptions are still returned
+    assert!(matches!(
+        parse_corpus_with_options(
+            Corpus::new("test_partial_empty", Vec::<String>::new()),
+            StatusHandle::default(),
+            Cancellation::None,
+            options,
+        ),
+        Err(CancelReason::EmptyVocabulary)
+    ));
+}
+
+#[test]
+pub(crate) fn test_limits() {
+    let parse = |name: &str, limits: Limits| {
+        parse_corpus_with_options(
+            Corpus::new(name, OTTOS_MOPS_CORPUS),
+            StatusHandle::default(),
+            Cancellation::None.with_limits(limits),
+            ParseOptions::default(),
+        )
+    };
+    assert!(matches!(
+        parse("test_limit_deadline", Limits::default().timeout(Duration::ZERO)),
+        Err(CancelReason::DeadlineExceeded)
+    ));
+    assert!(matches!(
+        parse("test_limit_steps", Limits::default().max_steps(1)),
+        Err(CancelReason::StepLimitExceeded)
+    ));
+    assert!(matches!(
+        parse("test_limit_vocab", Limits::default().max_vocab_entries(3)),
+        Err(CancelReason::MemoryLimitExceeded)
+    ));
+    let generous = Limits::default()
+        .timeout(Duration::from_secs(600))
+        .max_steps(usize::MAX)
+        .max_vocab_entries(usize::MAX);
+    assert!(parse("test_limit_generous", generous).is_ok());
+
+    // limits on no cancellation keep the platform variant
+    let limited = Cancellation::None.with_limits(generous);
+    assert!(matches!(limited, Cancellation::Native(_)));
+    assert_eq!(limited.limits(), generous);
+}
@@ -0,0 +1,185 @@
+use itertools::Itertools;
+use pretty_assertions::assert_eq;
+
+use crate::{
+    cancellation::Cancellation,
+    graph::{
+        labelling::{
+            pipeline::{
+                FrequencyCriterion,
+                FrequencyParams,
+                Pipeline,
+                Stage,
+            },
+            LabellingCtx,
+        },
+        metrics::ParseMetrics,
+        parse_corpus_with_options,
+        Corpus,
+        ParseOptions,
+        ParseResult,
+        StatusHandle,
+    },
+    tests::OTTOS_MOPS_CORPUS,
+};
+
+fn parse_with(
+    name: &str,
+    criterion: FrequencyCriterion,
+) -> ParseResult {
+    parse_corpus_with_options(
+        Corpus::new(name, OTTOS_MOPS_CORPUS),
+        StatusHandle::default(),
+        Cancellation::None,
+        ParseOptions::new(
+            Pipeline::empty()
+                .frequency(FrequencyParams::criterion(criterion))
+                .wrappers(),
+        ),
+    )
+    .unwrap()
+}
+
+#[test]
+pub(crate) fn test_criterion_json() {
+    let params = FrequencyParams {
+        min_count: 2,
+        criterion: FrequencyCriterion::BranchingEntropy(0.5),
+    };
+    let json = serde_json::to_string(&params).unwrap();
+    assert_eq!(
+        serde_json::from_str::<FrequencyParams>(&json).unwrap(),
+        params
+    );
+    assert_eq!(
+        serde_json::from_str::<FrequencyParams>(r#"{ "min_count": 2 }"#)
+            .unwrap()
+            .criterion,
+        FrequencyCriterion::Cover,
+    );
+}
+
+/// Labels of the frequency stage alone on the `abab` corpus
+fn frequency_labels(
+    name: &str,
+    criterion: FrequencyCriterion,
+) -> Vec<String> {
+    let mut ctx = LabellingCtx::from_corpus(
+        Corpus::new(name, ["abab", "abcabc", "babc"]),
+        Cancellation::None,
+    )
+    .unwrap();
+    ctx.run_stage(
+        &Stage::Frequency(FrequencyParams::criterion(criterion)),
+        &[],
+    )
+    .unwrap();
+    ctx.labels()
+        .iter()
+        .map(|key| ctx.vocab().get_vertex(key).unwrap().ngram.clone())
+        .sorted()
+        .collect()
+}
+
+/// Leaves and roots of the `abab` corpus followed by `labels`
+fn with_fixed(labels: &[&str]) -> Vec<String> {
+    ["a", "b", "c", "abab", "abcabc", "babc"]
+        .iter()
+        .chain(labels)
+        .map(ToString::to_string)
+        .sorted()
+        .collect()
+}
+
+#[test]
+pub(crate) fn test_criteria() {
+    // same labels as the cover criterion in test_graph1
+    assert_eq!(
+        frequency_labels(
+            "test_criterion_ratio_labels",
+            FrequencyCriterion::CoverRatio(1.0),
+        ),
+        with_fixed(&["ab", "abc", "bab"]),
+    );
+
+    assert_eq!(
+        frequency_labels(
+            "test_criterion_count_labels",
+            FrequencyCriterion::MinCount(2),
+        ),
+        with_fixed(&["ab", "abc", "ba", "bab", "bc"]),
+    );
+
+    // ln(P(ab) / (P(a) * P(b))) = ln((5/11) / (5/14 * 6/14)) = 1.09,
+    // ln(P(ba) / (P(b) * P(a))) = ln((2/11) / (6/14 * 5/14)) = 0.17
+    assert_eq!(
+        frequency_labels(
+            "test_criterion_pmi_labels",
+            FrequencyCriterion::Pmi(1.0),
+        ),
+        with_fixed(&["ab", "abc", "abcab", "bc", "bcab", "bcabc"]),
+    );
+
+    // parents of ab: aba 1, abc 3, bab 2, cab 1, entropy 1.28,
+    // parents of ba: bab 2, aba 1, entropy 0.64
+    assert_eq!(
+        frequency_labels(
+            "test_criterion_entropy_labels",
+            FrequencyCriterion::BranchingEntropy(0.66),
+        ),
+        with_fixed(&["ab", "abc", "bab", "bca", "bcab", "ca", "cab"]),
+    );
+
+    // only roots and leaves pass an unreachable count
+    assert_eq!(
+        frequency_labels(
+            "test_criterion_none_labels",
+            FrequencyCriterion::MinCount(usize::MAX),
+        ),
+        with_fixed(&[]),
+    );
+}
+
+#[test]
+pub(crate) fn test_compare_table() {
+    let cover = parse_with("test_criterion_cover", FrequencyCriterion::Cover);
+    let pmi = parse_with("test_criterion_pmi", FrequencyCriterion::Pmi(0.0));
+    for result in [&cover, &pmi] {
+        let metrics = ParseMetrics::from(result);
+        assert_eq!(metrics.labels, result.labels.len());
+        assert!(metrics.graph_vertices <= metrics.containment_vertices);
+    }
+
+    let wide = ParseMetrics {
+        labels: 12,
+        containment_vertices: 120,
+        graph_vertices: 30,
+        patterns: 41,
+        edges: 96,
+        mean_label_width: 2.5,
+        max_label_width: 11,
+    };
+    let narrow = ParseMetrics {
+        labels: 8,
+        mean_label_width: 1.0 / 3.0,
+        max_label_width: 2,
+        ..wide
+    };
+    let table = ParseMetrics::compare_table([
+        ("wide", &wide),
+        ("narrow", &narrow),
+    ]);
+    assert_eq!(
+        table.lines().collect_vec(),
+        [
+            "                      wide  narrow",
+            "labels                  12       8",
+            "containment vertices   120     120",
+            "graph vertices          30      30",
+            "patterns                41      41",
+            "edges                   96      96",
+            "mean label width      2.50    0.33",
+            "max label width         11       2",
+        ],
+    );
+}
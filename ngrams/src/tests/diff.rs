@@ -0,0 +1,139 @@
+use std::collections::BTreeSet;
+
+use maplit::btreemap;
+use pretty_assertions::assert_eq;
+
+use crate::{
+    cancellation::Cancellation,
+    graph::{
+        diff::{
+            GraphDiff,
+            PatternChange,
+        },
+        labelling::pipeline::{
+            Pipeline,
+            PruneParams,
+        },
+        parse_corpus_with_options,
+        strings::{
+            StringGraph,
+            StringVertex,
+        },
+        Corpus,
+        ParseOptions,
+        StatusHandle,
+    },
+    tests::{
+        io::parse_texts,
+        pipeline::label_strings,
+    },
+};
+
+const TEXTS: [&str; 3] = ["abab", "abcabc", "babc"];
+
+fn patterns(patterns: &[&[&str]]) -> BTreeSet<Vec<String>> {
+    patterns
+        .iter()
+        .map(|p| p.iter().map(ToString::to_string).collect())
+        .collect()
+}
+
+#[test]
+pub(crate) fn test_diff_identical() {
+    let result = parse_texts("test_diff_identical", &TEXTS);
+    let diff = result.diff(&result);
+    assert!(diff.is_empty());
+    assert_eq!(diff, GraphDiff::default());
+}
+
+#[test]
+pub(crate) fn test_diff_pruned() {
+    let old = parse_texts("test_diff_default", &TEXTS);
+    let new = parse_corpus_with_options(
+        Corpus::new("test_diff_pruned", TEXTS),
+        StatusHandle::default(),
+        Cancellation::None,
+        ParseOptions::new(Pipeline::default().prune(PruneParams {
+            min_count: usize::MAX,
+            min_width: 1,
+        })),
+    )
+    .unwrap();
+    let diff = old.diff(&new);
+    let kept = label_strings(&new);
+    let lost: Vec<String> = label_strings(&old)
+        .into_iter()
+        .filter(|s| !kept.contains(s))
+        .collect();
+    assert!(!lost.is_empty());
+    assert_eq!(diff.labels_lost, lost);
+    assert!(diff.labels_gained.is_empty());
+
+    let text = diff.to_string();
+    let mut lines = text.lines();
+    assert_eq!(lines.next(), Some(diff.summary().as_str()));
+    for string in &lost {
+        assert!(text.contains(&format!("-label {:?}\n", string)));
+    }
+    assert_eq!(GraphDiff::from_json(&diff.to_json()).unwrap(), diff);
+}
+
+#[test]
+pub(crate) fn test_diff_patterns() {
+    let old = StringGraph {
+        vertices: btreemap! {
+            "a".to_string() => StringVertex::default(),
+            "b".to_string() => StringVertex::default(),
+            "ab".to_string() => StringVertex {
+                patterns: patterns(&[&["a", "b"]]),
+                label: true,
+            },
+            "abb".to_string() => StringVertex {
+                patterns: patterns(&[&["ab", "b"]]),
+                label: false,
+            },
+        },
+    };
+    let new = StringGraph {
+        vertices: btreemap! {
+            "a".to_string() => StringVertex::default(),
+            "b".to_string() => StringVertex::default(),
+            "bb".to_string() => StringVertex {
+                patterns: patterns(&[&["b", "b"]]),
+                label: true,
+            },
+            "abb".to_string() => StringVertex {
+                patterns: patterns(&[&["a", "bb"]]),
+                label: true,
+            },
+        },
+    };
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff,
+        GraphDiff {
+            added: vec!["bb".to_string()],
+            removed: vec!["ab".to_string()],
+            labels_gained: vec!["abb".to_string(), "bb".to_string()],
+            labels_lost: vec!["ab".to_string()],
+            patterns: vec![PatternChange {
+                string: "abb".to_string(),
+                added: vec![vec!["a".to_string(), "bb".to_string()]],
+                removed: vec![vec!["ab".to_string(), "b".to_string()]],
+            }],
+        }
+    );
+    assert_eq!(
+        diff.to_string().lines().skip(1).collect::<Vec<_>>(),
+        [
+            r#"+ "bb""#,
+            r#"- "ab""#,
+            r#"+label "abb""#,
+            r#"+label "bb""#,
+            r#"-label "ab""#,
+            r#"~ "abb""#,
+            r#"    + "a" "bb""#,
+            r#"    - "ab" "b""#,
+        ],
+    );
+}
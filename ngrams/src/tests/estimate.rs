@@ -0,0 +1,119 @@
+use itertools::Itertools;
+use pretty_assertions::assert_eq;
+
+use crate::{
+    cancellation::Cancellation,
+    graph::{
+        estimate::Estimator,
+        vocabulary::Vocabulary,
+        Corpus,
+        StatusHandle,
+    },
+    tests::OTTOS_MOPS_CORPUS,
+};
+
+#[test]
+pub(crate) fn test_estimate_small_corpus_exact() {
+    let corpus = Corpus::new("test_estimate_small", OTTOS_MOPS_CORPUS);
+    let estimate = corpus.estimate(None);
+    let vocab = Vocabulary::from_corpus(
+        &corpus,
+        &mut StatusHandle::default(),
+        &Cancellation::None,
+    )
+    .unwrap();
+    // fewer distinct n-grams than the sketch size are counted exactly
+    assert_eq!(estimate.vocab_entries, vocab.entries.len());
+    assert_eq!(
+        estimate.occurrences,
+        vocab.entries.values().map(|e| e.count()).sum::<usize>(),
+    );
+    assert_eq!(estimate.distinct[0], vocab.leaves.len());
+
+    let limited = corpus.estimate(Some(2));
+    assert_eq!(limited.max_order, 2);
+    assert_eq!(limited.distinct, estimate.distinct[..2].to_vec());
+}
+
+/// Pseudo random texts over a small alphabet
+fn random_texts(
+    count: usize,
+    len: usize,
+) -> Vec<String> {
+    let mut state = 17u64;
+    (0..count)
+        .map(|_| {
+            (0..len)
+                .map(|_| {
+                    state = state
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1);
+                    (b'a' + (state >> 60) as u8 % 8) as char
+                })
+                .collect::<String>()
+        })
+        .collect_vec()
+}
+
+/// Exact number of distinct n-grams of order `n`
+fn distinct_ngrams(
+    texts: &[String],
+    n: usize,
+) -> usize {
+    texts
+        .iter()
+        .flat_map(|t| {
+            let chars = t.chars().collect_vec();
+            (0..(chars.len() + 1).saturating_sub(n))
+                .map(|i| chars[i..i + n].iter().collect::<String>())
+                .collect_vec()
+        })
+        .unique()
+        .count()
+}
+
+#[test]
+pub(crate) fn test_estimate_sketch_error() {
+    let texts = random_texts(20, 200);
+    let corpus = Corpus::new("test_estimate_sketch", texts.clone());
+    let estimate = Estimator::default()
+        .sketch_size(256)
+        .max_order(Some(12))
+        .estimate(&corpus);
+    for n in 1..=12 {
+        let exact = distinct_ngrams(&texts, n);
+        let estimated = estimate.distinct[n - 1];
+        let error = estimated as f64 / exact as f64 - 1.0;
+        assert!(
+            error.abs() < 0.25,
+            "order {}: {} vs {}",
+            n,
+            estimated,
+            exact
+        );
+    }
+}
+
+#[test]
+pub(crate) fn test_estimate_sampled_orders() {
+    let texts = random_texts(2, 1000);
+    let corpus = Corpus::new("test_estimate_sampled", texts.clone());
+    let estimate = Estimator::default().exact_orders(8).estimate(&corpus);
+    assert_eq!(estimate.max_order, 1000);
+    assert_eq!(estimate.distinct.len(), 1000);
+    // all occurrences are counted, sampled or not
+    assert_eq!(estimate.occurrences, 2 * 1000 * 1001 / 2);
+    // sampled and interpolated orders
+    for n in [16, 100, 500, 999, 1000] {
+        let exact = distinct_ngrams(&texts, n);
+        let estimated = estimate.distinct[n - 1];
+        let error = estimated as f64 / exact as f64 - 1.0;
+        assert!(
+            error.abs() < 0.25,
+            "order {}: {} vs {}",
+            n,
+            estimated,
+            exact
+        );
+    }
+}
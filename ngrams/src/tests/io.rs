@@ -0,0 +1,281 @@
+use context_trace::{
+    graph::Hypergraph,
+    insert_atoms,
+    insert_patterns,
+};
+use itertools::Itertools;
+use pretty_assertions::assert_eq;
+
+use crate::{
+    cancellation::Cancellation,
+    graph::{
+        parse_corpus,
+        Corpus,
+        ParseResult,
+        Status,
+        StatusHandle,
+    },
+    io::{
+        import_dot,
+        import_graphml,
+        read_corpus,
+        to_dot,
+        to_graphml,
+        CorpusFormat,
+        Encoding,
+        ExportOptions,
+        ImportError,
+        ResultIoError,
+        ValidationError,
+        FORMAT_VERSION,
+    },
+};
+
+pub(crate) fn parse_texts(
+    name: &str,
+    texts: &[&str],
+) -> ParseResult {
+    let texts = texts.iter().map(ToString::to_string).collect_vec();
+    parse_corpus(
+        Corpus::new(name, texts.clone()),
+        StatusHandle::from(Status::new(texts)),
+        Cancellation::None,
+    )
+    .unwrap()
+}
+
+#[test]
+pub(crate) fn test_parse_result_roundtrip() {
+    let result = parse_texts("test_io_roundtrip", &["abab", "abcabc", "babc"]);
+    for encoding in [Encoding::Cbor, Encoding::Json] {
+        let bytes = result.to_bytes(encoding).unwrap();
+        assert_eq!(Encoding::detect(&bytes), encoding);
+        let loaded = ParseResult::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.graph, result.graph);
+        assert_eq!(loaded.containment, result.containment);
+        assert_eq!(loaded.labels, result.labels);
+    }
+}
+
+#[test]
+pub(crate) fn test_parse_result_version_check() {
+    let result = parse_texts("test_io_version", &["abab"]);
+    let mut json: serde_json::Value = serde_json::from_slice(
+        &result.to_bytes(Encoding::Json).unwrap(),
+    )
+    .unwrap();
+    json["header"]["version"] = (FORMAT_VERSION + 1).into();
+    let bytes = serde_json::to_vec(&json).unwrap();
+    assert!(matches!(
+        ParseResult::from_bytes(&bytes),
+        Err(ResultIoError::UnsupportedVersion { .. })
+    ));
+    json["header"]["format"] = "something else".into();
+    let bytes = serde_json::to_vec(&json).unwrap();
+    assert!(matches!(
+        ParseResult::from_bytes(&bytes),
+        Err(ResultIoError::InvalidHeader(_))
+    ));
+}
+
+#[test]
+pub(crate) fn test_export_graphml_dot() {
+    let graph = Hypergraph::default();
+    insert_atoms!(graph, {a, b, c});
+    insert_patterns!(graph,
+        ab => [a, b],
+        abc => [ab, c]
+    );
+    let options = ExportOptions::default()
+        .labels([graph.expect_key_for_index(ab)]);
+    let (ab, abc, c) = (*ab.index, *abc.index, *c.index);
+    let graphml = to_graphml(&graph, &options);
+    let dot = to_dot(&graph, &options);
+    assert_eq!(graphml.matches("<node ").count(), 5);
+    assert_eq!(graphml.matches("<edge ").count(), 4);
+    assert_eq!(dot.matches(" vertex_width = ").count(), 5);
+    assert_eq!(dot.matches(" -> ").count(), 4);
+    // only the labelled vertex carries the flag, under its own string
+    let flagged = r#"<data key="label">true</data>"#;
+    assert_eq!(graphml.matches(flagged).count(), 1);
+    assert!(graphml.contains(&format!(
+        "<node id=\"n{}\">\n      <data key=\"string\">ab</data>\n      \
+         <data key=\"width\">2</data>\n      \
+         <data key=\"label\">true</data>",
+        ab,
+    )));
+    assert_eq!(dot.matches(r#"is_label = "true""#).count(), 1);
+    assert!(dot.contains(&format!(
+        r#"{} [ label = "ab" vertex_width = "2" is_label = "true" style = "bold" ]"#,
+        ab,
+    )));
+    assert!(dot.contains(&format!(r#"{} -> {} [ label = "1" "#, abc, c)));
+
+    let no_atoms = to_graphml(&graph, &options.clone().skip_atoms(true));
+    assert_eq!(no_atoms.matches("<node ").count(), 2);
+    assert_eq!(no_atoms.matches("<edge ").count(), 1);
+    let roots = to_graphml(&graph, &options.max_depth(0));
+    assert_eq!(roots.matches("<node ").count(), 1);
+    assert!(roots.contains(r#"<data key="string">abc</data>"#));
+    assert!(!roots.contains("<edge "));
+}
+
+#[test]
+pub(crate) fn test_import_roundtrip() {
+    let result = parse_texts("test_io_import", &["abab", "abcabc", "babc"]);
+    let options =
+        ExportOptions::default().labels(result.labels.iter().cloned());
+    let strings = |graph: &context_trace::graph::Hypergraph| {
+        graph
+            .vertex_keys()
+            .map(|k| graph.vertex_key_string(&k))
+            .sorted()
+            .collect_vec()
+    };
+    for imported in [
+        import_graphml(&to_graphml(&result.graph, &options)).unwrap(),
+        import_dot(&to_dot(&result.graph, &options)).unwrap(),
+    ] {
+        assert_eq!(strings(&imported.graph), strings(&result.graph));
+        assert_eq!(imported.labels.len(), result.labels.len());
+    }
+}
+
+#[test]
+pub(crate) fn test_import_assets() {
+    let imported = import_graphml(include_str!(
+        "../../../test/assets/complex_giggerish.graphml"
+    ))
+    .unwrap();
+    let root = imported
+        .graph
+        .vertex_keys()
+        .map(|k| imported.graph.vertex_key_string(&k))
+        .max_by_key(|s| s.len());
+    assert_eq!(root.as_deref(), Some("wxabyzabbyxabyz"));
+    for asset in [
+        include_str!("../../../test/assets/example_graph.graphml"),
+        include_str!("../../../test/assets/text_graph.graphml"),
+    ] {
+        import_graphml(asset).unwrap();
+    }
+    // the DOT assets are sequence graphs with cycles, imported as the
+    // transitions between their atoms
+    let imported =
+        import_dot(include_str!("../../../test/assets/test_graph.dot"))
+            .unwrap();
+    let strings = imported
+        .graph
+        .vertex_keys()
+        .map(|k| imported.graph.vertex_key_string(&k))
+        .sorted()
+        .collect_vec();
+    assert_eq!(strings, ["a", "ab", "b", "bc", "c", "ca"]);
+    let imported =
+        import_dot(include_str!("../../../test/assets/seq_graph.dot"))
+            .unwrap();
+    let strings = imported
+        .graph
+        .vertex_keys()
+        .map(|k| imported.graph.vertex_key_string(&k))
+        .collect::<Vec<_>>();
+    for transition in ["ab", "bb", "cb"] {
+        assert!(strings.iter().any(|s| s == transition));
+    }
+    assert!(!strings.iter().any(|s| s.contains("Start")));
+    assert!(matches!(
+        import_dot("digraph { 0 [ label = \"ab\" ] 0 -> 1 }"),
+        Err(ImportError::Invalid(errors))
+            if errors.contains(&ValidationError::SingleChild {
+                parent: "ab".into(),
+                child: "1".into(),
+            })
+    ));
+}
+
+#[test]
+pub(crate) fn test_import_escapes() {
+    let strings = |graph: &Hypergraph| {
+        graph
+            .vertex_keys()
+            .map(|k| graph.vertex_key_string(&k))
+            .sorted()
+            .collect_vec()
+    };
+    // escaped separators, quotes and whitespace are part of the string
+    let imported = import_dot(
+        r#"digraph {
+            0 [ label = "a\\|b|c" ]
+            1 [ label = "\\'a'" ]
+            2 [ label = "'b'" ]
+            3 [ label = "\\ x" ]
+        }"#,
+    )
+    .unwrap();
+    let expected = [
+        " ", " x", "'", "'a'", "a", "a|b", "a|bc", "b", "c", "x", "|",
+    ];
+    assert_eq!(strings(&imported.graph), expected.map(String::from));
+    // the exporters escape the strings they write
+    let options = ExportOptions::default();
+    for exported in [
+        import_dot(&to_dot(&imported.graph, &options)).unwrap(),
+        import_graphml(&to_graphml(&imported.graph, &options)).unwrap(),
+    ] {
+        assert_eq!(strings(&exported.graph), strings(&imported.graph));
+    }
+}
+
+#[test]
+pub(crate) fn test_import_edge_positions() {
+    // positioned edges are ordered by position, not by their order
+    let imported = import_dot(
+        r#"digraph {
+            0 [ label = "abc" ]
+            1 [ label = "a" ]
+            2 [ label = "bc" ]
+            0 -> 2 [ label = "1" ]
+            0 -> 1 [ label = "0" ]
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(imported.graph.vertex_count(), 5);
+    assert!(matches!(
+        import_dot(
+            r#"digraph {
+                0 [ label = "abc" ]
+                1 [ label = "a" ]
+                2 [ label = "bc" ]
+                0 -> 2 [ label = "1" ]
+                0 -> 1
+            }"#,
+        ),
+        Err(ImportError::Invalid(errors))
+            if errors == [ValidationError::MixedPositions("abc".into())]
+    ));
+}
+
+#[test]
+pub(crate) fn test_read_leipzig_corpus() {
+    let content = include_str!("../../../test/assets/leipzig_sentences.txt");
+    // lines without an id or with an empty sentence are skipped, the rest
+    // of a line after the id is the sentence
+    let corpus = read_corpus("leipzig", content, CorpusFormat::Leipzig);
+    assert_eq!(corpus.name(), "leipzig");
+    assert_eq!(
+        *corpus,
+        [
+            "Ottos Mops trotzt.",
+            "Otto: fort, Mops, fort!",
+            "Ottos Mops hopst fort.\twith a stray tab",
+            "Otto: soso.",
+        ]
+        .map(String::from),
+    );
+    let lines = read_corpus("lines", content, CorpusFormat::Lines);
+    assert_eq!(lines.len(), 7);
+    assert_eq!(lines[0], "1\tOttos Mops trotzt.");
+    assert_eq!(lines[1], "2\tOtto: fort, Mops, fort!");
+    assert_eq!("Leipzig".parse::<CorpusFormat>(), Ok(CorpusFormat::Leipzig));
+    assert!("tsv".parse::<CorpusFormat>().is_err());
+}
@@ -0,0 +1,215 @@
+use itertools::Itertools;
+use maplit::btreemap;
+use pretty_assertions::assert_eq;
+
+use crate::{
+    graph::{
+        merge::MergeConflict,
+        strings::{
+            StringGraph,
+            StringVertex,
+        },
+        ParseResult,
+    },
+    io::import::ValidationError,
+    tests::{
+        io::parse_texts,
+        pipeline::label_strings,
+    },
+};
+
+fn containment_strings(result: &ParseResult) -> StringGraph {
+    StringGraph::from_hypergraph(&result.containment, &Default::default())
+}
+fn graph_strings(result: &ParseResult) -> StringGraph {
+    StringGraph::from_hypergraph(&result.graph, &result.labels)
+}
+
+#[test]
+pub(crate) fn test_merge_containment() {
+    let left = parse_texts("test_merge_left", &["abab", "abcabc"]);
+    let right = parse_texts("test_merge_right", &["babc", "cab"]);
+    let union =
+        parse_texts("test_merge_union", &["abab", "abcabc", "babc", "cab"]);
+    let merged = left.merge(&right).unwrap().merged;
+    assert_eq!(containment_strings(&merged), containment_strings(&union));
+    assert_eq!(
+        merged.containment.vertex_count(),
+        union.containment.vertex_count()
+    );
+}
+
+#[test]
+pub(crate) fn test_merge_disjoint_halves() {
+    let left = parse_texts("test_merge_disjoint_left", &["abab", "abcabc"]);
+    let right = parse_texts("test_merge_disjoint_right", &["xyxy", "xyzxyz"]);
+    let union = parse_texts(
+        "test_merge_disjoint_union",
+        &["abab", "abcabc", "xyxy", "xyzxyz"],
+    );
+    let merged = left.merge(&right).unwrap();
+    assert_eq!(merged.conflicts, vec![]);
+    let merged = merged.merged;
+    assert_eq!(label_strings(&merged), label_strings(&union));
+    assert_eq!(graph_strings(&merged), graph_strings(&union));
+    assert_eq!(containment_strings(&merged), containment_strings(&union));
+    // the partition graph shares the keys of the containment graph
+    for key in &merged.labels {
+        assert_eq!(
+            merged.graph.vertex_key_string(key),
+            merged.containment.vertex_key_string(key)
+        );
+    }
+}
+
+#[test]
+pub(crate) fn test_merge_same_halves() {
+    // counts of a repeated corpus scale evenly, so the labels do not change
+    let texts = ["abab", "abcabc", "babc"];
+    let left = parse_texts("test_merge_same_left", &texts);
+    let right = parse_texts("test_merge_same_right", &texts);
+    let union = parse_texts("test_merge_same_union", &texts.repeat(2));
+    let merged = left.merge(&right).unwrap();
+    assert_eq!(merged.conflicts, vec![]);
+    let merged = merged.merged;
+    assert_eq!(label_strings(&merged), label_strings(&union));
+    assert_eq!(graph_strings(&merged), graph_strings(&union));
+    assert_eq!(containment_strings(&merged), containment_strings(&union));
+}
+
+#[test]
+pub(crate) fn test_merge_overlapping_halves() {
+    // the halves share n-grams like "ab" and "bc", whose counts in the
+    // union decide labels neither half sees
+    let left = parse_texts("test_merge_overlap_left", &["abab", "abcabc"]);
+    let right = parse_texts("test_merge_overlap_right", &["babc", "cab"]);
+    let merged = left.merge(&right).unwrap();
+    // labelled on one side only, but present on both
+    for conflict in &merged.conflicts {
+        if let MergeConflict::Label { string, .. } = conflict {
+            assert!(graph_strings(&left).vertices.contains_key(string));
+            assert!(graph_strings(&right).vertices.contains_key(string));
+        }
+    }
+    let merged = merged.merged;
+    // the merge labels what either half labels, not what the union would
+    let either = label_strings(&left)
+        .into_iter()
+        .chain(label_strings(&right))
+        .sorted()
+        .dedup()
+        .collect_vec();
+    assert_eq!(label_strings(&merged), either);
+    assert_eq!(
+        graph_strings(&merged),
+        graph_strings(&left).merge(&graph_strings(&right)).merged,
+    );
+}
+
+#[test]
+pub(crate) fn test_build_with_missing_keys() {
+    let vertex = |patterns: &[&[&str]]| StringVertex {
+        patterns: patterns
+            .iter()
+            .map(|p| p.iter().map(ToString::to_string).collect())
+            .collect(),
+        label: true,
+    };
+    let keys = StringGraph {
+        vertices: btreemap! {
+            "a".to_string() => vertex(&[]),
+            "b".to_string() => vertex(&[]),
+        },
+    };
+    let keys = keys.build().unwrap().graph;
+    let graph = StringGraph {
+        vertices: btreemap! {
+            "a".to_string() => vertex(&[]),
+            "b".to_string() => vertex(&[]),
+            "ab".to_string() => vertex(&[&["a", "b"]]),
+        },
+    };
+    assert_eq!(
+        graph.build_with_keys(&keys).unwrap_err(),
+        ValidationError::MissingKey("ab".to_string()),
+    );
+
+    // "b" has a key but no vertex in the graph to build
+    let keys = StringGraph {
+        vertices: btreemap! {
+            "a".to_string() => vertex(&[]),
+            "b".to_string() => vertex(&[]),
+            "ab".to_string() => vertex(&[&["a", "b"]]),
+        },
+    };
+    let keys = keys.build().unwrap().graph;
+    let graph = StringGraph {
+        vertices: btreemap! {
+            "a".to_string() => vertex(&[]),
+            "ab".to_string() => vertex(&[&["a", "b"]]),
+        },
+    };
+    assert_eq!(
+        graph.build_with_keys(&keys).unwrap_err(),
+        ValidationError::UnknownChild {
+            parent: "ab".to_string(),
+            child: "b".to_string(),
+        },
+    );
+}
+
+#[test]
+pub(crate) fn test_merge_conflicts() {
+    let vertex = |label: bool, patterns: &[&[&str]]| StringVertex {
+        patterns: patterns
+            .iter()
+            .map(|p| p.iter().map(ToString::to_string).collect())
+            .collect(),
+        label,
+    };
+    let left = StringGraph {
+        vertices: btreemap! {
+            "a".to_string() => vertex(true, &[]),
+            "b".to_string() => vertex(true, &[]),
+            "c".to_string() => vertex(true, &[]),
+            "ab".to_string() => vertex(true, &[&["a", "b"]]),
+            "abc".to_string() => vertex(true, &[&["ab", "c"]]),
+        },
+    };
+    let right = StringGraph {
+        vertices: btreemap! {
+            "a".to_string() => vertex(true, &[]),
+            "b".to_string() => vertex(true, &[]),
+            "c".to_string() => vertex(true, &[]),
+            "bc".to_string() => vertex(true, &[&["b", "c"]]),
+            "abc".to_string() => vertex(false, &[&["a", "bc"]]),
+        },
+    };
+    let merged = left.merge(&right);
+    assert_eq!(
+        merged.conflicts,
+        vec![
+            MergeConflict::Patterns {
+                string: "abc".to_string(),
+                left: vec![vec!["ab".to_string(), "c".to_string()]],
+                right: vec![vec!["a".to_string(), "bc".to_string()]],
+            },
+            MergeConflict::Label {
+                string: "abc".to_string(),
+                left: true,
+                right: false,
+            },
+        ]
+    );
+    let abc = &merged.merged.vertices["abc"];
+    assert!(abc.label);
+    assert_eq!(abc.patterns.len(), 2);
+    assert_eq!(merged.merged.vertices.len(), 6);
+
+    let built = merged.merged.build().unwrap();
+    assert_eq!(built.graph.vertex_count(), 6);
+    assert_eq!(
+        StringGraph::from_hypergraph(&built.graph, &built.labels),
+        merged.merged
+    );
+}
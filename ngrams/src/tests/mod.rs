@@ -1,4 +1,13 @@
+pub(crate) mod cancellation;
 pub(crate) mod count;
+pub(crate) mod criteria;
+pub(crate) mod diff;
+pub(crate) mod estimate;
+pub(crate) mod io;
+pub(crate) mod merge;
+pub(crate) mod pipeline;
+pub(crate) mod preprocess;
+pub(crate) mod traversal;
 
 use std::path::Path;
 
@@ -6,7 +15,6 @@ use crate::graph::{
     labelling::{
         frequency,
         LabellingCtx,
-        LabellingImage,
     },
     vocabulary::{
         entry::HasVertexEntries,
@@ -45,18 +53,14 @@ fn read_corpus(file_path: impl AsRef<Path>) -> String {
     csv.records().map(|r| r.unwrap()[1].to_string()).join(" ")
 }
 
+/// Roots and leaves expected in the vocabulary of a corpus
 #[derive(Debug)]
 pub(crate) struct TestCorpus {
-    pub(crate) image: LabellingImage,
-    pub(crate) corpus: Corpus,
     pub(crate) roots_test: HashSet<String>,
     pub(crate) leaves_test: HashSet<String>,
 }
 impl TestCorpus {
-    pub(crate) fn new(
-        image: LabellingImage,
-        corpus: Corpus,
-    ) -> Self {
+    pub(crate) fn new(corpus: &Corpus) -> Self {
         let roots_test: HashSet<_> =
             corpus.texts.iter().map(ToString::to_string).collect();
         let leaves_test: HashSet<_> = corpus
@@ -67,8 +71,6 @@ impl TestCorpus {
             })
             .collect();
         Self {
-            image,
-            corpus,
             roots_test,
             leaves_test,
         }
@@ -79,10 +81,11 @@ impl TestCorpus {
     pub(crate) fn get_leaves_test(&self) -> Vec<String> {
         self.leaves_test.iter().cloned().sorted().collect()
     }
-    pub(crate) fn test_containment(&self) {
+    pub(crate) fn test_containment(
+        &self,
+        vocab: &Vocabulary,
+    ) {
         let Self {
-            image: LabellingImage { vocab, .. },
-            corpus,
             leaves_test,
             roots_test,
         } = self;
@@ -170,7 +173,7 @@ pub(crate) struct TestCase {
 impl TestCase {
     pub(crate) fn execute(&mut self) {
         // graph of all containment edges between n and n+1
-        self.corpus.test_containment();
+        TestCorpus::new(&self.corpus).test_containment(self.vocab());
         self.label_freq().unwrap();
 
         if *self.status.pass() == ProcessStatus::Frequency {
@@ -202,9 +205,12 @@ pub(crate) struct LabelTestCtx<'a> {
     test: &'a TestCase,
 }
 impl<'a> LabelTestCtx<'a> {
+    fn expected(&self) -> TestCorpus {
+        TestCorpus::new(&self.test.corpus)
+    }
     pub(crate) fn test_roots(&self) {
         let label_strings = self.label_strings_set();
-        let roots_test = self.test.corpus.get_roots_test();
+        let roots_test = self.expected().get_roots_test();
         assert_eq!(
             label_strings
                 .intersection(&roots_test.iter().cloned().collect())
@@ -216,7 +222,7 @@ impl<'a> LabelTestCtx<'a> {
     }
     pub(crate) fn test_leaves(&self) {
         let label_strings = self.label_strings_set();
-        let leaves_test = self.test.corpus.get_leaves_test();
+        let leaves_test = self.expected().get_leaves_test();
         assert_eq!(
             label_strings
                 .intersection(&leaves_test.iter().cloned().collect())
@@ -227,11 +233,11 @@ impl<'a> LabelTestCtx<'a> {
         );
     }
     pub(crate) fn get_frequency_test(&self) -> Vec<String> {
-        self.test
-            .corpus
+        let expected = self.expected();
+        expected
             .get_leaves_test()
             .iter()
-            .chain(self.test.corpus.get_roots_test().iter())
+            .chain(expected.get_roots_test().iter())
             .chain(self.test.labels.frequency.iter())
             .sorted()
             .cloned()
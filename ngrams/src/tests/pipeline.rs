@@ -0,0 +1,100 @@
+use itertools::Itertools;
+use pretty_assertions::assert_eq;
+
+use crate::{
+    cancellation::Cancellation,
+    graph::{
+        labelling::pipeline::{
+            FrequencyParams,
+            Pipeline,
+            PruneParams,
+        },
+        parse_corpus,
+        parse_corpus_with_options,
+        Corpus,
+        ParseOptions,
+        ParseResult,
+        Status,
+        StatusHandle,
+    },
+};
+
+const TEXTS: [&str; 3] = ["abab", "abcabc", "babc"];
+
+pub(crate) fn label_strings(result: &ParseResult) -> Vec<String> {
+    result
+        .labels
+        .iter()
+        .map(|key| result.containment.vertex_key_string(key))
+        .sorted()
+        .collect()
+}
+
+#[test]
+pub(crate) fn test_pipeline_json() {
+    let pipeline = Pipeline::empty()
+        .frequency(FrequencyParams {
+            min_count: 2,
+            ..Default::default()
+        })
+        .wrappers()
+        .frequency(FrequencyParams::default())
+        .prune(PruneParams {
+            min_count: 2,
+            min_width: 2,
+        })
+        .custom("Bigram");
+    assert_eq!(Pipeline::from_json(&pipeline.to_json()).unwrap(), pipeline);
+    assert_eq!(
+        Pipeline::from_json(r#"{ "stages": [ "Wrappers", { "Prune": {} } ] }"#)
+            .unwrap(),
+        Pipeline::empty().wrappers().prune(PruneParams::default()),
+    );
+}
+
+#[test]
+pub(crate) fn test_pipeline_stages() {
+    let parse = |name: &str, pipeline: Pipeline| {
+        let status = StatusHandle::from(Status::new(TEXTS));
+        let result = parse_corpus_with_options(
+            Corpus::new(name, TEXTS),
+            status.clone(),
+            Cancellation::None,
+            ParseOptions::new(pipeline.clone()),
+        )
+        .unwrap();
+        let status = status.read().unwrap();
+        assert_eq!(*status.stages(), pipeline.stage_names());
+        assert_eq!(*status.stage(), pipeline.stages.len());
+        result
+    };
+    let default = parse_corpus(
+        Corpus::new("test_pipeline_default", TEXTS),
+        StatusHandle::default(),
+        Cancellation::None,
+    )
+    .unwrap();
+    let explicit = parse("test_pipeline_explicit", Pipeline::default());
+    assert_eq!(label_strings(&explicit), label_strings(&default));
+
+    // labels are only added by another frequency pass
+    let twice = parse(
+        "test_pipeline_twice",
+        Pipeline::default().frequency(FrequencyParams::default()),
+    );
+    let twice = label_strings(&twice);
+    assert!(label_strings(&default).iter().all(|l| twice.contains(l)));
+
+    let pruned = parse(
+        "test_pipeline_pruned",
+        Pipeline::default().prune(PruneParams {
+            min_count: usize::MAX,
+            min_width: 1,
+        }),
+    );
+    let pruned = label_strings(&pruned);
+    assert!(pruned.len() < label_strings(&default).len());
+    assert!(pruned
+        .iter()
+        .all(|s| s.len() == 1 || TEXTS.contains(&s.as_str())));
+}
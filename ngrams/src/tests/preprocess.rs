@@ -0,0 +1,71 @@
+use itertools::Itertools;
+use pretty_assertions::assert_eq;
+
+use crate::{
+    cancellation::Cancellation,
+    graph::{
+        preprocess::{
+            CharClass,
+            PreprocessStep,
+            Preprocessor,
+            UnicodeForm,
+        },
+        vocabulary::Vocabulary,
+        Corpus,
+        StatusHandle,
+    },
+};
+
+#[test]
+pub(crate) fn test_preprocess_steps() {
+    let preprocessor = Preprocessor::new()
+        .unicode(UnicodeForm::Nfc)
+        .case_fold()
+        .filter_chars([CharClass::Alphabetic, CharClass::Whitespace])
+        .collapse_whitespace();
+    assert_eq!(
+        preprocessor.apply_text("  Ottos\tMOPS,  trotzt! "),
+        vec!["ottos mops trotzt".to_string()],
+    );
+    // composed and decomposed forms normalise to the same text
+    assert_eq!(
+        preprocessor.apply_text("Cafe\u{0301}"),
+        preprocessor.apply_text("Caf\u{00E9}"),
+    );
+}
+
+#[test]
+pub(crate) fn test_preprocess_split_sentences() {
+    let preprocessor = Preprocessor::new().split_sentences(Some(11));
+    assert_eq!(
+        preprocessor.apply_text("Otto: fort. Mops hopst fort! Ende"),
+        vec!["Otto: fort.", "Mops hopst", "fort!", "Ende"]
+            .into_iter()
+            .map(ToString::to_string)
+            .collect_vec(),
+    );
+}
+
+#[test]
+pub(crate) fn test_preprocess_recorded_in_vocabulary() {
+    let preprocessor = Preprocessor::new().case_fold().collapse_whitespace();
+    let corpus = Corpus::new("test_preprocess", ["AB ab", "  ", "aB"])
+        .preprocess(&preprocessor);
+    assert_eq!(
+        corpus.texts,
+        vec!["ab ab".to_string(), "ab".to_string()],
+    );
+    assert_eq!(
+        corpus.preprocessing(),
+        &[PreprocessStep::CaseFold, PreprocessStep::CollapseWhitespace],
+    );
+    let vocab = Vocabulary::from_corpus(
+        &corpus,
+        &mut StatusHandle::default(),
+        &Cancellation::None,
+    )
+    .unwrap();
+    assert_eq!(vocab.preprocessing, corpus.preprocessing);
+    // case folding merges upper and lower case leaves
+    assert_eq!(vocab.leaves.len(), 3);
+}
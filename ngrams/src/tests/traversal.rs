@@ -0,0 +1,227 @@
+use std::collections::VecDeque;
+
+use itertools::Itertools;
+use pretty_assertions::assert_eq;
+
+use context_trace::{
+    graph::vertex::{
+        has_vertex_key::HasVertexKey,
+        wide::Wide,
+    },
+    HashSet,
+};
+
+use crate::{
+    cancellation::Cancellation,
+    graph::{
+        labelling::{
+            pipeline::Pipeline,
+            CustomPass,
+            LabellingCtx,
+        },
+        parse_corpus_with_options,
+        traversal::{
+            direction::{
+                BottomUp,
+                TopDown,
+                TraversalDirection,
+                TraversalEntry,
+                TraversalGraph,
+            },
+            pass::{
+                PassCtx,
+                RunResult,
+                TraversalPass,
+            },
+            queue::LinearQueue,
+            visited::VisitTracking,
+        },
+        vocabulary::{
+            entry::HasVertexEntries,
+            NGramId,
+        },
+        Corpus,
+        ParseOptions,
+        StatusHandle,
+    },
+};
+
+/// Visits every n-gram bottom up, collecting repeated bigrams
+#[derive(Debug)]
+struct BigramPass<'a> {
+    ctx: &'a LabellingCtx,
+    visited: HashSet<NGramId>,
+    bigrams: HashSet<NGramId>,
+}
+impl VisitTracking for BigramPass<'_> {
+    type Collection = HashSet<NGramId>;
+    fn visited_mut(&mut self) -> &mut Self::Collection {
+        &mut self.visited
+    }
+}
+impl TraversalPass for BigramPass<'_> {
+    type Ctx = LabellingCtx;
+    type Node = NGramId;
+    type NextNode = NGramId;
+    type Queue = LinearQueue<Self>;
+    fn ctx(&self) -> &LabellingCtx {
+        self.ctx
+    }
+    fn start_queue(&mut self) -> RunResult<Self::Queue> {
+        Ok(BottomUp::starting_nodes(self.ctx.vocab()).into_iter().collect())
+    }
+    fn node_condition(
+        &mut self,
+        node: NGramId,
+    ) -> bool {
+        self.visited.insert(node)
+    }
+    fn on_node(
+        &mut self,
+        node: &NGramId,
+    ) -> RunResult<Option<Vec<NGramId>>> {
+        let vertex = self.ctx.vocab().expect_vertex(node);
+        if vertex.width() == 2 && vertex.count() > 1 {
+            self.bigrams.insert(*node);
+        }
+        Ok(Some(BottomUp::next_nodes(&vertex)))
+    }
+}
+
+#[derive(Debug)]
+struct LabelBigrams;
+impl CustomPass for LabelBigrams {
+    fn name(&self) -> &str {
+        "Bigram"
+    }
+    fn run(
+        &self,
+        ctx: &mut LabellingCtx,
+    ) -> RunResult<()> {
+        let mut pass = BigramPass {
+            ctx: &*ctx,
+            visited: Default::default(),
+            bigrams: Default::default(),
+        };
+        pass.run()?;
+        let bigrams = pass.bigrams;
+        ctx.labels_mut()
+            .extend(bigrams.into_iter().map(|id| id.vertex_key()));
+        Ok(())
+    }
+}
+
+#[test]
+pub(crate) fn test_custom_pass() {
+    let texts = ["abab", "abcabc", "babc"];
+    let result = parse_corpus_with_options(
+        Corpus::new("test_custom_pass", texts),
+        StatusHandle::default(),
+        Cancellation::None,
+        ParseOptions::new(Pipeline::empty()).with_pass(LabelBigrams),
+    )
+    .unwrap();
+    let labels = result
+        .labels
+        .iter()
+        .map(|key| result.containment.vertex_key_string(key))
+        .sorted()
+        .collect_vec();
+    assert_eq!(labels, vec!["ab", "ba", "bc"]);
+}
+
+/// Prefixes of a word, layered by their length
+#[derive(Debug, Default)]
+struct Prefixes {
+    word: String,
+    status: StatusHandle,
+}
+/// Prefix of the given length
+struct Prefix<'a> {
+    prefixes: &'a Prefixes,
+    len: usize,
+}
+impl TraversalGraph for Prefixes {
+    type Node = usize;
+    fn leaf_nodes(&self) -> VecDeque<usize> {
+        VecDeque::from([1])
+    }
+    fn root_nodes(&self) -> VecDeque<usize> {
+        VecDeque::from([self.word.len()])
+    }
+}
+impl TraversalEntry for Prefix<'_> {
+    type Node = usize;
+    fn parent_nodes(&self) -> Vec<usize> {
+        (self.len < self.prefixes.word.len())
+            .then_some(self.len + 1)
+            .into_iter()
+            .collect()
+    }
+    fn child_nodes(&self) -> Vec<(usize, usize)> {
+        (self.len > 1).then_some((0, self.len - 1)).into_iter().collect()
+    }
+}
+impl PassCtx for Prefixes {
+    type Graph = Self;
+    fn graph(&self) -> &Self {
+        self
+    }
+    fn status(&self) -> &StatusHandle {
+        &self.status
+    }
+    fn check_cancelled(&self) -> RunResult<()> {
+        Ok(())
+    }
+}
+/// Collects the prefixes bottom up
+#[derive(Debug)]
+struct PrefixPass<'a> {
+    ctx: &'a Prefixes,
+    visited: Vec<String>,
+}
+impl TraversalPass for PrefixPass<'_> {
+    type Ctx = Prefixes;
+    type Node = usize;
+    type NextNode = usize;
+    type Queue = LinearQueue<Self>;
+    fn ctx(&self) -> &Prefixes {
+        self.ctx
+    }
+    fn start_queue(&mut self) -> RunResult<Self::Queue> {
+        Ok(BottomUp::starting_nodes(self.ctx.graph()).into_iter().collect())
+    }
+    fn on_node(
+        &mut self,
+        node: &usize,
+    ) -> RunResult<Option<Vec<usize>>> {
+        self.visited.push(self.ctx.word[..*node].to_string());
+        let prefix = Prefix {
+            prefixes: self.ctx,
+            len: *node,
+        };
+        Ok(Some(BottomUp::next_nodes(&prefix)))
+    }
+}
+
+#[test]
+pub(crate) fn test_pass_without_vocabulary() {
+    let prefixes = Prefixes {
+        word: "abc".to_string(),
+        ..Default::default()
+    };
+    let mut pass = PrefixPass {
+        ctx: &prefixes,
+        visited: Vec::new(),
+    };
+    pass.run().unwrap();
+    assert_eq!(pass.visited, vec!["a", "ab", "abc"]);
+
+    let root = TopDown::starting_nodes(&prefixes);
+    assert_eq!(root, [3]);
+    let prefix = Prefix {
+        prefixes: &prefixes,
+        len: root[0],
+    };
+    assert_eq!(TopDown::next_nodes(&prefix), vec![(0, 2)]);
+}